@@ -0,0 +1,87 @@
+//! Measure the quality of the resampler.
+//!
+//! ```sh
+//! cargo run --example src_quality -- [SOURCE_RATE] [TARGET_RATE]
+//! ```
+//!
+//! For each test tone, prints THD+N (everything that isn't the tone) and the
+//! suppression of the image at `SOURCE_RATE - FREQUENCY`, in dB relative to
+//! the tone.
+
+use fon::chan::{Ch64, Channel};
+use fon::mono::Mono64;
+use fon::{Audio, Frame};
+use std::f64::consts::PI;
+
+// Length of the test tones, in seconds.
+const SECONDS: f64 = 1.0;
+// Frames to ignore at each end of the resampled audio.
+const EDGE: usize = 64;
+
+fn main() {
+    let mut args = std::env::args()
+        .skip(1)
+        .map(|arg| arg.parse::<f64>().expect("Sample rates must be numbers"));
+    let src_rate = args.next().unwrap_or(44_100.0);
+    let dst_rate = args.next().unwrap_or(48_000.0);
+    let nyquist = src_rate.min(dst_rate) / 2.0;
+
+    println!("Resampling {} Hz to {} Hz", src_rate, dst_rate);
+    println!("{:>10} {:>10} {:>10}", "Hz", "THD+N", "Image");
+    for freq in [100.0, 1_000.0, 5_000.0, 10_000.0, 15_000.0, 20_000.0] {
+        if freq >= nyquist {
+            continue;
+        }
+        let len = (src_rate * SECONDS) as usize;
+        let mut tone = Audio::<Mono64>::with_silence(src_rate, len);
+        for (i, frame) in tone.iter_mut().enumerate() {
+            let value = (2.0 * PI * freq * i as f64 / src_rate).sin() * 0.5;
+            *frame = Mono64::new(Ch64::new(value));
+        }
+        let out = Audio::<Mono64>::with_stream(dst_rate, &tone);
+        let samples: Vec<f64> = out
+            .iter()
+            .skip(EDGE)
+            .take(out.len() - 2 * EDGE)
+            .map(|f| f.channels()[0].to_f64())
+            .collect();
+
+        let first = EDGE as f64;
+        let (amplitude, residual) = fit(&samples, freq, dst_rate, first);
+        let signal = amplitude * amplitude / 2.0;
+        let thd_n = 10.0 * (residual / signal).log10();
+        let image = src_rate - freq;
+        let image = if image < dst_rate / 2.0 {
+            let (image, _) = fit(&samples, image, dst_rate, first);
+            format!("{:.1}", 20.0 * (image / amplitude).log10())
+        } else {
+            "-".to_string()
+        };
+        println!("{:>10} {:>10.1} {:>10}", freq, thd_n, image);
+    }
+}
+
+// Least squares fit of a sine wave to the samples, returning the amplitude and
+// the mean power of what's left over.
+fn fit(samples: &[f64], freq: f64, s_rate: f64, first: f64) -> (f64, f64) {
+    let phase = |i: usize| 2.0 * PI * freq * (first + i as f64) / s_rate;
+    let (mut ss, mut sc, mut cc, mut ys, mut yc) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for (i, y) in samples.iter().enumerate() {
+        let (s, c) = phase(i).sin_cos();
+        ss += s * s;
+        sc += s * c;
+        cc += c * c;
+        ys += y * s;
+        yc += y * c;
+    }
+    let det = ss * cc - sc * sc;
+    let a = (ys * cc - yc * sc) / det;
+    let b = (yc * ss - ys * sc) / det;
+    let mut residual = 0.0;
+    for (i, y) in samples.iter().enumerate() {
+        let (s, c) = phase(i).sin_cos();
+        let error = y - (a * s + b * c);
+        residual += error * error;
+    }
+    ((a * a + b * b).sqrt(), residual / samples.len() as f64)
+}
@@ -9,13 +9,19 @@
 // LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
 
 use crate::{
-    chan::{Ch16, Ch32, Ch64, Ch8},
-    math, Frame, Resampler, Sink, Stream,
+    chan::{Ch16, Ch32, Ch64, Ch8, Channel, ClipPolicy},
+    math,
+    mono::Mono,
+    stereo::Stereo,
+    surround::Surround,
+    surround61::Surround61,
+    surround71::Surround71,
+    ChannelOrder, Error, Frame, Resampler, Sink, Stream,
 };
 use alloc::{
     boxed::Box,
     collections::{
-        vec_deque::{Iter, IterMut},
+        vec_deque::{IntoIter, Iter, IterMut},
         VecDeque,
     },
     vec,
@@ -24,9 +30,15 @@ use alloc::{
 use core::{
     fmt::Debug,
     iter::Cloned,
-    mem::{size_of, swap},
-    ops::RangeBounds,
-    slice::{from_raw_parts_mut, SliceIndex},
+    mem::{self, align_of, size_of, swap, ManuallyDrop},
+    ops::{
+        AddAssign, Bound, Index, IndexMut, MulAssign, Range, RangeBounds,
+        RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive,
+        SubAssign,
+    },
+    ptr::slice_from_raw_parts_mut,
+    slice::{Chunks, ChunksMut, SliceIndex},
+    time::Duration,
 };
 
 // Channel Identification
@@ -49,6 +61,22 @@ pub struct Audio<F: Frame> {
     frames: VecDeque<F>,
 }
 
+// Smallest normalized correlation of a window that counts as correlated, for
+// polarity flip detection.
+const POLARITY_CORRELATION: f64 = 0.3;
+
+// Check a sample rate, and that a buffer of `len` samples is a whole number
+// of frames.
+fn check_buffer<F: Frame>(s_rate: f64, len: usize) -> Result<(), Error> {
+    if !(s_rate > 0.0 && s_rate.is_finite()) {
+        Err(Error::BadSampleRate)
+    } else if !len.is_multiple_of(F::CHAN_COUNT) {
+        Err(Error::BadLength)
+    } else {
+        Ok(())
+    }
+}
+
 impl<F: Frame> Audio<F> {
     /// Get an audio frame.
     pub fn get(&self, index: usize) -> Option<F> {
@@ -60,6 +88,23 @@ impl<F: Frame> Audio<F> {
         self.frames.get_mut(index)
     }
 
+    /// Get the audio frame playing at a time from the start of the buffer.
+    pub fn get_at(&self, time: Duration) -> Option<F> {
+        self.get(self.frame_index_of(time))
+    }
+
+    /// Get a mutable reference to the audio frame playing at a time from the
+    /// start of the buffer.
+    pub fn get_mut_at(&mut self, time: Duration) -> Option<&mut F> {
+        self.get_mut(self.frame_index_of(time))
+    }
+
+    /// Get the index of the frame playing at a time from the start of the
+    /// buffer (rounded down).  The index may be out of range.
+    pub fn frame_index_of(&self, time: Duration) -> usize {
+        math::duration_index(self.s_rate, time)
+    }
+
     /// Get a contiguous slice of all audio frames.  This may have to re-arrange
     /// memory if `drain()` was used, and could be slow.  If `drain()` was not
     /// called, this method should run in constant time.
@@ -67,6 +112,44 @@ impl<F: Frame> Audio<F> {
         self.frames.make_contiguous()
     }
 
+    /// Returns an iterator over slices of `block` frames, for block-based
+    /// processing.  The last slice is shorter if the length isn't a multiple
+    /// of `block`.  Like [`as_slice()`](Audio::as_slice), this may have to
+    /// re-arrange memory.
+    ///
+    /// # Panics
+    /// If `block` is 0.
+    pub fn chunks(&mut self, block: usize) -> Chunks<'_, F> {
+        self.frames.make_contiguous().chunks(block)
+    }
+
+    /// Returns an iterator over mutable slices of `block` frames (see
+    /// [`chunks()`](Audio::chunks)).
+    ///
+    /// # Panics
+    /// If `block` is 0.
+    pub fn chunks_mut(&mut self, block: usize) -> ChunksMut<'_, F> {
+        self.frames.make_contiguous().chunks_mut(block)
+    }
+
+    /// Returns an iterator over overlapping windows of `size` frames, each
+    /// starting `hop` frames after the previous one (for spectral analysis).
+    /// Only full windows are returned.  Like [`as_slice()`](Audio::as_slice),
+    /// this may have to re-arrange memory.
+    ///
+    /// # Panics
+    /// If `size` or `hop` is 0.
+    pub fn windows(
+        &mut self,
+        size: usize,
+        hop: usize,
+    ) -> impl Iterator<Item = &[F]> + '_ {
+        assert!(size != 0 && hop != 0, "Window size and hop must not be 0");
+        let frames: &[F] = self.frames.make_contiguous();
+        let count = (frames.len() + hop).saturating_sub(size) / hop;
+        (0..count).map(move |i| &frames[i * hop..i * hop + size])
+    }
+
     /// Returns an iterator over the audio frames.
     pub fn iter(&self) -> Iter<'_, F> {
         self.frames.iter()
@@ -84,11 +167,81 @@ impl<F: Frame> Audio<F> {
         Audio { s_rate, frames }
     }
 
+    /// Construct an empty `Audio` buffer with space for at least `capacity`
+    /// frames, for growing with [`push()`](Audio::push).
+    pub fn with_capacity<R: Into<f64>>(s_rate: R, capacity: usize) -> Self {
+        let s_rate = s_rate.into();
+        let frames = VecDeque::with_capacity(capacity);
+        Audio { s_rate, frames }
+    }
+
+    /// Get the number of frames the `Audio` buffer can hold without
+    /// reallocating.
+    pub fn capacity(&self) -> usize {
+        self.frames.capacity()
+    }
+
+    /// Append a frame to the end of the `Audio` buffer.
+    pub fn push(&mut self, frame: F) {
+        self.frames.push_back(frame);
+    }
+
+    /// Move all of the frames of `other` onto the end of this `Audio` buffer.
+    /// If the sample rates differ, `other` is resampled to match (see
+    /// [`extend()`](Audio::extend)).
+    pub fn append(&mut self, mut other: Audio<F>) {
+        if other.s_rate == self.s_rate {
+            self.frames.append(&mut other.frames);
+        } else {
+            self.extend(&other);
+        }
+    }
+
+    // Convert every frame into another type.
+    fn map_frames<G: Frame + From<F>>(self) -> Audio<G> {
+        let frames: Vec<G> = self.frames.into_iter().map(G::from).collect();
+        Audio::with_frames(self.s_rate, frames)
+    }
+
+    /// Resize the `Audio` buffer to `len` frames, either removing frames from
+    /// the end or filling new frames at the end with `frame`.
+    pub fn resize(&mut self, len: usize, frame: F) {
+        self.frames.resize(len, frame);
+    }
+
+    /// Shorten the `Audio` buffer to `len` frames, removing frames from the
+    /// end.  Has no effect if the buffer is already shorter.
+    pub fn truncate(&mut self, len: usize) {
+        self.frames.truncate(len);
+    }
+
+    /// Split the `Audio` buffer in two at frame `index`, returning the frames
+    /// from `index` onwards as a new `Audio` buffer at the same sample rate.
+    ///
+    /// # Panics
+    /// If `index > len`
+    pub fn split_off(&mut self, index: usize) -> Self {
+        Self {
+            s_rate: self.s_rate,
+            frames: self.frames.split_off(index),
+        }
+    }
+
     /// Construct an `Audio` buffer with all all samples set to zero.
     pub fn with_silence<R: Into<f64>>(s_rate: R, len: usize) -> Self {
         Self::with_frame(s_rate, len, F::default())
     }
 
+    /// Construct an `Audio` buffer of silence lasting `duration`, rounded to
+    /// the nearest frame.
+    pub fn with_silence_duration<R: Into<f64>>(
+        s_rate: R,
+        duration: Duration,
+    ) -> Self {
+        let s_rate = s_rate.into();
+        Self::with_silence(s_rate, math::duration_frames(s_rate, duration))
+    }
+
     /// Construct an [`Audio`](crate::Audio) buffer from the contents of a
     /// [`Stream`](crate::Stream).
     ///
@@ -124,6 +277,29 @@ impl<F: Frame> Audio<F> {
         }
     }
 
+    /// Take the `Audio` buffer apart into its sample rate and frames (for
+    /// FFI, or custom allocators).
+    ///
+    /// ```rust
+    /// use fon::{mono::Mono32, Audio};
+    ///
+    /// let audio = Audio::<Mono32>::with_silence(48_000, 4);
+    /// let (s_rate, frames) = audio.into_raw_parts();
+    /// assert_eq!((s_rate, frames.len()), (48_000.0, 4));
+    /// let audio = Audio::from_raw_parts(s_rate, frames);
+    /// assert_eq!(audio.len(), 4);
+    /// ```
+    pub fn into_raw_parts(self) -> (f64, Box<[F]>) {
+        let s_rate = self.s_rate;
+        (s_rate, self.into())
+    }
+
+    /// Reassemble an `Audio` buffer taken apart with
+    /// [`into_raw_parts()`](Audio::into_raw_parts).
+    pub fn from_raw_parts(s_rate: f64, frames: Box<[F]>) -> Self {
+        Self::with_frames(s_rate, frames)
+    }
+
     /// Construct an `Audio` buffer from an `i8` buffer.
     #[allow(unsafe_code)]
     pub fn with_i8_buffer<B, R>(s_rate: R, buffer: B) -> Self
@@ -139,7 +315,7 @@ impl<F: Frame> Audio<F> {
         let slice = Box::<[i8]>::into_raw(buffer);
         let frames: Box<[F]> = unsafe {
             let ptr = (*slice).as_mut_ptr() as *mut F;
-            Box::from_raw(from_raw_parts_mut(ptr, len))
+            Box::from_raw(slice_from_raw_parts_mut(ptr, len))
         };
         let frames: Vec<F> = frames.into();
         Audio {
@@ -164,7 +340,7 @@ impl<F: Frame> Audio<F> {
         let slice = Box::<[i16]>::into_raw(buffer);
         let frames: Box<[F]> = unsafe {
             let ptr = (*slice).as_mut_ptr() as *mut F;
-            Box::from_raw(from_raw_parts_mut(ptr, len))
+            Box::from_raw(slice_from_raw_parts_mut(ptr, len))
         };
         let frames: Vec<F> = frames.into();
         Audio {
@@ -173,6 +349,31 @@ impl<F: Frame> Audio<F> {
         }
     }
 
+    /// Construct an `Audio` buffer from an unsigned `u16` buffer, with
+    /// silence at 32768 (as delivered by some ADCs).
+    ///
+    /// ```rust
+    /// use fon::{mono::Mono16, Audio};
+    ///
+    /// let audio = Audio::<Mono16>::with_u16_biased_buffer(48_000, [0, 32768]);
+    /// assert_eq!(audio.get(0), Some(Mono16::new(i16::MIN)));
+    /// assert_eq!(audio.get(1), Some(Mono16::new(0)));
+    /// assert_eq!(audio.to_u16_biased(), [0, 32768]);
+    /// ```
+    pub fn with_u16_biased_buffer<B, R>(s_rate: R, buffer: B) -> Self
+    where
+        B: Into<Box<[u16]>>,
+        F: Frame<Chan = Ch16>,
+        R: Into<f64>,
+    {
+        let buffer: Box<[u16]> = buffer.into();
+        let buffer: Vec<i16> = buffer
+            .iter()
+            .map(|sample| (sample ^ 0x8000) as i16)
+            .collect();
+        Self::with_i16_buffer(s_rate, buffer)
+    }
+
     /// Construct an `Audio` buffer from an `f32` buffer.
     #[allow(unsafe_code)]
     pub fn with_f32_buffer<B, R>(s_rate: R, buffer: B) -> Self
@@ -189,7 +390,7 @@ impl<F: Frame> Audio<F> {
         let slice = Box::<[f32]>::into_raw(buffer);
         let frames: Box<[F]> = unsafe {
             let ptr = (*slice).as_mut_ptr() as *mut F;
-            Box::from_raw(from_raw_parts_mut(ptr, len))
+            Box::from_raw(slice_from_raw_parts_mut(ptr, len))
         };
         let frames: Vec<F> = frames.into();
         Audio {
@@ -214,7 +415,7 @@ impl<F: Frame> Audio<F> {
         let slice = Box::<[f64]>::into_raw(buffer);
         let frames: Box<[F]> = unsafe {
             let ptr = (*slice).as_mut_ptr() as *mut F;
-            Box::from_raw(from_raw_parts_mut(ptr, len))
+            Box::from_raw(slice_from_raw_parts_mut(ptr, len))
         };
         let frames: Vec<F> = frames.into();
         Audio {
@@ -223,6 +424,71 @@ impl<F: Frame> Audio<F> {
         }
     }
 
+    /// Construct an `Audio` buffer from an `i8` buffer, returning an error
+    /// instead of panicking on a bad length or sample rate.
+    pub fn try_with_i8_buffer<B, R>(s_rate: R, buffer: B) -> Result<Self, Error>
+    where
+        B: Into<Box<[i8]>>,
+        F: Frame<Chan = Ch8>,
+        R: Into<f64>,
+    {
+        let s_rate = s_rate.into();
+        let buffer: Box<[i8]> = buffer.into();
+        check_buffer::<F>(s_rate, buffer.len())?;
+        Ok(Self::with_i8_buffer(s_rate, buffer))
+    }
+
+    /// Construct an `Audio` buffer from an `i16` buffer, returning an error
+    /// instead of panicking on a bad length or sample rate.
+    pub fn try_with_i16_buffer<B, R>(
+        s_rate: R,
+        buffer: B,
+    ) -> Result<Self, Error>
+    where
+        B: Into<Box<[i16]>>,
+        F: Frame<Chan = Ch16>,
+        R: Into<f64>,
+    {
+        let s_rate = s_rate.into();
+        let buffer: Box<[i16]> = buffer.into();
+        check_buffer::<F>(s_rate, buffer.len())?;
+        Ok(Self::with_i16_buffer(s_rate, buffer))
+    }
+
+    /// Construct an `Audio` buffer from an `f32` buffer, returning an error
+    /// instead of panicking on a bad length or sample rate.
+    pub fn try_with_f32_buffer<B, R>(
+        s_rate: R,
+        buffer: B,
+    ) -> Result<Self, Error>
+    where
+        B: Into<Box<[f32]>>,
+        F: Frame<Chan = Ch32>,
+        R: Into<f64>,
+    {
+        let s_rate = s_rate.into();
+        let buffer: Box<[f32]> = buffer.into();
+        check_buffer::<F>(s_rate, buffer.len())?;
+        Ok(Self::with_f32_buffer(s_rate, buffer))
+    }
+
+    /// Construct an `Audio` buffer from an `f64` buffer, returning an error
+    /// instead of panicking on a bad length or sample rate.
+    pub fn try_with_f64_buffer<B, R>(
+        s_rate: R,
+        buffer: B,
+    ) -> Result<Self, Error>
+    where
+        B: Into<Box<[f64]>>,
+        F: Frame<Chan = Ch64>,
+        R: Into<f64>,
+    {
+        let s_rate = s_rate.into();
+        let buffer: Box<[f64]> = buffer.into();
+        check_buffer::<F>(s_rate, buffer.len())?;
+        Ok(Self::with_f64_buffer(s_rate, buffer))
+    }
+
     /// Get the length of the `Audio` buffer.
     pub fn len(&self) -> usize {
         self.frames.len()
@@ -233,11 +499,193 @@ impl<F: Frame> Audio<F> {
         self.len() == 0
     }
 
+    /// Get the duration of the `Audio` buffer, rounded down to the
+    /// nanosecond (zero if the sample rate isn't positive).
+    pub fn duration(&self) -> Duration {
+        math::frames_duration(self.s_rate, self.len())
+    }
+
     /// Get the sample rate of the `Audio` buffer.
     pub fn sample_rate(&self) -> f64 {
         self.s_rate
     }
 
+    /// Change the level of every frame by `db` decibels (saturating).
+    pub fn gain_db(&mut self, db: f64) {
+        for frame in self.frames.iter_mut() {
+            *frame = frame.gain_db(db);
+        }
+    }
+
+    /// Multiply every channel of every frame by a gain factor (saturating).
+    pub fn gain(&mut self, gain: f32) {
+        *self *= gain;
+    }
+
+    /// Multiply every channel by a gain factor ramping linearly from `start`
+    /// at the first frame to `end` at the last (saturating), avoiding the
+    /// zipper noise of abrupt gain changes.
+    pub fn gain_ramp(&mut self, start: f32, end: f32) {
+        let (start, end) = (f64::from(start), f64::from(end));
+        let steps = self.len().saturating_sub(1).max(1) as f64;
+        for (i, frame) in self.frames.iter_mut().enumerate() {
+            let gain = start + (end - start) * i as f64 / steps;
+            for chan in frame.channels_mut() {
+                let value = chan.to_f64() * gain;
+                *chan = F::Chan::from_f64_clipped(value, ClipPolicy::Saturate);
+            }
+        }
+    }
+
+    /// Invert the polarity of one channel of every frame (see
+    /// [`Frame::invert_channel()`]).
+    ///
+    /// # Panics
+    /// If `channel` is out of range.
+    pub fn invert_phase(&mut self, channel: usize) {
+        assert!(channel < F::CHAN_COUNT, "Channel out of range");
+        for frame in self.frames.iter_mut() {
+            *frame = frame.invert_channel(channel);
+        }
+    }
+
+    /// Detect if one channel of a correlated pair (such as a stereo
+    /// recording) is wired with inverted polarity.
+    ///
+    /// The normalized correlation of the channels is measured over windows of
+    /// 50 milliseconds, and the pair is flagged if the windows where they are
+    /// clearly correlated are negatively correlated on balance.  Uncorrelated
+    /// or silent audio is never flagged.
+    ///
+    /// # Panics
+    /// If either channel is out of range.
+    pub fn detect_polarity_flip(
+        &self,
+        a_channel: usize,
+        b_channel: usize,
+    ) -> bool {
+        assert!(a_channel < F::CHAN_COUNT, "Channel out of range");
+        assert!(b_channel < F::CHAN_COUNT, "Channel out of range");
+        let window = math::ceil_usize(self.s_rate / 20.0).max(1);
+        let mut balance = 0.0;
+        let mut frames = self.frames.iter().peekable();
+        while frames.peek().is_some() {
+            let (mut ab, mut aa, mut bb) = (0.0, 0.0, 0.0);
+            for frame in frames.by_ref().take(window) {
+                let a = frame.channels()[a_channel].to_f64();
+                let b = frame.channels()[b_channel].to_f64();
+                ab += a * b;
+                aa += a * a;
+                bb += b * b;
+            }
+            if aa > 0.0 && bb > 0.0 {
+                let correlation = ab / (aa * bb).sqrt();
+                if correlation.abs() >= POLARITY_CORRELATION {
+                    balance += correlation;
+                }
+            }
+        }
+        balance < 0.0
+    }
+
+    /// Check if every frame is within `threshold` (linear amplitude) of
+    /// silence (see [`Frame::is_silent()`]).  Empty audio is silent.
+    pub fn is_silent(&self, threshold: f64) -> bool {
+        self.frames.iter().all(|frame| frame.is_silent(threshold))
+    }
+
+    /// Rotate the speaker channels of every frame (see
+    /// [`Frame::rotate_channels()`]).
+    pub fn rotate_channels(&mut self, n: isize) {
+        for frame in self.frames.iter_mut() {
+            *frame = frame.rotate_channels(n);
+        }
+    }
+
+    /// Mirror left and right in every frame (see [`Frame::mirror()`]).
+    pub fn mirror(&mut self) {
+        for frame in self.frames.iter_mut() {
+            *frame = frame.mirror();
+        }
+    }
+
+    /// Reorder the channels of every frame (see
+    /// [`Frame::reorder_channels()`]).
+    ///
+    /// ```rust
+    /// use fon::{stereo::Stereo32, Audio};
+    ///
+    /// let mut audio = Audio::<Stereo32>::with_f32_buffer(48_000, [0.25, 0.5]);
+    /// audio.reorder_channels(&[1, 0]);
+    /// assert_eq!(audio.get(0), Some(Stereo32::new(0.5, 0.25)));
+    /// ```
+    ///
+    /// # Panics
+    /// If the length of `order` isn't the channel count, or if an index in
+    /// it is out of bounds.
+    pub fn reorder_channels(&mut self, order: &[usize]) {
+        assert_eq!(order.len(), F::CHAN_COUNT, "Wrong number of channels");
+        assert!(
+            order.iter().all(|&index| index < F::CHAN_COUNT),
+            "Channel out of bounds"
+        );
+        for frame in self.frames.iter_mut() {
+            *frame = frame.reorder_channels(order);
+        }
+    }
+
+    /// Swap two channels in every frame.
+    ///
+    /// # Panics
+    /// If either channel is out of bounds.
+    pub fn swap_channels(&mut self, a: usize, b: usize) {
+        assert!(
+            a < F::CHAN_COUNT && b < F::CHAN_COUNT,
+            "Channel out of bounds"
+        );
+        for frame in self.frames.iter_mut() {
+            *frame = frame.swap_channels(a, b);
+        }
+    }
+
+    /// Convert every frame from one channel order to another (see
+    /// [`Frame::convert_channel_order()`]).
+    ///
+    /// # Panics
+    /// If either order isn't defined for the channel count (see
+    /// [`ChannelOrder::indices()`]).
+    pub fn convert_channel_order(
+        &mut self,
+        from: ChannelOrder,
+        to: ChannelOrder,
+    ) {
+        let order: Vec<usize> = from.reorder(to, F::CHAN_COUNT).collect();
+        self.reorder_channels(&order);
+    }
+
+    /// Mix (sum) another `Audio` buffer into this one, scaled by `gain`
+    /// (saturating).  The other buffer is resampled if the sample rates
+    /// differ, and converted with [`Frame::convert()`].  Only the frames that
+    /// overlap are changed.
+    pub fn mix_from<G: Frame>(&mut self, other: &Audio<G>, gain: f32) {
+        let resampled;
+        let other = if other.s_rate == self.s_rate {
+            other
+        } else {
+            resampled = Audio::<G>::with_stream(self.s_rate, other);
+            &resampled
+        };
+        let gain = f64::from(gain);
+        for (dst, src) in self.frames.iter_mut().zip(other.frames.iter()) {
+            let src: F = src.convert();
+            for (dst, src) in dst.channels_mut().iter_mut().zip(src.channels())
+            {
+                let value = dst.to_f64() + src.to_f64() * gain;
+                *dst = F::Chan::from_f64_clipped(value, ClipPolicy::Saturate);
+            }
+        }
+    }
+
     /// Create an audio sink to overwrite a region of this `Audio` buffer.
     ///
     /// # Panics
@@ -248,7 +696,7 @@ impl<F: Frame> Audio<F> {
     >(
         &'a mut self,
         reg: R,
-    ) -> impl Sink<F> + '_ {
+    ) -> impl Sink<F> + 'a {
         AudioSink {
             s_rate: self.sample_rate(),
             frames: &mut self.as_slice()[reg],
@@ -256,6 +704,41 @@ impl<F: Frame> Audio<F> {
         }
     }
 
+    /// Create an audio sink to mix (sum) into a region of this `Audio` buffer,
+    /// layering the streamed audio on top of the existing audio rather than
+    /// overwriting it.
+    ///
+    /// ```rust
+    /// use fon::{mono::Mono32, Audio, Sink};
+    ///
+    /// let quiet = Mono32::new(0.25);
+    /// let mut audio = Audio::<Mono32>::with_frame(48_000, 4, quiet);
+    /// let other = Audio::<Mono32>::with_frame(48_000, 2, Mono32::new(0.5));
+    /// audio.mix_sink(1..).stream(&other);
+    /// assert_eq!(audio[0], Mono32::new(0.25));
+    /// assert_eq!(audio[1], Mono32::new(0.75));
+    /// assert_eq!(audio[3], Mono32::new(0.25));
+    /// ```
+    ///
+    /// # Panics
+    /// If range is out of bounds
+    pub fn mix_sink<
+        'a,
+        R: 'a + RangeBounds<usize> + SliceIndex<[F], Output = [F]>,
+    >(
+        &'a mut self,
+        reg: R,
+    ) -> impl Sink<F> + 'a {
+        let s_rate = self.sample_rate();
+        let frames = &mut self.as_slice()[reg];
+        MixSink {
+            s_rate,
+            scratch: vec![F::default(); frames.len()],
+            frames,
+            resampler: Resampler::default(),
+        }
+    }
+
     /// Create a draining audio stream from this `Audio` buffer.  When the
     /// stream is dropped, only sinked audio samples will be removed.
     pub fn drain(&mut self) -> impl Stream<F> + '_ {
@@ -308,10 +791,12 @@ impl<F: Frame> Audio<F> {
         let mut audio = Self::with_frames(s_rate, audio);
 
         // Write to new audio.
-        let mut sink = audio.sink(orig_len..);
-        sink.stream(stream);
-        // Flush partial sample
-        sink.flush();
+        {
+            let mut sink = audio.sink(orig_len..);
+            sink.stream(stream);
+            // Flush partial sample
+            sink.flush();
+        }
         // Return audio
         audio
     }
@@ -341,6 +826,14 @@ impl<F: Frame<Chan = Ch16>> Audio<F> {
             v
         }
     }
+
+    /// Copy the samples into an unsigned `u16` buffer, with silence at 32768.
+    pub fn to_u16_biased(&self) -> Vec<u16> {
+        self.iter()
+            .flat_map(|frame| frame.channels().iter())
+            .map(|chan| i16::from(*chan) as u16 ^ 0x8000)
+            .collect()
+    }
 }
 
 impl<S: Frame<Chan = Ch32>> Audio<S> {
@@ -369,6 +862,72 @@ impl<F: Frame<Chan = Ch64>> Audio<F> {
     }
 }
 
+impl<F: Frame> Audio<F> {
+    /// Reshape into a mono buffer of every channel of every frame, in
+    /// interleaved order (the channels of the first frame, then the second,
+    /// and so on), without copying.  The sample rate is kept.
+    #[allow(unsafe_code)]
+    pub fn into_interleaved(self) -> Audio<Mono<F::Chan>> {
+        let s_rate = self.s_rate;
+        let frames = ManuallyDrop::new(Vec::from(self.frames));
+        let (ptr, len, cap) =
+            (frames.as_ptr(), frames.len(), frames.capacity());
+        assert_eq!(size_of::<F>(), F::CHAN_COUNT * size_of::<F::Chan>());
+        assert_eq!(align_of::<F>(), align_of::<F::Chan>());
+        // Frames are laid out as arrays of channels, just like mono frames.
+        let samples = unsafe {
+            Vec::from_raw_parts(
+                ptr as *mut Mono<F::Chan>,
+                len * F::CHAN_COUNT,
+                cap * F::CHAN_COUNT,
+            )
+        };
+        Audio {
+            s_rate,
+            frames: samples.into(),
+        }
+    }
+}
+
+impl<C: Channel> Audio<Mono<C>> {
+    /// Reshape an interleaved buffer (see
+    /// [`into_interleaved()`](Audio::into_interleaved)) back into frames of
+    /// type `G`, without copying (unless the capacity of the buffer isn't a
+    /// multiple of the channel count of `G`, when it's shrunk to fit first).
+    /// The sample rate is kept.
+    ///
+    /// Returns `Err` with the buffer unchanged if the length isn't a multiple
+    /// of the channel count of `G`.
+    #[allow(unsafe_code)]
+    pub fn into_frames<G: Frame<Chan = C>>(self) -> Result<Audio<G>, Self> {
+        if !self.len().is_multiple_of(G::CHAN_COUNT) {
+            return Err(self);
+        }
+        let s_rate = self.s_rate;
+        // The capacity must also be a multiple of the channel count.
+        let mut samples: Vec<Mono<C>> = Vec::from(self.frames);
+        if !samples.capacity().is_multiple_of(G::CHAN_COUNT) {
+            samples = samples.into_boxed_slice().into();
+        }
+        let samples = ManuallyDrop::new(samples);
+        let (ptr, len, cap) =
+            (samples.as_ptr(), samples.len(), samples.capacity());
+        assert_eq!(size_of::<G>(), G::CHAN_COUNT * size_of::<C>());
+        assert_eq!(align_of::<G>(), align_of::<C>());
+        let frames = unsafe {
+            Vec::from_raw_parts(
+                ptr as *mut G,
+                len / G::CHAN_COUNT,
+                cap / G::CHAN_COUNT,
+            )
+        };
+        Ok(Audio {
+            s_rate,
+            frames: frames.into(),
+        })
+    }
+}
+
 impl<'a, F: Frame> IntoIterator for &'a Audio<F> {
     type IntoIter = Cloned<Iter<'a, F>>;
     type Item = F;
@@ -378,6 +937,149 @@ impl<'a, F: Frame> IntoIterator for &'a Audio<F> {
     }
 }
 
+impl<'a, F: Frame> IntoIterator for &'a mut Audio<F> {
+    type IntoIter = IterMut<'a, F>;
+    type Item = &'a mut F;
+
+    fn into_iter(self) -> IterMut<'a, F> {
+        self.frames.iter_mut()
+    }
+}
+
+impl<F: Frame> IntoIterator for Audio<F> {
+    type IntoIter = IntoIter<F>;
+    type Item = F;
+
+    fn into_iter(self) -> IntoIter<F> {
+        self.frames.into_iter()
+    }
+}
+
+impl<F: Frame> Index<usize> for Audio<F> {
+    type Output = F;
+
+    fn index(&self, index: usize) -> &F {
+        &self.frames[index]
+    }
+}
+
+impl<F: Frame> IndexMut<usize> for Audio<F> {
+    fn index_mut(&mut self, index: usize) -> &mut F {
+        &mut self.frames[index]
+    }
+}
+
+macro_rules! index_range {
+    ($($range:ty),*) => {$(
+        /// Index a range of frames.  Through a shared reference, this panics
+        /// if the range wraps around the end of the ring buffer (which can
+        /// happen after [`Audio::drain()`]); call
+        /// [`as_slice()`](Audio::as_slice) first to make it contiguous.
+        impl<F: Frame> Index<$range> for Audio<F> {
+            type Output = [F];
+
+            fn index(&self, range: $range) -> &[F] {
+                let (start, end) = range_bounds(&range, self.len());
+                let (head, tail) = self.frames.as_slices();
+                let split = head.len();
+                if end <= split {
+                    &head[start..end]
+                } else if start >= split {
+                    &tail[start - split..end - split]
+                } else {
+                    panic!("Range isn't contiguous, call as_slice() first")
+                }
+            }
+        }
+
+        impl<F: Frame> IndexMut<$range> for Audio<F> {
+            fn index_mut(&mut self, range: $range) -> &mut [F] {
+                &mut self.frames.make_contiguous()[range]
+            }
+        }
+    )*};
+}
+
+index_range!(
+    Range<usize>,
+    RangeFrom<usize>,
+    RangeFull,
+    RangeInclusive<usize>,
+    RangeTo<usize>,
+    RangeToInclusive<usize>
+);
+
+// Get the start and end of a range of frames.
+//
+// Panics if the range is out of bounds.
+pub(crate) fn range_bounds<R>(range: &R, len: usize) -> (usize, usize)
+where
+    R: RangeBounds<usize>,
+{
+    let start = match range.start_bound() {
+        Bound::Included(start) => *start,
+        Bound::Excluded(start) => start + 1,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(end) => end + 1,
+        Bound::Excluded(end) => *end,
+        Bound::Unbounded => len,
+    };
+    assert!(start <= end && end <= len, "Range out of bounds");
+    (start, end)
+}
+
+/// Append frames from an iterator, without resampling.  Note that
+/// [`Audio::extend()`] takes a [`Stream`](crate::Stream) instead, so this is
+/// called as `Extend::extend(&mut audio, iter)`.
+impl<F: Frame> Extend<F> for Audio<F> {
+    fn extend<I: IntoIterator<Item = F>>(&mut self, iter: I) {
+        self.frames.extend(iter);
+    }
+}
+
+impl<F: Frame> AddAssign<&Audio<F>> for Audio<F> {
+    /// Add (mix) another `Audio` buffer into this one, frame by frame.  Only
+    /// the frames that overlap are changed.
+    fn add_assign(&mut self, other: &Audio<F>) {
+        for (dst, src) in self.frames.iter_mut().zip(other.frames.iter()) {
+            *dst += *src;
+        }
+    }
+}
+
+impl<F: Frame> SubAssign<&Audio<F>> for Audio<F> {
+    /// Subtract another `Audio` buffer from this one, frame by frame.  Only
+    /// the frames that overlap are changed.
+    fn sub_assign(&mut self, other: &Audio<F>) {
+        for (dst, src) in self.frames.iter_mut().zip(other.frames.iter()) {
+            *dst -= *src;
+        }
+    }
+}
+
+impl<F: Frame> MulAssign<F> for Audio<F> {
+    /// Multiply every frame by a frame (per-channel amplification).
+    fn mul_assign(&mut self, other: F) {
+        for frame in self.frames.iter_mut() {
+            *frame *= other;
+        }
+    }
+}
+
+impl<F: Frame> MulAssign<f32> for Audio<F> {
+    /// Multiply every channel of every frame by a gain factor, saturating.
+    fn mul_assign(&mut self, gain: f32) {
+        for frame in self.frames.iter_mut() {
+            for chan in frame.channels_mut() {
+                let value = chan.to_f64() * f64::from(gain);
+                *chan = F::Chan::from_f64_clipped(value, ClipPolicy::Saturate);
+            }
+        }
+    }
+}
+
 impl<F: Frame> Stream<F> for &Audio<F> {
     fn sample_rate(&self) -> Option<f64> {
         Some(self.s_rate)
@@ -409,8 +1111,80 @@ impl<F: Frame> Sink<F> for AudioSink<'_, F> {
     }
 }
 
+// Sink that streams into silent scratch frames, and then adds them to the
+// frames of the `Audio` buffer.
+struct MixSink<'a, F: Frame> {
+    s_rate: f64,
+    frames: &'a mut [F],
+    scratch: Vec<F>,
+    resampler: Resampler<F>,
+}
+
+impl<F: Frame> MixSink<'_, F> {
+    // Sink writing to the scratch frames, with the default `Sink` methods.
+    fn scratch(&mut self) -> AudioSink<'_, F> {
+        AudioSink {
+            s_rate: self.s_rate,
+            frames: &mut self.scratch,
+            resampler: mem::take(&mut self.resampler),
+        }
+    }
+
+    // Add the scratch frames to the audio, and silence them for next time.
+    fn mix(&mut self, resampler: Resampler<F>) {
+        self.resampler = resampler;
+        for (dst, src) in self.frames.iter_mut().zip(self.scratch.iter_mut()) {
+            *dst += *src;
+            *src = F::default();
+        }
+    }
+}
+
+impl<F: Frame> Sink<F> for MixSink<'_, F> {
+    fn sample_rate(&self) -> f64 {
+        self.s_rate
+    }
+
+    fn resampler(&mut self) -> &mut Resampler<F> {
+        &mut self.resampler
+    }
+
+    fn buffer(&mut self) -> &mut [F] {
+        &mut self.scratch
+    }
+
+    fn rewind(&mut self) {
+        let mut scratch = self.scratch();
+        scratch.rewind();
+        let resampler = mem::take(&mut scratch.resampler);
+        self.mix(resampler);
+    }
+
+    fn flush(&mut self) {
+        let mut scratch = self.scratch();
+        scratch.flush();
+        let resampler = mem::take(&mut scratch.resampler);
+        self.mix(resampler);
+    }
+
+    fn sink_block(&mut self, frames: &[F]) {
+        let mut scratch = self.scratch();
+        scratch.sink_block(frames);
+        let resampler = mem::take(&mut scratch.resampler);
+        self.mix(resampler);
+    }
+
+    fn stream<S: Frame, M: Stream<S>>(&mut self, stream: M) {
+        let mut scratch = self.scratch();
+        scratch.stream(stream);
+        let resampler = mem::take(&mut scratch.resampler);
+        self.mix(resampler);
+    }
+}
+
 /// A `Stream` created with `Audio.stream()`
 // FIXME
+#[allow(dead_code)]
 struct AudioStream<
     'a,
     F: Frame,
@@ -465,7 +1239,7 @@ impl<F: Frame> Iterator for AudioDrain<'_, F> {
     }
 }
 
-impl<'a, F: Frame> Stream<F> for AudioDrain<'_, F> {
+impl<F: Frame> Stream<F> for AudioDrain<'_, F> {
     fn sample_rate(&self) -> Option<f64> {
         Some(self.buffer.s_rate)
     }
@@ -475,12 +1249,60 @@ impl<'a, F: Frame> Stream<F> for AudioDrain<'_, F> {
     }
 }
 
-impl<'a, F: Frame> Drop for AudioDrain<'_, F> {
+impl<F: Frame> Drop for AudioDrain<'_, F> {
     fn drop(&mut self) {
         self.buffer.frames.drain(..self.cursor);
     }
 }
 
+impl<C: Channel> From<Audio<Mono<C>>> for Audio<Stereo<C>> {
+    fn from(audio: Audio<Mono<C>>) -> Self {
+        audio.map_frames()
+    }
+}
+
+impl<C: Channel> From<Audio<Mono<C>>> for Audio<Surround<C>> {
+    fn from(audio: Audio<Mono<C>>) -> Self {
+        audio.map_frames()
+    }
+}
+
+impl<C: Channel> From<Audio<Stereo<C>>> for Audio<Mono<C>> {
+    fn from(audio: Audio<Stereo<C>>) -> Self {
+        audio.map_frames()
+    }
+}
+
+impl<C: Channel> From<Audio<Stereo<C>>> for Audio<Surround<C>> {
+    fn from(audio: Audio<Stereo<C>>) -> Self {
+        audio.map_frames()
+    }
+}
+
+impl<C: Channel> From<Audio<Surround<C>>> for Audio<Mono<C>> {
+    fn from(audio: Audio<Surround<C>>) -> Self {
+        audio.map_frames()
+    }
+}
+
+impl<C: Channel> From<Audio<Surround<C>>> for Audio<Stereo<C>> {
+    fn from(audio: Audio<Surround<C>>) -> Self {
+        audio.map_frames()
+    }
+}
+
+impl<C: Channel> From<Audio<Surround61<C>>> for Audio<Surround71<C>> {
+    fn from(audio: Audio<Surround61<C>>) -> Self {
+        audio.map_frames()
+    }
+}
+
+impl<C: Channel> From<Audio<Surround71<C>>> for Audio<Surround61<C>> {
+    fn from(audio: Audio<Surround71<C>>) -> Self {
+        audio.map_frames()
+    }
+}
+
 impl<F: Frame> From<Audio<F>> for Vec<F> {
     /// Get internal sample data as `Vec` of audio frames.
     fn from(audio: Audio<F>) -> Self {
@@ -504,7 +1326,7 @@ impl<F: Frame<Chan = Ch8>> From<Audio<F>> for Box<[i8]> {
         let capacity = frames.len() * size_of::<F>();
         let buffer: Box<[i8]> = unsafe {
             let ptr = frames.as_mut_ptr() as *mut i8;
-            Box::from_raw(from_raw_parts_mut(ptr, capacity))
+            Box::from_raw(slice_from_raw_parts_mut(ptr, capacity))
         };
         buffer
     }
@@ -518,7 +1340,7 @@ impl<F: Frame<Chan = Ch16>> From<Audio<F>> for Box<[i16]> {
         let capacity = frames.len() * size_of::<F>() / 2;
         let buffer: Box<[i16]> = unsafe {
             let ptr = frames.as_mut_ptr() as *mut i16;
-            Box::from_raw(from_raw_parts_mut(ptr, capacity))
+            Box::from_raw(slice_from_raw_parts_mut(ptr, capacity))
         };
         buffer
     }
@@ -532,7 +1354,7 @@ impl<F: Frame<Chan = Ch32>> From<Audio<F>> for Box<[f32]> {
         let capacity = frames.len() * size_of::<F>() / 4;
         let buffer: Box<[f32]> = unsafe {
             let ptr = frames.as_mut_ptr() as *mut f32;
-            Box::from_raw(from_raw_parts_mut(ptr, capacity))
+            Box::from_raw(slice_from_raw_parts_mut(ptr, capacity))
         };
         buffer
     }
@@ -546,8 +1368,307 @@ impl<F: Frame<Chan = Ch64>> From<Audio<F>> for Box<[f64]> {
         let capacity = frames.len() * size_of::<F>() / 8;
         let buffer: Box<[f64]> = unsafe {
             let ptr = frames.as_mut_ptr() as *mut f64;
-            Box::from_raw(from_raw_parts_mut(ptr, capacity))
+            Box::from_raw(slice_from_raw_parts_mut(ptr, capacity))
         };
         buffer
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mono::{Mono16, Mono32, Mono64},
+        stereo::{Stereo16, Stereo32},
+        surround::{Surround16, Surround32},
+    };
+
+    #[test]
+    fn assign_ops() {
+        let mut audio =
+            Audio::<Stereo32>::with_frame(48_000, 4, Stereo32::new(0.5, -0.5));
+        let other =
+            Audio::<Stereo32>::with_frame(48_000, 2, Stereo32::new(0.25, 0.25));
+        audio += &other;
+        assert_eq!(audio.get(0), Some(Stereo32::new(0.75, -0.25)));
+        assert_eq!(audio.get(2), Some(Stereo32::new(0.5, -0.5)));
+        audio -= &other;
+        assert_eq!(audio.get(1), Some(Stereo32::new(0.5, -0.5)));
+        audio *= 0.5;
+        assert_eq!(audio.get(3), Some(Stereo32::new(0.25, -0.25)));
+        audio *= 8.0;
+        assert_eq!(audio.get(3), Some(Stereo32::new(1.0, -1.0)));
+        audio *= Stereo32::new(0.0, 1.0);
+        assert_eq!(audio.get(3), Some(Stereo32::new(0.0, -1.0)));
+
+        let mut audio =
+            Audio::<Stereo16>::with_frame(48_000, 1, Stereo16::new(16384, -8));
+        audio *= 0.5;
+        assert_eq!(audio.get(0), Some(Stereo16::new(8192, -4)));
+        assert_eq!(Stereo16::new(16384, -8) * 0.5, Stereo16::new(8192, -4));
+    }
+
+    #[test]
+    fn push_extend() {
+        let mut audio = Audio::<Stereo32>::with_capacity(48_000, 16);
+        assert!(audio.is_empty());
+        assert!(audio.capacity() >= 16);
+        audio.push(Stereo32::new(0.5, -0.5));
+        Extend::extend(&mut audio, vec![Stereo32::new(0.25, 0.0); 3]);
+        assert_eq!(audio.len(), 4);
+        assert_eq!(audio.get(0), Some(Stereo32::new(0.5, -0.5)));
+        assert_eq!(audio.get(3), Some(Stereo32::new(0.25, 0.0)));
+    }
+
+    #[test]
+    fn append() {
+        let mut audio =
+            Audio::<Stereo32>::with_frame(48_000, 2, Stereo32::new(0.5, 0.5));
+        audio.append(Audio::with_frame(48_000, 3, Stereo32::new(0.0, 1.0)));
+        assert_eq!(audio.len(), 5);
+        assert_eq!(audio.get(4), Some(Stereo32::new(0.0, 1.0)));
+        audio.append(Audio::with_silence(24_000, 3));
+        assert_eq!(audio.len(), 11);
+    }
+
+    #[test]
+    fn chunks() {
+        let mut audio = Audio::<Mono32>::with_silence(48_000, 10);
+        audio.drain().into_iter().take(2).for_each(drop);
+        audio.push(Mono32::new(1.0));
+        for (i, chunk) in audio.chunks_mut(4).enumerate() {
+            chunk[0] = Mono32::new(i as f32);
+        }
+        let lens: Vec<usize> = audio.chunks(4).map(|c| c.len()).collect();
+        assert_eq!(lens, [4, 4, 1]);
+        assert_eq!(audio.get(4), Some(Mono32::new(1.0)));
+        assert_eq!(audio.get(8), Some(Mono32::new(2.0)));
+    }
+
+    #[test]
+    fn windows() {
+        let samples: Vec<f32> = (0..10).map(|i| i as f32 / 10.0).collect();
+        let mut audio = Audio::<Mono32>::with_f32_buffer(48_000, samples);
+        let starts: Vec<Mono32> = audio.windows(4, 3).map(|w| w[0]).collect();
+        assert_eq!(starts, [0.0, 0.3, 0.6].map(Mono32::new));
+        assert!(audio.windows(4, 1).all(|w| w.len() == 4));
+        assert_eq!(audio.windows(4, 1).count(), 7);
+        assert_eq!(audio.windows(11, 1).count(), 0);
+    }
+
+    #[test]
+    fn resize_truncate() {
+        let mut audio = Audio::<Mono32>::with_silence(48_000, 3);
+        audio.resize(8, Mono32::new(1.0));
+        assert_eq!(audio.len(), 8);
+        assert_eq!(audio.get(2), Some(Mono32::new(0.0)));
+        assert_eq!(audio.get(7), Some(Mono32::new(1.0)));
+        audio.truncate(2);
+        audio.truncate(4);
+        assert_eq!(audio.len(), 2);
+    }
+
+    #[test]
+    fn duration() {
+        let duration = Duration::from_micros(10_010);
+        let audio = Audio::<Mono32>::with_silence_duration(48_000, duration);
+        assert_eq!(audio.len(), 480);
+        assert_eq!(audio.duration(), Duration::from_millis(10));
+        let audio = Audio::<Mono32>::with_silence_duration(44_100, duration);
+        assert_eq!(audio.len(), 441);
+        let audio = Audio::<Mono32>::with_silence(44_100, 44_101);
+        assert_eq!(audio.duration(), Duration::new(1, 22_675));
+        let audio = Audio::<Mono32>::with_silence(0, 0);
+        assert_eq!(audio.duration(), Duration::ZERO);
+
+        let mut audio = Audio::<Mono32>::with_silence(48_000, 4800);
+        assert_eq!(audio.frame_index_of(Duration::from_millis(100)), 4800);
+        assert_eq!(audio.frame_index_of(Duration::from_micros(41)), 1);
+        *audio.get_mut_at(Duration::from_millis(50)).unwrap() =
+            Mono32::new(1.0);
+        assert_eq!(audio.get(2400), Some(Mono32::new(1.0)));
+        assert_eq!(
+            audio.get_at(Duration::from_secs_f64(0.05)),
+            audio.get(2400)
+        );
+        assert_eq!(audio.get_at(Duration::from_millis(100)), None);
+    }
+
+    #[test]
+    fn into_iter() {
+        let mut audio = Audio::<Mono32>::with_silence(48_000, 3);
+        for frame in &mut audio {
+            *frame = Mono32::new(0.5);
+        }
+        let mut sum = 0.0;
+        for frame in &audio {
+            sum += frame.channels()[0].to_f64();
+        }
+        assert_eq!(sum, 1.5);
+        let frames: Vec<Mono32> = audio.into_iter().collect();
+        assert_eq!(frames, [Mono32::new(0.5); 3]);
+    }
+
+    #[test]
+    fn index() {
+        let samples: Vec<f32> = (0..10).map(|i| i as f32 / 10.0).collect();
+        let mut audio = Audio::<Mono32>::with_f32_buffer(48_000, samples);
+        assert_eq!(audio[5], Mono32::new(0.5));
+        audio[5] = Mono32::new(1.0);
+        assert_eq!(audio.get(5), Some(Mono32::new(1.0)));
+        assert_eq!(audio[2..4], [Mono32::new(0.2), Mono32::new(0.3)]);
+        assert_eq!(audio[..].len(), 10);
+        assert_eq!(audio[8..=9].len(), 2);
+        for frame in &mut audio[..=1] {
+            *frame = Mono32::new(-1.0);
+        }
+        assert_eq!(audio[..2], [Mono32::new(-1.0); 2]);
+        assert_eq!(audio[9..], [Mono32::new(0.9)]);
+        // Indexing works after the ring buffer wraps around.
+        audio.drain().into_iter().take(3).for_each(drop);
+        Extend::extend(&mut audio, [Mono32::new(-1.0); 3]);
+        assert_eq!(audio[0], Mono32::new(0.3));
+        assert_eq!(audio[7], Mono32::new(-1.0));
+        assert_eq!(audio.as_slice()[7..], [Mono32::new(-1.0); 3]);
+        assert_eq!(audio[7..], [Mono32::new(-1.0); 3]);
+    }
+
+    #[test]
+    fn mix_from() {
+        let mut audio =
+            Audio::<Mono32>::with_frame(48_000, 10, Mono32::new(0.25));
+        let other = Audio::<Stereo16>::with_frame(
+            48_000,
+            5,
+            Stereo16::new(Ch16::new(16_384), Ch16::new(16_384)),
+        );
+        audio.mix_from(&other, 0.5);
+        let expected = 0.25 + 0.5 * Ch16::new(16_384).to_f64();
+        assert!((audio[4].channels()[0].to_f64() - expected).abs() < 1e-6);
+        assert_eq!(audio[5], Mono32::new(0.25));
+        audio.mix_from(
+            &Audio::<Mono64>::with_frame(96_000, 20, Mono64::new(0.25)),
+            2.0,
+        );
+        assert_eq!(audio.len(), 10);
+        assert_eq!(audio[9], Mono32::new(0.75));
+    }
+
+    #[test]
+    fn detect_polarity_flip() {
+        let tone = |i: usize| (i as f32 / 10.0).sin() / 2.0;
+        let mut audio = Audio::<Stereo32>::with_silence(48_000, 9_600);
+        assert!(!audio.detect_polarity_flip(0, 1));
+        for (i, frame) in audio.iter_mut().enumerate() {
+            *frame = Stereo32::new(tone(i), tone(i + 1) / 2.0);
+        }
+        assert!(!audio.detect_polarity_flip(0, 1));
+        audio.invert_phase(1);
+        assert!(audio.detect_polarity_flip(0, 1));
+        assert!(audio.detect_polarity_flip(1, 0));
+        assert!(!audio.detect_polarity_flip(1, 1));
+    }
+
+    #[test]
+    fn layout_from() {
+        let audio =
+            Audio::<Stereo32>::with_frame(48_000, 2, Stereo32::new(0.5, 0.25));
+        let surround: Audio<Surround32> = audio.into();
+        let frame = surround.get(1).unwrap();
+        assert_eq!(frame, Stereo32::new(0.5, 0.25).into());
+        assert_eq!(Stereo32::from(frame), Stereo32::new(0.5, 0.25));
+        assert_eq!(Mono32::from(Stereo32::new(0.5, 0.25)), Mono32::new(0.375));
+    }
+
+    #[test]
+    fn is_silent() {
+        let mut audio = Audio::<Stereo16>::with_silence(48_000, 4);
+        assert!(audio.is_silent(0.0));
+        *audio.get_mut(3).unwrap() = Stereo16::new(0, -1);
+        assert!(!audio.is_silent(0.0));
+        assert!(audio.is_silent(0.001));
+    }
+
+    #[test]
+    fn invert_phase() {
+        let mut audio =
+            Audio::<Stereo16>::with_frame(48_000, 3, Stereo16::new(1000, -5));
+        audio.invert_phase(0);
+        assert_eq!(audio.get(2), Some(Stereo16::new(-1001, -5)));
+    }
+
+    #[test]
+    fn gain_db() {
+        let mut audio =
+            Audio::<Stereo32>::with_frame(48_000, 2, Stereo32::new(0.1, -1.0));
+        audio.gain_db(20.0);
+        let frame = audio.get(1).unwrap();
+        assert!((frame.channels()[0].to_f64() - 1.0).abs() < 1e-6);
+        assert_eq!(frame.channels()[1], Ch32::MIN);
+        audio.gain_db(-40.0);
+        let frame = audio.get(0).unwrap();
+        assert!((frame.channels()[0].to_f64() - 0.01).abs() < 1e-6);
+    }
+
+    #[test]
+    fn gain() {
+        let mut audio =
+            Audio::<Mono32>::with_frame(48_000, 5, Mono32::new(0.5));
+        audio.gain(0.5);
+        assert_eq!(audio[0], Mono32::new(0.25));
+        audio.gain_ramp(0.0, 4.0);
+        let ramp: Vec<f32> = audio
+            .iter()
+            .map(|frame| f32::from(frame.channels()[0]))
+            .collect();
+        assert_eq!(ramp, [0.0, 0.25, 0.5, 0.75, 1.0]);
+        audio.gain(100.0);
+        assert_eq!(audio[4], Mono32::new(1.0));
+    }
+
+    #[test]
+    fn interleaved() {
+        let audio = Audio::<Stereo16>::with_i16_buffer(48_000, [1, 2, 3, 4]);
+        let mono = audio.into_interleaved();
+        assert_eq!(mono.len(), 4);
+        assert_eq!(mono.sample_rate(), 48_000.0);
+        assert_eq!(mono[2], Mono16::new(3));
+        let ptr = mono.frames.as_slices().0.as_ptr();
+        let mono = mono.into_frames::<Stereo16>().unwrap().into_interleaved();
+        assert_eq!(mono.frames.as_slices().0.as_ptr(), ptr);
+        let mut mono = mono.into_frames::<Surround16>().unwrap_err();
+        mono.push(Mono16::new(5));
+        mono.push(Mono16::new(6));
+        let stereo = mono.into_frames::<Stereo16>().unwrap();
+        assert_eq!(stereo.len(), 3);
+        assert_eq!(stereo[1], Stereo16::new(3, 4));
+        assert_eq!(stereo[2], Stereo16::new(5, 6));
+    }
+
+    #[test]
+    fn mix_sink() {
+        let mut audio =
+            Audio::<Mono16>::with_frame(48_000, 4, Mono16::new(100));
+        let other = Audio::<Mono16>::with_frame(48_000, 2, Mono16::new(10));
+        // Layer the audio twice.
+        for _ in 0..2 {
+            let mut sink = audio.mix_sink(1..);
+            sink.stream(&other);
+            sink.flush();
+        }
+        let expected = [100, 120, 120, 100].map(Mono16::new);
+        assert!(audio.iter().eq(expected.iter()));
+    }
+
+    #[test]
+    fn try_with_buffer() {
+        let ok = Audio::<Stereo16>::try_with_i16_buffer(48_000, [1, 2, 3, 4]);
+        assert_eq!(ok.unwrap().len(), 2);
+        let err = Audio::<Stereo16>::try_with_i16_buffer(48_000, [1, 2, 3]);
+        assert_eq!(err.unwrap_err(), Error::BadLength);
+        let err = Audio::<Stereo32>::try_with_f32_buffer(0.0, [0.0; 2]);
+        assert_eq!(err.unwrap_err(), Error::BadSampleRate);
+        let err = Audio::<Mono64>::try_with_f64_buffer(f64::NAN, [0.0]);
+        assert_eq!(err.unwrap_err(), Error::BadSampleRate);
+    }
+}
@@ -0,0 +1,140 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Concatenating builder for `Audio` buffers.
+
+use crate::{Audio, Frame};
+use alloc::{boxed::Box, vec::Vec};
+
+// One segment of the audio being built.
+enum Segment<'a, F: Frame> {
+    Audio(&'a Audio<F>),
+    Silence(usize),
+    Generate(usize, Box<dyn FnMut(usize) -> F + 'a>),
+}
+
+/// Builder for concatenating segments (buffers, silence and generated signals)
+/// into one [`Audio`](crate::Audio) buffer.
+///
+/// The total length is counted as segments are added, so
+/// [`build()`](AudioBuilder::build) makes one allocation of the exact final
+/// size, rather than reallocating and copying for each segment.
+///
+/// ```rust
+/// use fon::{mono::Mono32, Audio, AudioBuilder};
+///
+/// let intro = Audio::<Mono32>::with_frame(48_000, 100, Mono32::new(0.5));
+/// let audio = AudioBuilder::new(48_000)
+///     .audio(&intro)
+///     .silence(50)
+///     .generate(10, |i| Mono32::new(i as f32 / 10.0))
+///     .build();
+/// assert_eq!(audio.len(), 160);
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct AudioBuilder<'a, F: Frame> {
+    s_rate: f64,
+    len: usize,
+    segments: Vec<Segment<'a, F>>,
+}
+
+impl<'a, F: Frame> AudioBuilder<'a, F> {
+    /// Start building an `Audio` buffer at a sample rate.
+    pub fn new<R: Into<f64>>(s_rate: R) -> Self {
+        Self {
+            s_rate: s_rate.into(),
+            len: 0,
+            segments: Vec::new(),
+        }
+    }
+
+    /// Append a copy of an `Audio` buffer.
+    ///
+    /// # Panics
+    /// If the sample rate of the buffer differs from the builder's.
+    pub fn audio(mut self, audio: &'a Audio<F>) -> Self {
+        assert_eq!(audio.sample_rate(), self.s_rate, "Sample rate mismatch");
+        self.len += audio.len();
+        self.segments.push(Segment::Audio(audio));
+        self
+    }
+
+    /// Append `len` frames of silence.
+    pub fn silence(mut self, len: usize) -> Self {
+        self.len += len;
+        self.segments.push(Segment::Silence(len));
+        self
+    }
+
+    /// Append `len` frames generated by calling `f` with the index of each
+    /// frame within the segment.
+    pub fn generate<G>(mut self, len: usize, f: G) -> Self
+    where
+        G: FnMut(usize) -> F + 'a,
+    {
+        self.len += len;
+        self.segments.push(Segment::Generate(len, Box::new(f)));
+        self
+    }
+
+    /// Get the length of the `Audio` buffer that will be built.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if the `Audio` buffer that will be built is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Build the `Audio` buffer.
+    pub fn build(self) -> Audio<F> {
+        let mut audio = Audio::with_capacity(self.s_rate, self.len);
+        for segment in self.segments {
+            match segment {
+                Segment::Audio(src) => Extend::extend(&mut audio, src),
+                Segment::Silence(len) => {
+                    audio.resize(audio.len() + len, F::default())
+                }
+                Segment::Generate(len, f) => {
+                    Extend::extend(&mut audio, (0..len).map(f))
+                }
+            }
+        }
+        audio
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mono::Mono32;
+
+    #[test]
+    fn builder() {
+        let a = Audio::<Mono32>::with_frame(48_000, 3, Mono32::new(0.5));
+        let builder = AudioBuilder::new(48_000)
+            .audio(&a)
+            .silence(2)
+            .generate(2, |i| Mono32::new(i as f32))
+            .audio(&a);
+        assert_eq!(builder.len(), 10);
+        let audio = builder.build();
+        assert_eq!(audio.len(), 10);
+        // No reallocation after the first.
+        let capacity = Audio::<Mono32>::with_capacity(48_000, 10).capacity();
+        assert_eq!(audio.capacity(), capacity);
+        assert_eq!(audio.get(2), Some(Mono32::new(0.5)));
+        assert_eq!(audio.get(4), Some(Mono32::new(0.0)));
+        assert_eq!(audio.get(6), Some(Mono32::new(1.0)));
+        assert_eq!(audio.get(9), Some(Mono32::new(0.5)));
+        assert!(AudioBuilder::<Mono32>::new(48_000).build().is_empty());
+    }
+}
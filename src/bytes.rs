@@ -0,0 +1,249 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Conversion of audio buffers to and from bytes.
+
+use crate::{
+    chan::Ch24,
+    private::{AnyBits, Sealed},
+    Audio, Frame,
+};
+use alloc::{vec, vec::Vec};
+use core::{
+    mem::{size_of, size_of_val},
+    slice,
+};
+
+impl<F: Frame> Audio<F> {
+    /// Construct an `Audio` buffer from interleaved little endian samples, of
+    /// the frame's channel type (for example `i16` for
+    /// [`Ch16`](crate::chan::Ch16) and `f32` for [`Ch32`](crate::chan::Ch32)).
+    ///
+    /// # Panics
+    /// If the number of bytes isn't a multiple of the frame size.
+    pub fn with_le_bytes<R: Into<f64>>(s_rate: R, bytes: &[u8]) -> Self {
+        Self::with_bytes(s_rate, bytes, F::from_le_bytes)
+    }
+
+    /// Construct an `Audio` buffer from interleaved big endian samples (such
+    /// as AIFF data, or network streams), of the frame's channel type.
+    ///
+    /// ```rust
+    /// use fon::{stereo::Stereo16, Audio};
+    ///
+    /// let bytes = [0x01, 0x02, 0xFF, 0xFE];
+    /// let audio = Audio::<Stereo16>::with_be_bytes(48_000, &bytes);
+    /// assert_eq!(audio.get(0), Some(Stereo16::new(0x0102, -2)));
+    /// assert_eq!(audio.to_be_bytes(), bytes);
+    /// ```
+    ///
+    /// # Panics
+    /// If the number of bytes isn't a multiple of the frame size.
+    pub fn with_be_bytes<R: Into<f64>>(s_rate: R, bytes: &[u8]) -> Self {
+        Self::with_bytes(s_rate, bytes, F::from_be_bytes)
+    }
+
+    fn with_bytes<R: Into<f64>>(
+        s_rate: R,
+        bytes: &[u8],
+        decode: fn(&[u8]) -> F,
+    ) -> Self {
+        assert_eq!(0, bytes.len() % size_of::<F>(), "Partial frame");
+        let chunks = bytes.chunks_exact(size_of::<F>());
+        let mut audio = Self::with_capacity(s_rate, chunks.len());
+        Extend::extend(&mut audio, chunks.map(decode));
+        audio
+    }
+
+    /// Encode the audio as interleaved little endian samples, of the frame's
+    /// channel type.
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        self.to_bytes(F::Chan::to_le)
+    }
+
+    /// Encode the audio as interleaved big endian samples, of the frame's
+    /// channel type.
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        self.to_bytes(F::Chan::to_be)
+    }
+
+    fn to_bytes(&self, encode: fn(F::Chan, &mut [u8])) -> Vec<u8> {
+        let mut bytes = vec![0; self.len() * size_of::<F>()];
+        let chans = self.iter().flat_map(|frame| frame.channels().iter());
+        let dsts = bytes.chunks_exact_mut(size_of::<F::Chan>());
+        for (dst, src) in dsts.zip(chans) {
+            encode(*src, dst);
+        }
+        bytes
+    }
+
+    /// Get a view of the audio as native endian bytes (for handing to I/O
+    /// layers).  May require reordering memory.
+    #[allow(unsafe_code)]
+    pub fn as_byte_slice(&mut self) -> &[u8] {
+        let frames: &[F] = self.as_slice();
+        let len = size_of_val(frames);
+        // Channels are plain integers and floats, and every frame of this
+        // crate is made of channels without padding.
+        unsafe { slice::from_raw_parts(frames.as_ptr().cast(), len) }
+    }
+
+    /// Construct an `Audio` buffer from a copy of native endian bytes
+    /// (returning `None` if the number of bytes isn't a multiple of the frame
+    /// size).
+    ///
+    /// ```rust
+    /// use fon::{stereo::Stereo32, Audio};
+    ///
+    /// let mut audio = Audio::<Stereo32>::with_f32_buffer(48_000, [0.5, 1.0]);
+    /// let bytes = audio.as_byte_slice();
+    /// let copy = Audio::<Stereo32>::from_byte_slice(48_000, bytes).unwrap();
+    /// assert_eq!(copy.get(0), Some(Stereo32::new(0.5, 1.0)));
+    /// let partial = Audio::<Stereo32>::from_byte_slice(48_000, &bytes[1..]);
+    /// assert!(partial.is_none());
+    /// ```
+    pub fn from_byte_slice<R: Into<f64>>(
+        s_rate: R,
+        bytes: &[u8],
+    ) -> Option<Self> {
+        if !bytes.len().is_multiple_of(size_of::<F>()) {
+            return None;
+        }
+        Some(if cfg!(target_endian = "little") {
+            Self::with_le_bytes(s_rate, bytes)
+        } else {
+            Self::with_be_bytes(s_rate, bytes)
+        })
+    }
+}
+
+impl<F: Frame> Audio<F>
+where
+    F::Chan: AnyBits,
+{
+    /// Get a mutable view of the audio as native endian bytes.  May require
+    /// reordering memory.
+    ///
+    /// Only available for channels where every bit pattern is a valid sample
+    /// (8- and 16-bit integer, and 32- and 64-bit float); 12-, 20- and 24-bit
+    /// audio can be built from bytes with
+    /// [`from_byte_slice()`](Audio::from_byte_slice) instead.
+    ///
+    /// ```rust,compile_fail
+    /// use fon::{mono::Mono24, Audio};
+    ///
+    /// let mut audio = Audio::<Mono24>::with_silence(48_000, 1);
+    /// audio.as_mut_byte_slice()[0] = 0xFF;
+    /// ```
+    #[allow(unsafe_code)]
+    pub fn as_mut_byte_slice(&mut self) -> &mut [u8] {
+        let frames = self.as_slice();
+        let len = size_of_val(frames);
+        // Channels are plain integers and floats, and every frame of this
+        // crate is made of channels without padding.  Any bytes written are a
+        // valid channel value.
+        unsafe { slice::from_raw_parts_mut(frames.as_mut_ptr().cast(), len) }
+    }
+}
+
+impl<F: Frame<Chan = Ch24>> Audio<F> {
+    /// Construct an `Audio` buffer from packed 3-byte little endian 24-bit
+    /// samples (as stored in WAV files).
+    ///
+    /// ```rust
+    /// use fon::{mono::Mono24, Audio};
+    ///
+    /// let bytes = [0x01, 0x02, 0x03, 0xFF, 0xFF, 0xFF];
+    /// let audio = Audio::<Mono24>::with_i24_packed_buffer(48_000, &bytes);
+    /// assert_eq!(audio.get(0), Some(Mono24::new(0x030201)));
+    /// assert_eq!(audio.get(1), Some(Mono24::new(-1)));
+    /// assert_eq!(audio.to_i24_packed(), bytes);
+    /// ```
+    ///
+    /// # Panics
+    /// If the number of bytes isn't a multiple of the packed frame size.
+    pub fn with_i24_packed_buffer<R: Into<f64>>(
+        s_rate: R,
+        bytes: &[u8],
+    ) -> Self {
+        let frame = 3 * F::CHAN_COUNT;
+        assert_eq!(0, bytes.len() % frame, "Partial frame");
+        let mut audio = Self::with_capacity(s_rate, bytes.len() / frame);
+        let mut chans = vec![Ch24::default(); F::CHAN_COUNT];
+        for packed in bytes.chunks_exact(frame) {
+            for (chan, sample) in chans.iter_mut().zip(packed.chunks_exact(3)) {
+                // Sign extend from the top byte.
+                let value =
+                    i32::from_le_bytes([0, sample[0], sample[1], sample[2]]);
+                *chan = Ch24::new(value >> 8);
+            }
+            audio.push(F::from_channels(&chans));
+        }
+        audio
+    }
+
+    /// Encode the audio as packed 3-byte little endian 24-bit samples (as
+    /// stored in WAV files).
+    pub fn to_i24_packed(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.len() * 3 * F::CHAN_COUNT);
+        for frame in self.iter() {
+            for chan in frame.channels() {
+                bytes.extend_from_slice(&i32::from(*chan).to_le_bytes()[..3]);
+            }
+        }
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mono::Mono32,
+        stereo::{Stereo16, Stereo24},
+        Audio,
+    };
+
+    #[test]
+    fn bytes() {
+        let audio = Audio::<Mono32>::with_f32_buffer(48_000, [0.5, -0.25]);
+        let le = audio.to_le_bytes();
+        assert_eq!(&le[..4], 0.5_f32.to_le_bytes());
+        assert_eq!(&le[4..], (-0.25_f32).to_le_bytes());
+        let back = Audio::<Mono32>::with_le_bytes(48_000, &le);
+        assert!(back.iter().eq(audio.iter()));
+        let be = Audio::<Mono32>::with_be_bytes(48_000, &audio.to_be_bytes());
+        assert!(be.iter().eq(audio.iter()));
+    }
+
+    #[test]
+    fn byte_slice() {
+        let mut audio = Audio::<Stereo16>::with_silence(48_000, 2);
+        audio.as_mut_byte_slice()[2..4].copy_from_slice(&7i16.to_ne_bytes());
+        assert_eq!(audio.get(0), Some(Stereo16::new(0, 7)));
+        assert_eq!(audio.as_byte_slice().len(), 8);
+        let copy =
+            Audio::<Stereo16>::from_byte_slice(48_000, audio.as_byte_slice());
+        assert!(copy.unwrap().iter().eq(audio.iter()));
+
+        let mut audio = Audio::<Stereo24>::with_silence(48_000, 1);
+        audio.as_slice()[0] = Stereo24::new(-1, 8_388_607);
+        let copy =
+            Audio::<Stereo24>::from_byte_slice(48_000, audio.as_byte_slice());
+        assert!(copy.unwrap().iter().eq(audio.iter()));
+    }
+
+    #[test]
+    fn i24_packed() {
+        let bytes = [0xFF, 0xFF, 0x7F, 0x00, 0x00, 0x80];
+        let audio = Audio::<Stereo24>::with_i24_packed_buffer(48_000, &bytes);
+        assert_eq!(audio.get(0), Some(Stereo24::new(8_388_607, -8_388_608)));
+        assert_eq!(audio.to_i24_packed(), bytes);
+    }
+}
@@ -37,11 +37,17 @@ pub trait Channel:
     + Sealed
     + Unpin
     + From<Ch8>
+    + From<Ch12>
     + From<Ch16>
+    + From<Ch20>
+    + From<Ch24>
     + From<Ch32>
     + From<Ch64>
     + Into<Ch8>
+    + Into<Ch12>
     + Into<Ch16>
+    + Into<Ch20>
+    + Into<Ch24>
     + Into<Ch32>
     + Into<Ch64>
     + 'static
@@ -61,10 +67,137 @@ pub trait Channel:
     /// Convert from `f64`
     fn from_f64(from: f64) -> Self;
 
-    /// Linear interpolation
+    /// Linear interpolation, with the interpolation amount `t` stored as a
+    /// channel value (see [`lerp_f32()`](Channel::lerp_f32) for better
+    /// precision on integer channels).
     #[inline(always)]
     fn lerp(self, rhs: Self, t: Self) -> Self {
-        self + t * (rhs - self)
+        lerp(self, rhs, t.to_f64())
+    }
+
+    /// Linear interpolation, where `t` goes from 0 (`self`) to 1 (`rhs`).
+    /// Calculated at 64-bit floating point precision, so that fades on
+    /// integer channels are smooth.
+    #[inline(always)]
+    fn lerp_f32(self, rhs: Self, t: f32) -> Self {
+        lerp(self, rhs, t.into())
+    }
+
+    /// Get the level of this channel value in dBFS (decibels relative to full
+    /// scale).  Silence on a floating point channel is negative infinity.
+    #[inline(always)]
+    fn to_dbfs(self) -> f64 {
+        gain_to_db(self.to_f64().abs())
+    }
+
+    /// Convert from a level in dBFS (decibels relative to full scale) to a
+    /// positive channel value.  Levels above 0 dBFS saturate.
+    #[inline(always)]
+    fn from_dbfs(db: f64) -> Self {
+        Self::from_f64(db_to_gain(db).min(1.0))
+    }
+
+    /// Convert from `f64`, handling values outside of -1 to 1 according to a
+    /// [`ClipPolicy`](ClipPolicy).
+    #[inline(always)]
+    fn from_f64_clipped(from: f64, policy: ClipPolicy) -> Self {
+        Self::from_f64(policy.clip::<Self>(from))
+    }
+
+    /// Check if this channel is within `threshold` (linear amplitude) of
+    /// silence.  Integer channels are measured from [`MID`](Channel::MID)
+    /// (which isn't exactly zero as `f64`), and denormal floats are always
+    /// silent.
+    #[inline(always)]
+    fn is_silent(self, threshold: f64) -> bool {
+        let distance = if Self::STEPS.is_some() {
+            (self.to_f64() - Self::MID.to_f64()).abs()
+        } else {
+            self.to_f64().abs()
+        };
+        distance <= threshold || distance < f64::from(f32::MIN_POSITIVE)
+    }
+}
+
+/// Convert a level change in decibels to a gain factor.
+#[inline(always)]
+pub(crate) fn db_to_gain(db: f64) -> f64 {
+    10.0f64.powf(db / 20.0)
+}
+
+/// Convert a gain factor (or linear level) to a level change in decibels.
+#[inline(always)]
+pub(crate) fn gain_to_db(gain: f64) -> f64 {
+    20.0 * gain.log10()
+}
+
+/// How to handle values outside of the range -1 to 1 when converting to a
+/// [`Channel`](Channel).
+///
+/// Arithmetic between channels always saturates to the range of the channel
+/// type; the policy applies to conversions, including the gain applied by
+/// [`GainSink`](crate::sink::GainSink) and
+/// [`MixerSink`](crate::sink::MixerSink) (see their `clip_policy()`).  NaN is
+/// converted to 1, the same as when constructing a floating point channel.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ClipPolicy {
+    /// Clamp to -1 to 1 for all channel types (default).
+    #[default]
+    Saturate,
+    /// Only clip when converting to an integer channel type, leaving floating
+    /// point channels unclipped for extra headroom.
+    OnConversion,
+    /// Panic on out of range values in debug builds (saturate in release
+    /// builds).
+    DebugPanic,
+}
+
+impl ClipPolicy {
+    /// Apply the clipping policy to a value that's going to be converted to
+    /// channel type `C`.
+    #[inline(always)]
+    pub fn clip<C: Channel>(self, value: f64) -> f64 {
+        match self {
+            ClipPolicy::Saturate => clamp64(value),
+            ClipPolicy::OnConversion if value.is_nan() => 1.0,
+            ClipPolicy::OnConversion if C::STEPS.is_none() => value,
+            ClipPolicy::OnConversion => clamp64(value),
+            ClipPolicy::DebugPanic => {
+                debug_assert!(
+                    (-1.0..=1.0).contains(&value),
+                    "Channel value {} out of range",
+                    value
+                );
+                clamp64(value)
+            }
+        }
+    }
+}
+
+// Linear interpolation at 64-bit floating point precision.
+#[inline(always)]
+fn lerp<C: Channel>(this: C, rhs: C, t: f64) -> C {
+    let this = this.to_f64();
+    C::from_f64(this + t * (rhs.to_f64() - this))
+}
+
+// Clamp to -1 to 1, mapping NaN to 1 (so `Ch32` can be `Eq`).
+#[inline(always)]
+fn clamp32(value: f32) -> f32 {
+    if value.is_nan() {
+        1.0
+    } else {
+        value.clamp(-1.0, 1.0)
+    }
+}
+
+// Clamp to -1 to 1, mapping NaN to 1 (so `Ch64` can be `Eq`).
+#[inline(always)]
+fn clamp64(value: f64) -> f64 {
+    if value.is_nan() {
+        1.0
+    } else {
+        value.clamp(-1.0, 1.0)
     }
 }
 
@@ -78,6 +211,27 @@ pub struct Ch8(i8);
 #[repr(transparent)]
 pub struct Ch16(i16);
 
+/// 12-bit sample [Channel](Channel), stored in an `i16`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd, Ord, Eq)]
+#[repr(transparent)]
+pub struct Ch12(i16);
+
+/// 20-bit sample [Channel](Channel), stored in an `i32`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd, Ord, Eq)]
+#[repr(transparent)]
+pub struct Ch20(i32);
+
+/// 24-bit sample [Channel](Channel), stored in an `i32`.
+///
+/// Storing the sample in an `i32` (rather than packed in 3 bytes) keeps it
+/// aligned for fast math.  Packed 3-byte samples (as stored in WAV files) are
+/// converted with
+/// [`Audio::with_i24_packed_buffer()`](crate::Audio::with_i24_packed_buffer)
+/// and [`Audio::to_i24_packed()`](crate::Audio::to_i24_packed).
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd, Ord, Eq)]
+#[repr(transparent)]
+pub struct Ch24(i32);
+
 /// 32-bit sample [Channel](Channel).
 #[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
 #[repr(transparent)]
@@ -112,7 +266,7 @@ impl Ch32 {
     /// Create a new 32-bit [`Channel`](Channel) value.
     #[inline(always)]
     pub fn new(value: f32) -> Self {
-        Ch32(value.min(1.0).max(-1.0))
+        Ch32(clamp32(value))
     }
 }
 
@@ -120,7 +274,7 @@ impl Ch64 {
     /// Create a new 64-bit [`Channel`](Channel) value.
     #[inline(always)]
     pub fn new(value: f64) -> Self {
-        Ch64(value.min(1.0).max(-1.0))
+        Ch64(clamp64(value))
     }
 }
 
@@ -155,7 +309,7 @@ impl From<Ch16> for i16 {
 impl From<f32> for Ch32 {
     #[inline(always)]
     fn from(value: f32) -> Self {
-        Self(value.min(1.0).max(-1.0))
+        Self(clamp32(value))
     }
 }
 
@@ -166,6 +320,13 @@ impl From<Ch32> for f32 {
     }
 }
 
+impl From<f64> for Ch64 {
+    #[inline(always)]
+    fn from(value: f64) -> Self {
+        Self(clamp64(value))
+    }
+}
+
 impl From<Ch64> for f64 {
     #[inline(always)]
     fn from(c: Ch64) -> f64 {
@@ -199,7 +360,7 @@ impl<R: Into<Self>> Sub<R> for Ch32 {
 
     #[inline(always)]
     fn sub(self, rhs: R) -> Self {
-        Self((self.0 - rhs.into().0).min(1.0).max(-1.0))
+        Self(clamp32(self.0 - rhs.into().0))
     }
 }
 
@@ -209,7 +370,7 @@ impl<R: Into<Self>> Sub<R> for Ch64 {
 
     #[inline(always)]
     fn sub(self, rhs: R) -> Self {
-        Self((self.0 - rhs.into().0).min(1.0).max(-1.0))
+        Self(clamp64(self.0 - rhs.into().0))
     }
 }
 
@@ -240,7 +401,7 @@ impl<R: Into<Self>> Add<R> for Ch32 {
     #[inline(always)]
     fn add(self, rhs: R) -> Self {
         let value = self.0 + rhs.into().0;
-        Self(value.min(1.0).max(-1.0))
+        Self(clamp32(value))
     }
 }
 
@@ -251,7 +412,7 @@ impl<R: Into<Self>> Add<R> for Ch64 {
     #[inline(always)]
     fn add(self, rhs: R) -> Self {
         let value = self.0 + rhs.into().0;
-        Self(value.min(1.0).max(-1.0))
+        Self(clamp64(value))
     }
 }
 
@@ -265,7 +426,7 @@ impl<R: Into<Self>> Div<R> for Ch8 {
         if rhs != 0 {
             let ss = i32::from(self.0) << 8;
             let rr = i32::from(rhs);
-            let value = (ss / rr).min(127).max(-128) as i8;
+            let value = (ss / rr).clamp(-128, 127) as i8;
             Self(value)
         } else {
             Self::MAX
@@ -283,7 +444,7 @@ impl<R: Into<Self>> Div<R> for Ch16 {
         if rhs != 0 {
             let ss = i32::from(self.0) << 16;
             let rr = i32::from(rhs);
-            let value = (ss / rr).min(i16::MAX.into()).max(i16::MIN.into());
+            let value = (ss / rr).clamp(i16::MIN.into(), i16::MAX.into());
             Self(value as i16)
         } else {
             Self::MAX
@@ -297,7 +458,7 @@ impl<R: Into<Self>> Div<R> for Ch32 {
 
     #[inline(always)]
     fn div(self, rhs: R) -> Self {
-        Self((self.0 / rhs.into().0).min(1.0).max(-1.0))
+        Self(clamp32(self.0 / rhs.into().0))
     }
 }
 
@@ -307,7 +468,7 @@ impl<R: Into<Self>> Div<R> for Ch64 {
 
     #[inline(always)]
     fn div(self, rhs: R) -> Self {
-        Self((self.0 / rhs.into().0).min(1.0).max(-1.0))
+        Self(clamp64(self.0 / rhs.into().0))
     }
 }
 
@@ -695,62 +856,934 @@ impl Neg for Ch64 {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl Ch12 {
+    /// Create a new 12-bit [`Channel`](Channel) value (clamped to -2048 thru
+    /// 2047).
+    #[inline(always)]
+    pub fn new(value: i16) -> Self {
+        Ch12(value.clamp(-2048, 2047))
+    }
+}
 
-    #[test]
-    fn channel_neg() {
-        assert_eq!(Ch8::new(-128), -Ch8::new(127));
-        assert_eq!(Ch16::new(-32768), -Ch16::new(32767));
-        assert_eq!(Ch32::new(-1.0), -Ch32::new(1.0));
-        assert_eq!(Ch64::new(-1.0), -Ch64::new(1.0));
+impl Ch20 {
+    /// Create a new 20-bit [`Channel`](Channel) value (clamped to -524288 thru
+    /// 524287).
+    #[inline(always)]
+    pub fn new(value: i32) -> Self {
+        Ch20(value.clamp(-524_288, 524_287))
+    }
+}
 
-        assert_eq!(Ch8::new(127), -Ch8::new(-128));
-        assert_eq!(Ch16::new(32767), -Ch16::new(-32768));
-        assert_eq!(Ch32::new(1.0), -Ch32::new(-1.0));
-        assert_eq!(Ch64::new(1.0), -Ch64::new(-1.0));
+impl Ch24 {
+    /// Create a new 24-bit [`Channel`](Channel) value (clamped to -8388608
+    /// thru 8388607).
+    #[inline(always)]
+    pub fn new(value: i32) -> Self {
+        Ch24(value.clamp(-8_388_608, 8_388_607))
+    }
 
-        assert_eq!(Ch8::new(-1), -Ch8::new(0));
-        assert_eq!(Ch8::new(0), -Ch8::new(-1));
-        assert_eq!(Ch16::new(-1), -Ch16::new(0));
-        assert_eq!(Ch16::new(0), -Ch16::new(-1));
-        assert_eq!(Ch32::new(0.0), -Ch32::new(0.0));
-        assert_eq!(Ch64::new(0.0), -Ch64::new(0.0));
+    /// Create a new 24-bit [`Channel`](Channel) value from an `i32` sample,
+    /// clamping values out of the 24-bit range (same as
+    /// [`new()`](Ch24::new)).
+    #[inline(always)]
+    pub fn from_i32_clamped(value: i32) -> Self {
+        Self::new(value)
     }
+}
 
-    #[test]
-    fn ch8_roundtrip() {
-        assert_eq!(-1.0, Ch8::new(-128).to_f64());
-        assert_eq!(1.0, Ch8::new(127).to_f64());
+impl From<i16> for Ch12 {
+    #[inline(always)]
+    fn from(value: i16) -> Self {
+        Ch12::new(value)
+    }
+}
 
-        assert_eq!(Ch8::new(-128), Ch8::from_f64(Ch8::new(-128).to_f64()));
-        assert_eq!(Ch8::new(0), Ch8::from_f64(Ch8::new(0).to_f64()));
-        assert_eq!(Ch8::new(127), Ch8::from_f64(Ch8::new(127).to_f64()));
+impl From<Ch12> for i16 {
+    #[inline(always)]
+    fn from(c: Ch12) -> i16 {
+        c.0
     }
+}
 
-    #[test]
-    fn ch16_roundtrip() {
-        assert_eq!(-1.0, Ch16::new(-32768).to_f64());
-        assert_eq!(1.0, Ch16::new(32767).to_f64());
+impl From<i32> for Ch20 {
+    #[inline(always)]
+    fn from(value: i32) -> Self {
+        Ch20::new(value)
+    }
+}
 
-        assert_eq!(
-            Ch16::new(-32768),
-            Ch16::from_f64(Ch16::new(-32768).to_f64())
-        );
-        assert_eq!(Ch16::new(0), Ch16::from_f64(Ch16::new(0).to_f64()));
-        assert_eq!(Ch16::new(32767), Ch16::from_f64(Ch16::new(32767).to_f64()));
+impl From<Ch20> for i32 {
+    #[inline(always)]
+    fn from(c: Ch20) -> i32 {
+        c.0
     }
+}
 
-    #[test]
-    fn ch32_roundtrip() {
-        assert_eq!(-1.0, Ch32::new(-1.0).to_f64());
-        assert_eq!(0.0, Ch32::new(0.0).to_f64());
-        assert_eq!(1.0, Ch32::new(1.0).to_f64());
+// test: ch12_arith()
+impl<R: Into<Self>> Sub<R> for Ch12 {
+    type Output = Self;
 
-        assert_eq!(Ch32::new(-1.0), Ch32::from_f64(Ch32::new(-1.0).to_f64()));
-        assert_eq!(Ch32::new(0.0), Ch32::from_f64(Ch32::new(0.0).to_f64()));
-        assert_eq!(Ch32::new(1.0), Ch32::from_f64(Ch32::new(1.0).to_f64()));
+    #[inline(always)]
+    fn sub(self, rhs: R) -> Self {
+        Self::new(self.0 - rhs.into().0)
+    }
+}
+
+// test: ch20_arith()
+impl<R: Into<Self>> Sub<R> for Ch20 {
+    type Output = Self;
+
+    #[inline(always)]
+    fn sub(self, rhs: R) -> Self {
+        Self::new(self.0 - rhs.into().0)
+    }
+}
+
+// test: ch12_arith()
+impl<R: Into<Self>> Add<R> for Ch12 {
+    type Output = Self;
+
+    #[inline(always)]
+    fn add(self, rhs: R) -> Self {
+        Self::new(self.0 + rhs.into().0)
+    }
+}
+
+// test: ch20_arith()
+impl<R: Into<Self>> Add<R> for Ch20 {
+    type Output = Self;
+
+    #[inline(always)]
+    fn add(self, rhs: R) -> Self {
+        Self::new(self.0 + rhs.into().0)
+    }
+}
+
+// test: ch12_arith()
+impl<R: Into<Self>> Div<R> for Ch12 {
+    type Output = Self;
+
+    #[inline(always)]
+    fn div(self, rhs: R) -> Self {
+        let rhs = rhs.into().0;
+        if rhs != 0 {
+            let ss = i32::from(self.0) << 11;
+            let rr = i32::from(rhs);
+            Self::new((ss / rr).clamp(-2048, 2047) as i16)
+        } else {
+            Self::MAX
+        }
+    }
+}
+
+// test: ch20_arith()
+impl<R: Into<Self>> Div<R> for Ch20 {
+    type Output = Self;
+
+    #[inline(always)]
+    fn div(self, rhs: R) -> Self {
+        let rhs = rhs.into().0;
+        if rhs != 0 {
+            let ss = i64::from(self.0) << 19;
+            let rr = i64::from(rhs);
+            Self::new((ss / rr).clamp(-524_288, 524_287) as i32)
+        } else {
+            Self::MAX
+        }
+    }
+}
+
+// test: ch12_arith()
+impl<R: Into<Self>> Mul<R> for Ch12 {
+    type Output = Self;
+
+    #[inline(always)]
+    fn mul(self, rhs: R) -> Self {
+        let l = i32::from(self.0);
+        let r = i32::from(rhs.into().0);
+        let v = (l * r) / 2047;
+        Self::new(v.clamp(-2048, 2047) as i16)
+    }
+}
+
+// test: ch20_arith()
+impl<R: Into<Self>> Mul<R> for Ch20 {
+    type Output = Self;
+
+    #[inline(always)]
+    fn mul(self, rhs: R) -> Self {
+        let l = i64::from(self.0);
+        let r = i64::from(rhs.into().0);
+        let v = (l * r) / 524_287;
+        Self::new(v.clamp(-524_288, 524_287) as i32)
+    }
+}
+
+// test: See Add
+impl<R: Into<Self>> AddAssign<R> for Ch12 {
+    #[inline(always)]
+    fn add_assign(&mut self, rhs: R) {
+        *self = *self + rhs.into();
+    }
+}
+
+// test: See Add
+impl<R: Into<Self>> AddAssign<R> for Ch20 {
+    #[inline(always)]
+    fn add_assign(&mut self, rhs: R) {
+        *self = *self + rhs.into();
+    }
+}
+
+// test: See Sub
+impl<R: Into<Self>> SubAssign<R> for Ch12 {
+    #[inline(always)]
+    fn sub_assign(&mut self, rhs: R) {
+        *self = *self - rhs.into();
+    }
+}
+
+// test: See Sub
+impl<R: Into<Self>> SubAssign<R> for Ch20 {
+    #[inline(always)]
+    fn sub_assign(&mut self, rhs: R) {
+        *self = *self - rhs.into();
+    }
+}
+
+// test: See Mul
+impl<R: Into<Self>> MulAssign<R> for Ch12 {
+    #[inline(always)]
+    fn mul_assign(&mut self, rhs: R) {
+        *self = *self * rhs.into();
+    }
+}
+
+// test: See Mul
+impl<R: Into<Self>> MulAssign<R> for Ch20 {
+    #[inline(always)]
+    fn mul_assign(&mut self, rhs: R) {
+        *self = *self * rhs.into();
+    }
+}
+
+// test: See Div
+impl<R: Into<Self>> DivAssign<R> for Ch12 {
+    #[inline(always)]
+    fn div_assign(&mut self, rhs: R) {
+        *self = *self / rhs.into();
+    }
+}
+
+// test: See Div
+impl<R: Into<Self>> DivAssign<R> for Ch20 {
+    #[inline(always)]
+    fn div_assign(&mut self, rhs: R) {
+        *self = *self / rhs.into();
+    }
+}
+
+// test: ch12_roundtrip()
+impl Channel for Ch12 {
+    const MIN: Ch12 = Ch12(-2048);
+    const MID: Ch12 = Ch12(0);
+    const MAX: Ch12 = Ch12(2047);
+
+    #[inline(always)]
+    fn to_f64(self) -> f64 {
+        Ch64::from(self).0
+    }
+
+    #[inline(always)]
+    fn from_f64(from: f64) -> Self {
+        Self::from(Ch64::new(from))
+    }
+}
+
+// test: ch20_roundtrip()
+impl Channel for Ch20 {
+    const MIN: Ch20 = Ch20(-524_288);
+    const MID: Ch20 = Ch20(0);
+    const MAX: Ch20 = Ch20(524_287);
+
+    #[inline(always)]
+    fn to_f64(self) -> f64 {
+        Ch64::from(self).0
+    }
+
+    #[inline(always)]
+    fn from_f64(from: f64) -> Self {
+        Self::from(Ch64::new(from))
+    }
+}
+
+// test: ch12_roundtrip()
+impl From<Ch64> for Ch12 {
+    #[inline(always)]
+    fn from(value: Ch64) -> Self {
+        Ch12::new(math::floor_i16(value.0 * 2047.5))
+    }
+}
+
+// test: ch20_roundtrip()
+impl From<Ch64> for Ch20 {
+    #[inline(always)]
+    fn from(value: Ch64) -> Self {
+        Ch20::new(math::floor_i32(value.0 * 524_287.5))
+    }
+}
+
+// test: ch12_roundtrip()
+impl From<Ch12> for Ch64 {
+    #[inline(always)]
+    fn from(c: Ch12) -> Self {
+        Self((f64::from(c.0) + 0.5) / 2047.5)
+    }
+}
+
+// test: ch20_roundtrip()
+impl From<Ch20> for Ch64 {
+    #[inline(always)]
+    fn from(c: Ch20) -> Self {
+        Self((f64::from(c.0) + 0.5) / 524_287.5)
+    }
+}
+
+// test: ch12_ch20_conversions()
+impl From<Ch8> for Ch12 {
+    #[inline(always)]
+    fn from(c: Ch8) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+// test: ch12_ch20_conversions()
+impl From<Ch12> for Ch8 {
+    #[inline(always)]
+    fn from(c: Ch12) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+// test: ch12_ch20_conversions()
+impl From<Ch16> for Ch12 {
+    #[inline(always)]
+    fn from(c: Ch16) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+// test: ch12_ch20_conversions()
+impl From<Ch12> for Ch16 {
+    #[inline(always)]
+    fn from(c: Ch12) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+// test: ch12_ch20_conversions()
+impl From<Ch32> for Ch12 {
+    #[inline(always)]
+    fn from(c: Ch32) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+// test: ch12_ch20_conversions()
+impl From<Ch12> for Ch32 {
+    #[inline(always)]
+    fn from(c: Ch12) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+// test: ch12_ch20_conversions()
+impl From<Ch20> for Ch12 {
+    #[inline(always)]
+    fn from(c: Ch20) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+// test: ch12_ch20_conversions()
+impl From<Ch8> for Ch20 {
+    #[inline(always)]
+    fn from(c: Ch8) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+// test: ch12_ch20_conversions()
+impl From<Ch20> for Ch8 {
+    #[inline(always)]
+    fn from(c: Ch20) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+// test: ch12_ch20_conversions()
+impl From<Ch16> for Ch20 {
+    #[inline(always)]
+    fn from(c: Ch16) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+// test: ch12_ch20_conversions()
+impl From<Ch20> for Ch16 {
+    #[inline(always)]
+    fn from(c: Ch20) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+// test: ch12_ch20_conversions()
+impl From<Ch32> for Ch20 {
+    #[inline(always)]
+    fn from(c: Ch32) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+// test: ch12_ch20_conversions()
+impl From<Ch20> for Ch32 {
+    #[inline(always)]
+    fn from(c: Ch20) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+// test: ch12_ch20_conversions()
+impl From<Ch12> for Ch20 {
+    #[inline(always)]
+    fn from(c: Ch12) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+// test: channel_neg()
+impl Neg for Ch12 {
+    type Output = Ch12;
+
+    /// Invert sound wave (-x).
+    #[inline(always)]
+    fn neg(self) -> Self {
+        Ch12(!self.0)
+    }
+}
+
+// test: channel_neg()
+impl Neg for Ch20 {
+    type Output = Ch20;
+
+    /// Invert sound wave (-x).
+    #[inline(always)]
+    fn neg(self) -> Self {
+        Ch20(!self.0)
+    }
+}
+
+impl From<i32> for Ch24 {
+    #[inline(always)]
+    fn from(value: i32) -> Self {
+        Ch24::new(value)
+    }
+}
+
+impl From<Ch24> for i32 {
+    #[inline(always)]
+    fn from(c: Ch24) -> i32 {
+        c.0
+    }
+}
+
+// test: ch24_arith()
+impl<R: Into<Self>> Sub<R> for Ch24 {
+    type Output = Self;
+
+    #[inline(always)]
+    fn sub(self, rhs: R) -> Self {
+        Self::new(self.0 - rhs.into().0)
+    }
+}
+
+// test: ch24_arith()
+impl<R: Into<Self>> Add<R> for Ch24 {
+    type Output = Self;
+
+    #[inline(always)]
+    fn add(self, rhs: R) -> Self {
+        Self::new(self.0 + rhs.into().0)
+    }
+}
+
+// test: ch24_arith()
+impl<R: Into<Self>> Div<R> for Ch24 {
+    type Output = Self;
+
+    #[inline(always)]
+    fn div(self, rhs: R) -> Self {
+        let rhs = rhs.into().0;
+        if rhs != 0 {
+            let ss = i64::from(self.0) << 23;
+            let rr = i64::from(rhs);
+            Self::new((ss / rr).clamp(-8_388_608, 8_388_607) as i32)
+        } else {
+            Self::MAX
+        }
+    }
+}
+
+// test: ch24_arith()
+impl<R: Into<Self>> Mul<R> for Ch24 {
+    type Output = Self;
+
+    #[inline(always)]
+    fn mul(self, rhs: R) -> Self {
+        let l = i64::from(self.0);
+        let r = i64::from(rhs.into().0);
+        let v = (l * r) / 8_388_607;
+        Self::new(v.clamp(-8_388_608, 8_388_607) as i32)
+    }
+}
+
+// test: See Add
+impl<R: Into<Self>> AddAssign<R> for Ch24 {
+    #[inline(always)]
+    fn add_assign(&mut self, rhs: R) {
+        *self = *self + rhs.into();
+    }
+}
+
+// test: See Sub
+impl<R: Into<Self>> SubAssign<R> for Ch24 {
+    #[inline(always)]
+    fn sub_assign(&mut self, rhs: R) {
+        *self = *self - rhs.into();
+    }
+}
+
+// test: See Mul
+impl<R: Into<Self>> MulAssign<R> for Ch24 {
+    #[inline(always)]
+    fn mul_assign(&mut self, rhs: R) {
+        *self = *self * rhs.into();
+    }
+}
+
+// test: See Div
+impl<R: Into<Self>> DivAssign<R> for Ch24 {
+    #[inline(always)]
+    fn div_assign(&mut self, rhs: R) {
+        *self = *self / rhs.into();
+    }
+}
+
+// test: ch24_roundtrip()
+impl Channel for Ch24 {
+    const MIN: Ch24 = Ch24(-8_388_608);
+    const MID: Ch24 = Ch24(0);
+    const MAX: Ch24 = Ch24(8_388_607);
+
+    #[inline(always)]
+    fn to_f64(self) -> f64 {
+        Ch64::from(self).0
+    }
+
+    #[inline(always)]
+    fn from_f64(from: f64) -> Self {
+        Self::from(Ch64::new(from))
+    }
+}
+
+// test: ch24_roundtrip()
+impl From<Ch64> for Ch24 {
+    #[inline(always)]
+    fn from(value: Ch64) -> Self {
+        Ch24::new(math::floor_i32(value.0 * 8_388_607.5))
+    }
+}
+
+// test: ch24_roundtrip()
+impl From<Ch24> for Ch64 {
+    #[inline(always)]
+    fn from(c: Ch24) -> Self {
+        Self((f64::from(c.0) + 0.5) / 8_388_607.5)
+    }
+}
+
+// test: channel_neg()
+impl Neg for Ch24 {
+    type Output = Ch24;
+
+    /// Invert sound wave (-x).
+    #[inline(always)]
+    fn neg(self) -> Self {
+        Ch24(!self.0)
+    }
+}
+
+// test: ch24_conversions()
+impl From<Ch8> for Ch24 {
+    #[inline(always)]
+    fn from(c: Ch8) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+// test: ch24_conversions()
+impl From<Ch24> for Ch8 {
+    #[inline(always)]
+    fn from(c: Ch24) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+// test: ch24_conversions()
+impl From<Ch12> for Ch24 {
+    #[inline(always)]
+    fn from(c: Ch12) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+// test: ch24_conversions()
+impl From<Ch24> for Ch12 {
+    #[inline(always)]
+    fn from(c: Ch24) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+// test: ch24_conversions()
+impl From<Ch16> for Ch24 {
+    #[inline(always)]
+    fn from(c: Ch16) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+// test: ch24_conversions()
+impl From<Ch24> for Ch16 {
+    #[inline(always)]
+    fn from(c: Ch24) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+// test: ch24_conversions()
+impl From<Ch20> for Ch24 {
+    #[inline(always)]
+    fn from(c: Ch20) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+// test: ch24_conversions()
+impl From<Ch24> for Ch20 {
+    #[inline(always)]
+    fn from(c: Ch24) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+// test: ch24_conversions()
+impl From<Ch32> for Ch24 {
+    #[inline(always)]
+    fn from(c: Ch32) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+// test: ch24_conversions()
+impl From<Ch24> for Ch32 {
+    #[inline(always)]
+    fn from(c: Ch24) -> Self {
+        Self::from(Ch64::from(c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_neg() {
+        assert_eq!(Ch8::new(-128), -Ch8::new(127));
+        assert_eq!(Ch16::new(-32768), -Ch16::new(32767));
+        assert_eq!(Ch32::new(-1.0), -Ch32::new(1.0));
+        assert_eq!(Ch64::new(-1.0), -Ch64::new(1.0));
+
+        assert_eq!(Ch8::new(127), -Ch8::new(-128));
+        assert_eq!(Ch16::new(32767), -Ch16::new(-32768));
+        assert_eq!(Ch32::new(1.0), -Ch32::new(-1.0));
+        assert_eq!(Ch64::new(1.0), -Ch64::new(-1.0));
+
+        assert_eq!(Ch8::new(-1), -Ch8::new(0));
+        assert_eq!(Ch8::new(0), -Ch8::new(-1));
+        assert_eq!(Ch16::new(-1), -Ch16::new(0));
+        assert_eq!(Ch16::new(0), -Ch16::new(-1));
+        assert_eq!(Ch32::new(0.0), -Ch32::new(0.0));
+        assert_eq!(Ch64::new(0.0), -Ch64::new(0.0));
+
+        assert_eq!(Ch12::new(-2048), -Ch12::new(2047));
+        assert_eq!(Ch12::new(2047), -Ch12::new(-2048));
+        assert_eq!(Ch12::new(-1), -Ch12::new(0));
+        assert_eq!(Ch20::new(-524_288), -Ch20::new(524_287));
+        assert_eq!(Ch20::new(524_287), -Ch20::new(-524_288));
+        assert_eq!(Ch20::new(-1), -Ch20::new(0));
+    }
+
+    #[test]
+    fn ch12_roundtrip() {
+        assert_eq!(-1.0, Ch12::new(-2048).to_f64());
+        assert_eq!(1.0, Ch12::new(2047).to_f64());
+        assert_eq!(Ch12::MAX, Ch12::new(i16::MAX));
+        assert_eq!(Ch12::MIN, Ch12::new(i16::MIN));
+        for i in -2048..=2047 {
+            let c = Ch12::new(i);
+            assert_eq!(c, Ch12::from_f64(c.to_f64()));
+            assert_eq!(c, Ch12::from(Ch32::from(c)));
+            assert_eq!(c, Ch12::from(Ch16::from(c)));
+            assert_eq!(c, Ch12::from(Ch20::from(c)));
+        }
+    }
+
+    #[test]
+    fn ch20_roundtrip() {
+        assert_eq!(-1.0, Ch20::new(-524_288).to_f64());
+        assert_eq!(1.0, Ch20::new(524_287).to_f64());
+        for i in -524_288..=524_287 {
+            let c = Ch20::new(i);
+            assert_eq!(c, Ch20::from_f64(c.to_f64()));
+        }
+    }
+
+    #[test]
+    fn ch24_roundtrip() {
+        assert_eq!(-1.0, Ch24::new(-8_388_608).to_f64());
+        assert_eq!(1.0, Ch24::new(8_388_607).to_f64());
+        let (mut prev_f64, mut prev_f32) = (f64::NEG_INFINITY, f32::MIN);
+        for i in -8_388_608..=8_388_607 {
+            let c = Ch24::new(i);
+            let (f64_value, f32_value) = (c.to_f64(), Ch32::from(c).0);
+            // Bit-transparent through both floating point types.
+            assert_eq!(c, Ch24::from_f64(f64_value));
+            assert_eq!(c, Ch24::from(Ch32::new(f32_value)));
+            // Strictly monotonic, so no two values map to the same float.
+            assert!(f64_value > prev_f64 && f32_value > prev_f32);
+            prev_f64 = f64_value;
+            prev_f32 = f32_value;
+        }
+        assert_eq!(Ch24::from_i32_clamped(i32::MAX), Ch24::MAX);
+        assert_eq!(Ch24::from_i32_clamped(-8_388_609), Ch24::MIN);
+        assert_eq!(i32::from(Ch24::from_i32_clamped(-42)), -42);
+    }
+
+    #[test]
+    fn ch24_conversions() {
+        // Only 24 bits of precision are kept.
+        for i in [-8_388_608, -257, -256, -1, 0, 255, 256, 8_388_607] {
+            let c = Ch16::from(Ch24::new(i));
+            assert_eq!(i32::from(i16::from(c)), i >> 8);
+        }
+        assert_eq!(Ch16::new(-32768), Ch16::from(Ch24::MIN));
+        assert_eq!(Ch16::new(32767), Ch16::from(Ch24::MAX));
+        assert_eq!(Ch20::MAX, Ch20::from(Ch24::MAX));
+        assert_eq!(Ch24::new(8_388_607), Ch24::from(Ch8::MAX));
+        assert_eq!(Ch24::new(4_194_303), Ch24::from(Ch64::new(0.5)));
+        assert_eq!(Ch24::new(-8_388_608), -Ch24::new(8_388_607));
+    }
+
+    #[test]
+    fn ch24_arith() {
+        assert_eq!(Ch24::new(-1), Ch24::MIN + Ch24::MAX);
+        assert_eq!(Ch24::MAX, Ch24::new(8_000_000) + Ch24::new(8_000_000));
+        assert_eq!(Ch24::MIN, Ch24::new(-8_000_000) - Ch24::new(8_000_000));
+        assert_eq!(Ch24::MAX, Ch24::MIN * Ch24::MIN);
+        assert_eq!(Ch24::new(-1000), Ch24::MAX * Ch24::new(-1000));
+        assert_eq!(Ch24::new(4_194_304), Ch24::new(1000) / Ch24::new(2000));
+        assert_eq!(Ch24::MAX, Ch24::new(1) / Ch24::new(0));
+    }
+
+    #[test]
+    fn ch12_ch20_conversions() {
+        // Only 12 or 20 bits of precision are kept.
+        for i in [-32768, -32767, -17, -16, -1, 0, 15, 16, 32767] {
+            assert_eq!(i16::from(Ch12::from(Ch16::new(i))), i >> 4);
+        }
+        assert_eq!(Ch16::new(-32768), Ch16::from(Ch12::MIN));
+        assert_eq!(Ch16::new(32767), Ch16::from(Ch12::MAX));
+        assert_eq!(Ch8::new(127), Ch8::from(Ch20::MAX));
+        assert_eq!(Ch20::MIN, Ch20::from(Ch8::MIN));
+        assert_eq!(Ch12::new(1023), Ch12::from(Ch32::new(0.5)));
+        assert_eq!(Ch20::new(262_143), Ch20::from(Ch64::new(0.5)));
+    }
+
+    #[test]
+    fn ch12_arith() {
+        assert_eq!(Ch12::new(-1), Ch12::new(-2048) + Ch12::new(2047));
+        assert_eq!(Ch12::new(2047), Ch12::new(1500) + Ch12::new(1500));
+        assert_eq!(Ch12::new(-2048), Ch12::new(-1500) - Ch12::new(1500));
+        assert_eq!(Ch12::new(2047), Ch12::new(2047) * Ch12::new(2047));
+        assert_eq!(Ch12::new(2047), Ch12::new(-2048) * Ch12::new(-2048));
+        assert_eq!(Ch12::new(-1024), Ch12::new(2047) * Ch12::new(-1024));
+        assert_eq!(Ch12::new(1024), Ch12::new(512) / Ch12::new(1024));
+        assert_eq!(Ch12::new(-2048), Ch12::new(1024) / Ch12::new(-512));
+        assert_eq!(Ch12::MAX, Ch12::new(1) / Ch12::new(0));
+    }
+
+    #[test]
+    fn ch20_arith() {
+        assert_eq!(Ch20::new(-1), Ch20::new(-524_288) + Ch20::new(524_287));
+        assert_eq!(Ch20::new(524_287), Ch20::new(400_000) + Ch20::new(400_000));
+        assert_eq!(
+            Ch20::new(-524_288),
+            Ch20::new(-400_000) - Ch20::new(400_000)
+        );
+        assert_eq!(
+            Ch20::new(524_287),
+            Ch20::new(-524_288) * Ch20::new(-524_288)
+        );
+        assert_eq!(Ch20::new(-1000), Ch20::new(524_287) * Ch20::new(-1000));
+        assert_eq!(Ch20::new(262_144), Ch20::new(1000) / Ch20::new(2000));
+        assert_eq!(Ch20::MAX, Ch20::new(1) / Ch20::new(0));
+    }
+
+    #[test]
+    fn ch8_roundtrip() {
+        assert_eq!(-1.0, Ch8::new(-128).to_f64());
+        assert_eq!(1.0, Ch8::new(127).to_f64());
+
+        assert_eq!(Ch8::new(-128), Ch8::from_f64(Ch8::new(-128).to_f64()));
+        assert_eq!(Ch8::new(0), Ch8::from_f64(Ch8::new(0).to_f64()));
+        assert_eq!(Ch8::new(127), Ch8::from_f64(Ch8::new(127).to_f64()));
+    }
+
+    #[test]
+    fn ch16_roundtrip() {
+        assert_eq!(-1.0, Ch16::new(-32768).to_f64());
+        assert_eq!(1.0, Ch16::new(32767).to_f64());
+
+        assert_eq!(
+            Ch16::new(-32768),
+            Ch16::from_f64(Ch16::new(-32768).to_f64())
+        );
+        assert_eq!(Ch16::new(0), Ch16::from_f64(Ch16::new(0).to_f64()));
+        assert_eq!(Ch16::new(32767), Ch16::from_f64(Ch16::new(32767).to_f64()));
+    }
+
+    #[test]
+    fn ch32_roundtrip() {
+        assert_eq!(-1.0, Ch32::new(-1.0).to_f64());
+        assert_eq!(0.0, Ch32::new(0.0).to_f64());
+        assert_eq!(1.0, Ch32::new(1.0).to_f64());
+
+        assert_eq!(Ch32::new(-1.0), Ch32::from_f64(Ch32::new(-1.0).to_f64()));
+        assert_eq!(Ch32::new(0.0), Ch32::from_f64(Ch32::new(0.0).to_f64()));
+        assert_eq!(Ch32::new(1.0), Ch32::from_f64(Ch32::new(1.0).to_f64()));
+    }
+
+    #[test]
+    fn ch64_roundtrip() {
+        assert_eq!(-1.0, Ch64::new(-1.0).to_f64());
+        assert_eq!(0.0, Ch64::new(0.0).to_f64());
+        assert_eq!(1.0, Ch64::new(1.0).to_f64());
+
+        // More precision than `f32` can hold.
+        let precise = 0.1 + f64::EPSILON;
+        assert_eq!(precise, Ch64::from_f64(precise).to_f64());
+        assert_eq!(precise, f64::from(Ch64::from(precise)));
+        assert_eq!(Ch64::new(1.0), Ch64::from(2.0));
+    }
+
+    #[test]
+    fn lerp() {
+        assert_eq!(
+            Ch16::new(0),
+            Ch16::new(-32768).lerp_f32(Ch16::new(32767), 0.5)
+        );
+        assert_eq!(
+            Ch16::new(32767),
+            Ch16::new(-32768).lerp_f32(Ch16::new(32767), 1.0)
+        );
+        assert_eq!(
+            Ch16::new(-32768),
+            Ch16::new(-32768).lerp_f32(Ch16::new(32767), 0.0)
+        );
+        assert_eq!(Ch8::new(-64), Ch8::new(-128).lerp_f32(Ch8::new(-1), 0.5));
+        assert_eq!(
+            Ch32::new(0.25),
+            Ch32::new(0.0).lerp_f32(Ch32::new(1.0), 0.25)
+        );
+        // Interpolating by a channel value.
+        assert_eq!(
+            Ch16::new(32767),
+            Ch16::new(-32768).lerp(Ch16::new(32767), Ch16::MAX)
+        );
+        assert_eq!(
+            Ch64::new(0.5),
+            Ch64::new(0.0).lerp(Ch64::new(1.0), Ch64::new(0.5))
+        );
+        // The interpolation amount isn't rounded to `f32`.
+        let third = Ch64::new(1.0 / 3.0);
+        assert_eq!(third, Ch64::new(0.0).lerp(Ch64::new(1.0), third));
+    }
+
+    #[test]
+    fn dbfs() {
+        assert_eq!(0.0, Ch32::MAX.to_dbfs());
+        assert_eq!(0.0, Ch16::MIN.to_dbfs());
+        assert_eq!(f64::NEG_INFINITY, Ch64::MID.to_dbfs());
+        assert!((Ch32::new(0.5).to_dbfs() + 6.0206).abs() < 0.0001);
+        assert_eq!(Ch32::MAX, Ch32::from_dbfs(0.0));
+        assert_eq!(Ch64::MAX, Ch64::from_dbfs(6.0));
+        assert_eq!(Ch16::new(16383), Ch16::from_dbfs(-6.0206));
+        assert!((Ch64::from_dbfs(-20.0).to_f64() - 0.1).abs() < 1e-12);
+    }
+
+    #[test]
+    fn is_silent() {
+        assert!(Ch8::MID.is_silent(0.0));
+        assert!(Ch20::MID.is_silent(0.0));
+        assert!(!Ch16::new(-1).is_silent(0.0));
+        assert!(Ch16::new(-1).is_silent(1.0 / 32767.0));
+        assert!(Ch32::new(f32::MIN_POSITIVE / 2.0).is_silent(0.0));
+        assert!(!Ch64::new(1e-30).is_silent(0.0));
+        assert!(Ch64::new(-0.01).is_silent(0.01));
+    }
+
+    #[test]
+    fn clip_policy() {
+        assert_eq!(
+            Ch32::new(1.0),
+            Ch32::from_f64_clipped(1.5, ClipPolicy::Saturate)
+        );
+        assert_eq!(
+            1.5,
+            Ch32::from_f64_clipped(1.5, ClipPolicy::OnConversion).to_f64()
+        );
+        assert_eq!(
+            Ch16::MAX,
+            Ch16::from_f64_clipped(1.5, ClipPolicy::OnConversion)
+        );
+        assert_eq!(Ch8::MIN, Ch8::from_f64_clipped(-2.0, ClipPolicy::Saturate));
+        assert_eq!(
+            Ch64::new(0.5),
+            Ch64::from_f64_clipped(0.5, ClipPolicy::DebugPanic)
+        );
+        for policy in [ClipPolicy::Saturate, ClipPolicy::OnConversion] {
+            assert_eq!(policy.clip::<Ch32>(f64::NAN), 1.0);
+            assert_eq!(policy.clip::<Ch16>(f64::NAN), 1.0);
+            assert_eq!(Ch32::from_f64_clipped(f64::NAN, policy), Ch32::MAX);
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn clip_policy_panic() {
+        Ch16::from_f64_clipped(1.5, ClipPolicy::DebugPanic);
     }
 
     #[test]
@@ -844,6 +1877,9 @@ mod tests {
         assert_eq!(Ch32::new(-1.0), Ch32::new(-1.0) / Ch32::new(1.0));
         assert_eq!(Ch32::new(1.0), Ch32::new(-1.0) / Ch32::new(-1.0));
         assert_eq!(Ch32::new(-1.0), Ch32::new(0.5) / Ch32::new(-0.5));
+        // Test NaN
+        assert_eq!(Ch32::new(1.0), Ch32::new(0.0) / Ch32::new(0.0));
+        assert_eq!(Ch32::new(1.0), Ch32::new(f32::NAN));
     }
 
     #[test]
@@ -870,6 +1906,9 @@ mod tests {
         assert_eq!(Ch64::new(-1.0), Ch64::new(-1.0) / Ch64::new(1.0));
         assert_eq!(Ch64::new(1.0), Ch64::new(-1.0) / Ch64::new(-1.0));
         assert_eq!(Ch64::new(-1.0), Ch64::new(0.5) / Ch64::new(-0.5));
+        // Test NaN
+        assert_eq!(Ch64::new(1.0), Ch64::new(0.0) / Ch64::new(0.0));
+        assert_eq!(Ch64::new(1.0), Ch64::from(f64::NAN));
     }
 
     #[test]
@@ -0,0 +1,292 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Dithered (and noise-shaped) bit depth reduction.
+
+use crate::{
+    chan::{self, Channel},
+    mono::Mono,
+    private::Sealed,
+    Audio, Frame,
+};
+use alloc::vec::Vec;
+use core::iter;
+
+// Error feedback filter for second order noise shaping, (1 - z⁻¹)².
+const SECOND_ORDER: &[f64] = &[2.0, -1.0];
+
+// Error feedback filter for F-weighted noise shaping (Wannamaker 9-tap).
+const F_WEIGHTED: &[f64] = &[
+    2.412, -3.370, 3.937, -4.174, 3.353, -2.205, 1.281, -0.569, 0.0847,
+];
+
+/// Spectral shape of the quantization noise.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DitherShape {
+    /// Flat TPDF (triangular probability density function) dither.
+    Flat,
+    /// TPDF dither with a simple 2nd-order highpass noise shaper.
+    SecondOrder,
+    /// TPDF dither with an F-weighted noise shaper, pushing the noise into
+    /// the frequencies where hearing is least sensitive.
+    FWeighted,
+}
+
+impl DitherShape {
+    fn coefficients(self) -> &'static [f64] {
+        match self {
+            DitherShape::Flat => &[],
+            DitherShape::SecondOrder => SECOND_ORDER,
+            DitherShape::FWeighted => F_WEIGHTED,
+        }
+    }
+}
+
+/// Dithering context for converting audio to a lower bit depth.
+///
+/// Converting to a floating point channel type doesn't add any dither.
+#[derive(Clone, Debug)]
+pub struct Dither {
+    shape: DitherShape,
+    seed: u32,
+    // Quantization error history for each channel, most recent first.
+    errors: Vec<[f64; 9]>,
+}
+
+impl Dither {
+    /// Create a new dithering context.
+    pub fn new(shape: DitherShape) -> Self {
+        Self {
+            shape,
+            seed: 0x9E37_79B9,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Get the noise shape of this dither.
+    pub fn shape(&self) -> DitherShape {
+        self.shape
+    }
+
+    /// Quantize a frame to a different channel type, with dither.
+    ///
+    /// # Panics
+    /// If the frames don't have the same number of channels.
+    pub fn quantize<F: Frame, G: Frame>(&mut self, frame: G) -> F {
+        assert_eq!(
+            F::CHAN_COUNT,
+            G::CHAN_COUNT,
+            "Dither::quantize() can't change the number of channels"
+        );
+        self.quantize_f64(frame.channels().iter().map(|c| c.to_f64()))
+    }
+
+    // Quantize one frame worth of channel values.
+    pub(crate) fn quantize_f64<F, I>(&mut self, values: I) -> F
+    where
+        F: Frame,
+        I: Iterator<Item = f64>,
+    {
+        let mut out = F::default();
+        let chans = out.channels_mut().iter_mut().zip(values);
+        let steps = if let Some(steps) = <F::Chan as Sealed>::STEPS {
+            steps
+        } else {
+            for (dst, src) in chans {
+                *dst = F::Chan::from_f64(src);
+            }
+            return out;
+        };
+        if self.errors.len() < F::CHAN_COUNT {
+            self.errors.resize(F::CHAN_COUNT, [0.0; 9]);
+        }
+        let coefficients = self.shape.coefficients();
+        for ((dst, src), errors) in chans.zip(self.errors.iter_mut()) {
+            // Work in units of the least significant bit.
+            let mut value = src * steps;
+            for (h, e) in coefficients.iter().zip(errors.iter()) {
+                value -= h * e;
+            }
+            let noise = self.seed.random() + self.seed.random();
+            let quantized =
+                (value + noise).floor().clamp(-steps - 0.5, steps - 0.5);
+            // Floor quantization reconstructs at the center of the step.
+            let reconstructed = quantized + 0.5;
+            errors.rotate_right(1);
+            errors[0] = (reconstructed - value).clamp(-1.5, 1.5);
+            *dst = F::Chan::from_f64(reconstructed / steps);
+        }
+        out
+    }
+}
+
+impl<F: Frame> Audio<F> {
+    /// Construct an `Audio` buffer by reducing the bit depth of another with
+    /// dither (for instance, creating a 16-bit master from 32-bit float
+    /// audio).  The sample rate and speaker configuration are kept.
+    ///
+    /// # Panics
+    /// If the frames don't have the same number of channels.
+    pub fn with_dither<G: Frame>(src: &Audio<G>, shape: DitherShape) -> Self {
+        let mut dither = Dither::new(shape);
+        let frames: Vec<F> = src.iter().map(|f| dither.quantize(*f)).collect();
+        Self::with_frames(src.sample_rate(), frames)
+    }
+}
+
+/// Error added by reducing the bit depth of audio, measured by
+/// [`requantization_noise_report()`].  Levels are in dBFS, and negative
+/// infinity when there is no error.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NoiseReport {
+    /// RMS level of the error (the noise floor).
+    pub noise_floor: f64,
+    /// Level of the mean error (DC bias).
+    pub dc_bias: f64,
+    /// Level of the largest error.
+    pub max_error: f64,
+}
+
+/// Measure the error of converting audio (usually floating point) to the
+/// channel type `C`, with or without dither.
+///
+/// This helps with choosing a [`DitherShape`] for a target format.  Each
+/// channel is dithered independently, as with
+/// [`Audio::with_dither()`](crate::Audio::with_dither).
+///
+/// ```rust
+/// use fon::{chan::Ch16, dither, mono::Mono32, Audio};
+///
+/// let audio = Audio::<Mono32>::with_frame(48_000, 4800, Mono32::new(0.1));
+/// let report = dither::requantization_noise_report::<Ch16, _>(&audio, None);
+/// assert!(report.max_error < -96.0);
+/// ```
+pub fn requantization_noise_report<C: Channel, F: Frame>(
+    audio: &Audio<F>,
+    shape: Option<DitherShape>,
+) -> NoiseReport {
+    let mut dithers: Vec<Dither> = match shape {
+        Some(shape) => (0..F::CHAN_COUNT).map(|_| Dither::new(shape)).collect(),
+        None => Vec::new(),
+    };
+    let (mut sum, mut squares, mut max, mut count) = (0.0, 0.0, 0.0f64, 0);
+    for frame in audio.iter() {
+        for (i, chan) in frame.channels().iter().enumerate() {
+            let value = chan.to_f64();
+            let quantized = match dithers.get_mut(i) {
+                Some(dither) => {
+                    let mono: Mono<C> = dither.quantize_f64(iter::once(value));
+                    mono.channels()[0]
+                }
+                None => C::from_f64(value),
+            };
+            let error = quantized.to_f64() - value;
+            sum += error;
+            squares += error * error;
+            max = max.max(error.abs());
+            count += 1;
+        }
+    }
+    let count = count.max(1) as f64;
+    NoiseReport {
+        noise_floor: chan::gain_to_db((squares / count).sqrt()),
+        dc_bias: chan::gain_to_db((sum / count).abs()),
+        max_error: chan::gain_to_db(max),
+    }
+}
+
+// Random number generator (xorshift), uniform from -0.5 to 0.5.
+pub(crate) trait Random {
+    fn random(&mut self) -> f64;
+}
+
+impl Random for u32 {
+    #[inline(always)]
+    fn random(&mut self) -> f64 {
+        *self ^= *self << 13;
+        *self ^= *self >> 17;
+        *self ^= *self << 5;
+        f64::from(*self) / f64::from(u32::MAX) - 0.5
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chan::{Ch16, Ch64},
+        mono::{Mono16, Mono64},
+    };
+
+    #[test]
+    fn dither_linearizes() {
+        // A constant a quarter of a step above zero.
+        let value = 0.25 / 32767.5 + 0.5 / 32767.5;
+        let src = Audio::<Mono64>::with_frame(
+            48_000,
+            4096,
+            Mono64::new(Ch64::new(value)),
+        );
+        for shape in [
+            DitherShape::Flat,
+            DitherShape::SecondOrder,
+            DitherShape::FWeighted,
+        ] {
+            let audio = Audio::<Mono16>::with_dither(&src, shape);
+            let mut sum = 0.0;
+            for frame in audio.iter() {
+                let sample = i16::from(frame.channels()[0]);
+                assert!((-32..=32).contains(&sample), "{:?}", shape);
+                sum += f64::from(sample);
+            }
+            let mean = sum / audio.len() as f64;
+            assert!((mean - 0.25).abs() < 0.05, "{:?}: {}", shape, mean);
+        }
+        // Without dither the offset is lost.
+        assert_eq!(
+            src.get(0).unwrap().convert::<Mono16>(),
+            Mono16::new(Ch16::new(0))
+        );
+    }
+
+    #[test]
+    fn noise_report() {
+        let samples: Vec<f64> =
+            (0..48_000).map(|i| (i as f64 / 10.0).sin() / 2.0).collect();
+        let audio = Audio::<Mono64>::with_f64_buffer(48_000, samples);
+        // Half a step of error at most, for a noise floor 6 dB/bit down.
+        let report = requantization_noise_report::<Ch16, _>(&audio, None);
+        assert!((report.noise_floor + 101.1).abs() < 0.5, "{:?}", report);
+        assert!(report.max_error <= -96.3, "{:?}", report);
+        // TPDF dither triples the noise power, and up to 1.5 steps of error.
+        let flat = Some(DitherShape::Flat);
+        let report = requantization_noise_report::<Ch16, _>(&audio, flat);
+        assert!((report.noise_floor + 96.3).abs() < 0.5, "{:?}", report);
+        assert!(report.max_error <= -86.7, "{:?}", report);
+
+        // Dither removes the DC bias of a constant quarter step offset.
+        let value = 0.25 / 32767.5 + 0.5 / 32767.5;
+        let audio =
+            Audio::<Mono64>::with_frame(48_000, 48_000, Mono64::new(value));
+        let report = requantization_noise_report::<Ch16, _>(&audio, None);
+        assert!((report.dc_bias + 102.3).abs() < 0.5, "{:?}", report);
+        let report = requantization_noise_report::<Ch16, _>(&audio, flat);
+        assert!(report.dc_bias < -120.0, "{:?}", report);
+        let report = requantization_noise_report::<Ch64, _>(&audio, flat);
+        assert_eq!(report.max_error, f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn dither_float_passthrough() {
+        let mut dither = Dither::new(DitherShape::FWeighted);
+        let out: Mono64 = dither.quantize(Mono64::new(Ch64::new(0.125)));
+        assert_eq!(out, Mono64::new(Ch64::new(0.125)));
+    }
+}
@@ -0,0 +1,320 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Destructive, sample-exact edits.
+
+use crate::{
+    audio,
+    chan::{self, Channel},
+    fade::Curve,
+    Audio, Frame,
+};
+use alloc::vec::Vec;
+use core::ops::{Range, RangeBounds};
+
+/// Record of the frame counts changed by a destructive edit on an
+/// [`Audio`](crate::Audio) buffer, for keeping an audit trail (or undo log)
+/// of edits.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Edit {
+    /// Frame index the edit was made at.
+    pub at: usize,
+    /// Number of frames removed at `at`.
+    pub removed: usize,
+    /// Number of frames inserted at `at`.
+    pub inserted: usize,
+    /// Length of the buffer after the edit.
+    pub len: usize,
+}
+
+impl<F: Frame> Audio<F> {
+    /// Insert `len` frames of silence before frame `at`, returning a record
+    /// of the edit.
+    ///
+    /// # Panics
+    /// If `at` is greater than the length of the buffer.
+    pub fn insert_silence(&mut self, at: usize, len: usize) -> Edit {
+        assert!(at <= self.len(), "Insert position out of bounds");
+        let tail = self.split_off(at);
+        self.resize(at + len, F::default());
+        self.append(tail);
+        Edit {
+            at,
+            removed: 0,
+            inserted: len,
+            len: self.len(),
+        }
+    }
+
+    /// Insert a copy of another `Audio` buffer before frame `at`, shifting the
+    /// tail, and returning a record of the edit.  The other buffer is
+    /// resampled if the sample rates differ, and converted with
+    /// [`Frame::convert()`].
+    ///
+    /// ```rust
+    /// use fon::{mono::Mono32, stereo::Stereo32, Audio};
+    ///
+    /// let mut audio = Audio::<Stereo32>::with_silence(48_000, 4);
+    /// let clip = Audio::<Mono32>::with_f32_buffer(48_000, [0.5, 0.5]);
+    /// assert_eq!(audio.insert(1, &clip).len, 6);
+    /// assert_ne!(audio[2], Stereo32::default());
+    /// assert_eq!(audio[3], Stereo32::default());
+    /// ```
+    ///
+    /// # Panics
+    /// If `at` is greater than the length of the buffer.
+    pub fn insert<G: Frame>(&mut self, at: usize, other: &Audio<G>) -> Edit {
+        assert!(at <= self.len(), "Insert position out of bounds");
+        let resampled;
+        let other = if other.sample_rate() == self.sample_rate() {
+            other
+        } else {
+            resampled = Audio::<G>::with_stream(self.sample_rate(), other);
+            &resampled
+        };
+        let tail = self.split_off(at);
+        Extend::extend(self, other.iter().map(|frame| frame.convert::<F>()));
+        self.append(tail);
+        Edit {
+            at,
+            removed: 0,
+            inserted: other.len(),
+            len: self.len(),
+        }
+    }
+
+    /// Remove a range of frames, returning a record of the edit.
+    ///
+    /// # Panics
+    /// If the range is out of bounds.
+    pub fn remove_range<R: RangeBounds<usize>>(&mut self, range: R) -> Edit {
+        let (start, end) = audio::range_bounds(&range, self.len());
+        let mut tail = self.split_off(start);
+        let tail = tail.split_off(end - start);
+        self.append(tail);
+        Edit {
+            at: start,
+            removed: end - start,
+            inserted: 0,
+            len: self.len(),
+        }
+    }
+
+    /// Keep only the frames for which `predicate` returns `true`, compacting
+    /// the buffer in place.
+    ///
+    /// Returns a record for each removed run of frames, ordered from the end
+    /// of the buffer to the start, so that each can be applied in turn (for
+    /// example to [`Markers`](crate::Markers)) with `at` still an index into
+    /// the original buffer.
+    pub fn retain_frames<P>(&mut self, mut predicate: P) -> Vec<Edit>
+    where
+        P: FnMut(&F) -> bool,
+    {
+        let mut runs: Vec<Range<usize>> = Vec::new();
+        let frames = self.as_slice();
+        let mut kept = 0;
+        for index in 0..frames.len() {
+            if predicate(&frames[index]) {
+                frames[kept] = frames[index];
+                kept += 1;
+            } else {
+                match runs.last_mut() {
+                    Some(run) if run.end == index => run.end += 1,
+                    _ => runs.push(index..index + 1),
+                }
+            }
+        }
+        let mut len = frames.len();
+        self.truncate(kept);
+        runs.iter()
+            .rev()
+            .map(|run| {
+                len -= run.len();
+                Edit {
+                    at: run.start,
+                    removed: run.len(),
+                    inserted: 0,
+                    len,
+                }
+            })
+            .collect()
+    }
+
+    /// Remove the frames quieter than `threshold_dbfs` (in every channel)
+    /// from the start and end of the buffer, then fade the first and last
+    /// `fade` frames that are left in and out linearly (to avoid clicks
+    /// where the audio was cut, or 0 for no fade).
+    ///
+    /// Returns a record for each removed run of frames, ordered like
+    /// [`retain_frames()`](Audio::retain_frames).
+    pub fn trim_silence(
+        &mut self,
+        threshold_dbfs: f64,
+        fade: usize,
+    ) -> Vec<Edit> {
+        let threshold = chan::db_to_gain(threshold_dbfs);
+        let frames = self.as_slice();
+        let start = frames
+            .iter()
+            .position(|frame| !frame.is_silent(threshold))
+            .unwrap_or(frames.len());
+        let end = frames
+            .iter()
+            .rposition(|frame| !frame.is_silent(threshold))
+            .map_or(start, |index| index + 1);
+        let mut edits = Vec::new();
+        if end < self.len() {
+            edits.push(self.remove_range(end..));
+        }
+        if start > 0 {
+            edits.push(self.remove_range(..start));
+        }
+        let fade = fade.min(self.len());
+        self.fade_in(fade, Curve::Linear);
+        self.fade_out(fade, Curve::Linear);
+        edits
+    }
+
+    /// Find the first zero crossing of a channel at or after frame `from`,
+    /// as the index of the frame after the sign change (so cutting before it
+    /// doesn't click).
+    ///
+    /// # Panics
+    /// If `channel` is out of bounds.
+    pub fn next_zero_crossing(
+        &self,
+        from: usize,
+        channel: usize,
+    ) -> Option<usize> {
+        let from = from.max(1);
+        (from..self.len()).find(|&index| self.crosses_zero(index, channel))
+    }
+
+    /// Find the last zero crossing of a channel at or before frame `from`,
+    /// as the index of the frame after the sign change.
+    ///
+    /// # Panics
+    /// If `channel` is out of bounds.
+    pub fn prev_zero_crossing(
+        &self,
+        from: usize,
+        channel: usize,
+    ) -> Option<usize> {
+        let from = from.min(self.len().saturating_sub(1));
+        (1..=from)
+            .rev()
+            .find(|&index| self.crosses_zero(index, channel))
+    }
+
+    // Check if the sign of a channel changes between frame `index - 1` and
+    // frame `index`.
+    fn crosses_zero(&self, index: usize, channel: usize) -> bool {
+        let sign =
+            |index: usize| self[index].channels()[channel].to_f64() < 0.0;
+        sign(index - 1) != sign(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mono::Mono32;
+
+    #[test]
+    fn edits() {
+        let samples: Vec<f32> = (0..10).map(|i| i as f32 / 10.0).collect();
+        let mut audio = Audio::<Mono32>::with_f32_buffer(48_000, samples);
+        let edit = audio.insert_silence(3, 4);
+        assert_eq!(edit.len, 14);
+        assert_eq!(edit.inserted, 4);
+        assert_eq!(audio.get(2), Some(Mono32::new(0.2)));
+        assert_eq!(audio.get(6), Some(Mono32::new(0.0)));
+        assert_eq!(audio.get(7), Some(Mono32::new(0.3)));
+
+        let edit = audio.remove_range(3..7);
+        assert_eq!(
+            edit,
+            Edit {
+                at: 3,
+                removed: 4,
+                inserted: 0,
+                len: 10
+            }
+        );
+        assert_eq!(audio.get(3), Some(Mono32::new(0.3)));
+        assert_eq!(audio.remove_range(8..).len, 8);
+        assert_eq!(audio.remove_range(..=1).len, 6);
+        assert_eq!(audio.get(0), Some(Mono32::new(0.2)));
+    }
+
+    #[test]
+    fn insert() {
+        let mut audio =
+            Audio::<Mono32>::with_frame(48_000, 4, Mono32::new(1.0));
+        let clip = Audio::<Mono32>::with_silence(96_000, 4);
+        let edit = audio.insert(2, &clip);
+        assert_eq!((edit.at, edit.inserted, edit.len), (2, 2, 6));
+        assert_eq!(audio.get(1), Some(Mono32::new(1.0)));
+        assert_eq!(audio.get(3), Some(Mono32::new(0.0)));
+        assert_eq!(audio.get(4), Some(Mono32::new(1.0)));
+    }
+
+    #[test]
+    fn retain_frames() {
+        let samples = [0.5, 0.0, 0.0, 0.5, 0.0, 0.5, 0.5, 0.0];
+        let mut audio = Audio::<Mono32>::with_f32_buffer(48_000, samples);
+        let mut markers = crate::Markers::new();
+        markers.add_position("last", 7);
+        let edits = audio.retain_frames(|frame| !frame.is_silent(0.0));
+        assert_eq!(audio.len(), 4);
+        assert!(audio.iter().all(|frame| *frame == Mono32::new(0.5)));
+        let runs: Vec<(usize, usize, usize)> =
+            edits.iter().map(|e| (e.at, e.removed, e.len)).collect();
+        assert_eq!(runs, [(7, 1, 7), (4, 1, 6), (1, 2, 4)]);
+        for edit in &edits {
+            markers.apply(edit);
+        }
+        assert_eq!(markers.get("last").unwrap().range, 4..4);
+    }
+
+    #[test]
+    fn zero_crossings() {
+        let samples = [0.5, 0.25, -0.25, -0.5, -0.25, 0.0, 0.25];
+        let audio = Audio::<Mono32>::with_f32_buffer(48_000, samples);
+        assert_eq!(audio.next_zero_crossing(0, 0), Some(2));
+        assert_eq!(audio.next_zero_crossing(2, 0), Some(2));
+        assert_eq!(audio.next_zero_crossing(3, 0), Some(5));
+        assert_eq!(audio.next_zero_crossing(6, 0), None);
+        assert_eq!(audio.prev_zero_crossing(4, 0), Some(2));
+        assert_eq!(audio.prev_zero_crossing(100, 0), Some(5));
+        assert_eq!(audio.prev_zero_crossing(1, 0), None);
+    }
+
+    #[test]
+    fn trim_silence() {
+        let samples = [0.0, 0.0001, 0.5, 0.0, 0.5, 0.5, 0.0005, 0.0];
+        let mut audio = Audio::<Mono32>::with_f32_buffer(48_000, samples);
+        let edits = audio.trim_silence(-60.0, 0);
+        assert_eq!(audio.len(), 4);
+        let runs: Vec<(usize, usize, usize)> =
+            edits.iter().map(|e| (e.at, e.removed, e.len)).collect();
+        assert_eq!(runs, [(6, 2, 6), (0, 2, 4)]);
+
+        let faded = audio.trim_silence(-60.0, 2);
+        assert!(faded.is_empty());
+        assert_eq!(audio.get(0), Some(Mono32::new(0.125)));
+        assert_eq!(audio.get(3), Some(Mono32::new(0.125)));
+
+        let mut silence = Audio::<Mono32>::with_silence(48_000, 10);
+        assert_eq!(silence.trim_silence(-60.0, 4).len(), 1);
+        assert!(silence.is_empty());
+    }
+}
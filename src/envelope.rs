@@ -0,0 +1,318 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Amplitude envelopes for waveform displays.
+
+use crate::{chan::Channel, Audio, Frame};
+use alloc::{vec, vec::Vec};
+use core::ops::{Bound, Range, RangeBounds};
+
+/// Multi-resolution peak cache (waveform overview pyramid).
+///
+/// The lowest level stores the minimum and maximum of each channel for every
+/// block of frames, and each level above it halves the resolution of the one
+/// below.  Build it once with [`PeakCache::new()`], keep it up to date with
+/// [`PeakCache::update()`] when a region of the `Audio` buffer is edited, and
+/// use [`PeakCache::query()`] to draw the waveform at any zoom level.
+#[derive(Clone, Debug)]
+pub struct PeakCache<F: Frame> {
+    block: usize,
+    len: usize,
+    levels: Vec<Vec<(F, F)>>,
+}
+
+impl<F: Frame> PeakCache<F> {
+    /// Build a peak cache for an `Audio` buffer, with `block` frames per peak
+    /// on the finest level.
+    ///
+    /// # Panics
+    /// If `block` is zero.
+    pub fn new(audio: &Audio<F>, block: usize) -> Self {
+        assert_ne!(block, 0, "PeakCache::new() called with zero block size");
+        let mut cache = Self {
+            block,
+            len: 0,
+            levels: vec![Vec::new()],
+        };
+        cache.update(audio, ..);
+        cache
+    }
+
+    /// Update the cache after the frames in `range` of `audio` were modified.
+    /// If the buffer length changed, everything after the start of the range
+    /// is recalculated.
+    pub fn update<R: RangeBounds<usize>>(
+        &mut self,
+        audio: &Audio<F>,
+        range: R,
+    ) {
+        let start = match range.start_bound() {
+            Bound::Included(start) => *start,
+            Bound::Excluded(start) => *start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = if audio.len() != self.len {
+            audio.len()
+        } else {
+            match range.end_bound() {
+                Bound::Included(end) => *end + 1,
+                Bound::Excluded(end) => *end,
+                Bound::Unbounded => audio.len(),
+            }
+        };
+        self.len = audio.len();
+        let end = end.min(self.len);
+        let start = start.min(end);
+
+        // Recalculate the affected blocks of the finest level.
+        let mut lo = start / self.block;
+        let mut hi = end.div_ceil(self.block);
+        let blocks = self.len.div_ceil(self.block);
+        self.levels[0].resize(blocks, (F::default(), F::default()));
+        let mut frames = audio.iter().skip(lo * self.block);
+        for peak in &mut self.levels[0][lo..hi] {
+            let mut block = frames.by_ref().take(self.block);
+            let first = *block.next().unwrap();
+            *peak = (first, first);
+            for frame in block {
+                min_max(&mut peak.0, &mut peak.1, frame);
+            }
+        }
+
+        // Propagate changes up through the coarser levels.
+        let mut level = 1;
+        while self.levels[level - 1].len() > 1 {
+            if self.levels.len() == level {
+                self.levels.push(Vec::new());
+            }
+            lo /= 2;
+            hi = hi.div_ceil(2);
+            let (lower, upper) = self.levels.split_at_mut(level);
+            let (lower, upper) = (&lower[level - 1], &mut upper[0]);
+            upper.resize(lower.len().div_ceil(2), (F::default(), F::default()));
+            for (i, peak) in upper[lo..hi].iter_mut().enumerate() {
+                let pair =
+                    &lower[(lo + i) * 2..lower.len().min((lo + i) * 2 + 2)];
+                *peak = pair[0];
+                if let Some(other) = pair.get(1) {
+                    min_max(&mut peak.0, &mut peak.1, &other.0);
+                    min_max(&mut peak.0, &mut peak.1, &other.1);
+                }
+            }
+            level += 1;
+        }
+        self.levels.truncate(level);
+    }
+
+    /// Get the number of frames the cache covers.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if the cache covers no frames.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the number of resolution levels in the cache.
+    pub fn levels(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Get the minimum and maximum of each channel for `n_buckets` evenly
+    /// sized regions of `range`.  Each bucket is assembled from the coarsest
+    /// levels that fit inside it, so querying is fast at any zoom level.
+    /// Bucket edges are rounded out to the block size the cache was built
+    /// with, and buckets outside of the cache are silent.
+    pub fn query(&self, range: Range<usize>, n_buckets: usize) -> Vec<(F, F)> {
+        let mut buckets = vec![(F::default(), F::default()); n_buckets];
+        let end = range.end.min(self.len);
+        let start = range.start.min(end);
+        let len = end - start;
+        if len == 0 {
+            return buckets;
+        }
+        for (i, bucket) in buckets.iter_mut().enumerate() {
+            let lo = start + i * len / n_buckets;
+            let hi = (start + (i + 1) * len / n_buckets).max(lo + 1).min(end);
+            let mut lo = lo / self.block;
+            let mut hi = hi.div_ceil(self.block);
+            *bucket = self.levels[0][lo];
+            for level in &self.levels {
+                if lo >= hi {
+                    break;
+                }
+                if lo % 2 == 1 {
+                    min_max(&mut bucket.0, &mut bucket.1, &level[lo].0);
+                    min_max(&mut bucket.0, &mut bucket.1, &level[lo].1);
+                    lo += 1;
+                }
+                if hi % 2 == 1 {
+                    hi -= 1;
+                    min_max(&mut bucket.0, &mut bucket.1, &level[hi].0);
+                    min_max(&mut bucket.0, &mut bucket.1, &level[hi].1);
+                }
+                lo /= 2;
+                hi /= 2;
+            }
+        }
+        buckets
+    }
+}
+
+impl<F: Frame> Audio<F> {
+    /// Get the minimum and maximum value of each channel for `n_buckets`
+    /// evenly sized regions of the `Audio` buffer (one region per pixel column
+    /// of a waveform display).  The buffer is only scanned once.
+    ///
+    /// Buckets that don't contain any frames (when there are more buckets than
+    /// frames) are silent.
+    pub fn min_max_per_bucket(&self, n_buckets: usize) -> Vec<(F, F)> {
+        let mut buckets = vec![(F::default(), F::default()); n_buckets];
+        let len = self.len();
+        let mut frames = self.iter();
+        for (i, (min, max)) in buckets.iter_mut().enumerate() {
+            let count = (i + 1) * len / n_buckets - i * len / n_buckets;
+            let mut bucket = frames.by_ref().take(count);
+            let first = if let Some(first) = bucket.next() {
+                *first
+            } else {
+                continue;
+            };
+            *min = first;
+            *max = first;
+            for frame in bucket {
+                min_max(min, max, frame);
+            }
+        }
+        buckets
+    }
+
+    /// Get the RMS (root mean square) amplitude envelope of the `Audio`
+    /// buffer, with one frame for each `window` frames.  The last frame covers
+    /// what's left over when the length isn't a multiple of `window`.
+    ///
+    /// # Panics
+    /// If `window` is zero.
+    pub fn energy_map(&self, window: usize) -> Vec<F> {
+        assert_ne!(window, 0, "Audio::energy_map() called with zero window");
+        let mut envelope = Vec::with_capacity(self.len().div_ceil(window));
+        let mut sums = vec![0.0; F::CHAN_COUNT];
+        let mut count = 0;
+        for frame in self.iter() {
+            for (sum, chan) in sums.iter_mut().zip(frame.channels().iter()) {
+                let value = chan.to_f64();
+                *sum += value * value;
+            }
+            count += 1;
+            if count == window {
+                envelope.push(rms(&mut sums, count));
+                count = 0;
+            }
+        }
+        if count != 0 {
+            envelope.push(rms(&mut sums, count));
+        }
+        envelope
+    }
+}
+
+// Widen `min` and `max` to include `frame`.
+#[inline(always)]
+fn min_max<F: Frame>(min: &mut F, max: &mut F, frame: &F) {
+    let chans = min.channels_mut().iter_mut().zip(max.channels_mut());
+    for ((min, max), chan) in chans.zip(frame.channels().iter()) {
+        if *chan < *min {
+            *min = *chan;
+        }
+        if *chan > *max {
+            *max = *chan;
+        }
+    }
+}
+
+// Build a frame from the sums of squares, and reset the sums.
+#[inline(always)]
+fn rms<F: Frame>(sums: &mut [f64], count: usize) -> F {
+    let mut out = F::default();
+    for (chan, sum) in out.channels_mut().iter_mut().zip(sums.iter_mut()) {
+        *chan = F::Chan::from_f64((*sum / count as f64).sqrt());
+        *sum = 0.0;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PeakCache;
+    use crate::{mono::Mono32, Audio};
+
+    #[test]
+    fn min_max_buckets() {
+        let audio = Audio::<Mono32>::with_f32_buffer(
+            48_000,
+            vec![0.5, -0.25, 0.0, 1.0, -1.0, 0.25],
+        );
+        let buckets = audio.min_max_per_bucket(3);
+        assert_eq!(buckets.len(), 3);
+        assert_eq!(buckets[0], (Mono32::new(-0.25), Mono32::new(0.5)));
+        assert_eq!(buckets[1], (Mono32::new(0.0), Mono32::new(1.0)));
+        assert_eq!(buckets[2], (Mono32::new(-1.0), Mono32::new(0.25)));
+        // More buckets than frames.
+        let buckets = audio.min_max_per_bucket(12);
+        assert_eq!(buckets[0], (Mono32::default(), Mono32::default()));
+        assert_eq!(buckets[1], (Mono32::new(0.5), Mono32::new(0.5)));
+    }
+
+    #[test]
+    fn energy_envelope() {
+        let audio = Audio::<Mono32>::with_f32_buffer(
+            48_000,
+            vec![0.5, -0.5, 0.5, -0.5, 1.0],
+        );
+        let envelope = audio.energy_map(2);
+        assert_eq!(envelope.len(), 3);
+        assert_eq!(envelope[0], Mono32::new(0.5));
+        assert_eq!(envelope[1], Mono32::new(0.5));
+        assert_eq!(envelope[2], Mono32::new(1.0));
+    }
+
+    #[test]
+    fn peak_cache() {
+        let samples: Vec<f32> =
+            (0..100).map(|i| (i as f32 - 50.0) / 50.0).collect();
+        let mut audio = Audio::<Mono32>::with_f32_buffer(48_000, samples);
+        let mut cache = PeakCache::new(&audio, 4);
+        assert_eq!(cache.len(), 100);
+        assert_eq!(cache.levels(), 6);
+        assert_eq!(cache.query(0..100, 25), audio.min_max_per_bucket(25));
+        assert_eq!(cache.query(0..100, 5), audio.min_max_per_bucket(5));
+        assert_eq!(
+            cache.query(4..8, 1),
+            vec![(Mono32::new(-0.92), Mono32::new(-0.86))]
+        );
+
+        // Edit a region, and check that the change propagates.
+        *audio.get_mut(42).unwrap() = Mono32::new(1.0);
+        cache.update(&audio, 42..43);
+        assert_eq!(cache.query(0..100, 1)[0].1, Mono32::new(1.0));
+        assert_eq!(cache.query(0..100, 5), audio.min_max_per_bucket(5));
+
+        // Grow the buffer.
+        audio.extend(&Audio::<Mono32>::with_frame(
+            48_000,
+            28,
+            Mono32::new(-1.0),
+        ));
+        cache.update(&audio, 100..);
+        assert_eq!(cache.len(), 128);
+        assert_eq!(cache.query(0..128, 4), audio.min_max_per_bucket(4));
+    }
+}
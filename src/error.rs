@@ -0,0 +1,72 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Error type.
+
+use crate::pipeline::PipelineError;
+use core::fmt;
+
+/// An error from a fallible operation of this crate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The length of a buffer isn't a whole number of frames.
+    BadLength,
+    /// The sample rate isn't a positive, finite number.
+    BadSampleRate,
+    /// The sample rates of two buffers or streams differ.
+    SampleRateMismatch,
+    /// The operation isn't supported for the number of channels.
+    ChannelCount,
+    /// A format conversion (resampling) pipeline couldn't be built.
+    Pipeline(PipelineError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Error::BadLength => "buffer length isn't a whole number of frames",
+            Error::BadSampleRate => "invalid sample rate",
+            Error::SampleRateMismatch => "sample rates don't match",
+            Error::ChannelCount => "unsupported channel count",
+            Error::Pipeline(error) => return write!(f, "pipeline: {}", error),
+        })
+    }
+}
+
+impl From<PipelineError> for Error {
+    fn from(error: PipelineError) -> Self {
+        Error::Pipeline(error)
+    }
+}
+
+// The pipeline error is part of the message, so it's not also a source.
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PipelineError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn display() {
+        let error = Error::from(PipelineError::SampleRate);
+        assert_eq!(error.to_string(), "pipeline: invalid sample rate");
+        #[cfg(feature = "std")]
+        assert!(std::error::Error::source(&error).is_none());
+        assert_eq!(
+            Error::ChannelCount.to_string(),
+            "unsupported channel count"
+        );
+    }
+}
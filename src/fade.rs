@@ -0,0 +1,401 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Fades and crossfades.
+
+use crate::{
+    chan::{Channel, ClipPolicy},
+    loudness, Audio, Frame, Sink, Stream,
+};
+use core::f64::consts::FRAC_PI_2;
+
+/// Fade curve.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Curve {
+    /// Linear gain (constant amplitude when crossfading correlated audio).
+    Linear,
+    /// Equal power gain (constant loudness when crossfading uncorrelated
+    /// audio).
+    EqualPower,
+    /// Exponential gain, changing at a steady rate in decibels over a range
+    /// of 60 dB (sounds even for long fades to and from silence).
+    Exponential,
+}
+
+impl Curve {
+    /// Get the gain of a fade in at `t`, a position in the fade from 0 to 1.
+    #[inline(always)]
+    pub fn fade_in(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Curve::Linear => t,
+            Curve::EqualPower => (t * FRAC_PI_2).sin(),
+            Curve::Exponential => (1000.0_f64.powf(t) - 1.0) / 999.0,
+        }
+    }
+
+    /// Get the gain of a fade out at `t`, a position in the fade from 0 to 1.
+    #[inline(always)]
+    pub fn fade_out(self, t: f64) -> f64 {
+        self.fade_in(1.0 - t)
+    }
+}
+
+impl<F: Frame> Audio<F> {
+    /// Fade in the first `len` frames.
+    ///
+    /// # Panics
+    /// If the buffer is shorter than the fade.
+    pub fn fade_in(&mut self, len: usize, curve: Curve) {
+        assert!(len <= self.len(), "Fade longer than buffer");
+        for (i, frame) in self.iter_mut().take(len).enumerate() {
+            let t = (i as f64 + 0.5) / len as f64;
+            fade_frame(frame, curve.fade_in(t));
+        }
+    }
+
+    /// Fade out the last `len` frames.
+    ///
+    /// # Panics
+    /// If the buffer is shorter than the fade.
+    pub fn fade_out(&mut self, len: usize, curve: Curve) {
+        assert!(len <= self.len(), "Fade longer than buffer");
+        let start = self.len() - len;
+        for (i, frame) in self.iter_mut().skip(start).enumerate() {
+            let t = (i as f64 + 0.5) / len as f64;
+            fade_frame(frame, curve.fade_out(t));
+        }
+    }
+
+    /// Join two buffers into a new one, crossfading the last `len` frames of
+    /// `a` with the first `len` frames of `b`.
+    ///
+    /// `b` is resampled to the sample rate of `a` if they differ (with `len`
+    /// counted at the sample rate of `a`), and converted to the format of `a`.
+    ///
+    /// # Panics
+    /// If either buffer is shorter than the crossfade.
+    pub fn crossfade<G: Frame>(
+        a: &Audio<F>,
+        b: &Audio<G>,
+        len: usize,
+        curve: Curve,
+    ) -> Self {
+        let resampled;
+        let b = if b.sample_rate() == a.sample_rate() {
+            b
+        } else {
+            resampled = Audio::<G>::with_stream(a.sample_rate(), b);
+            &resampled
+        };
+        let fade = CrossfadeStream::new(a, b, len, curve);
+        let mut out =
+            Self::with_capacity(a.sample_rate(), a.len() + b.len() - len);
+        Extend::extend(&mut out, a.iter().take(a.len() - len).cloned());
+        Extend::extend(&mut out, fade);
+        Extend::extend(&mut out, b.iter().skip(len).map(|f| f.convert::<F>()));
+        out
+    }
+
+    /// Join two buffers like [`crossfade()`](Audio::crossfade), but with
+    /// fade curves shaped so the loudness moves steadily from that of `a` to
+    /// that of `b`, rather than dipping or jumping when their levels differ.
+    ///
+    /// The loudness of each buffer is measured over up to 3 seconds (the
+    /// short-term window of EBU R128) next to the crossfade.  The louder
+    /// buffer fades along an equal power curve (or faster, so as not to
+    /// overshoot), and the quieter one makes up the rest of the target
+    /// loudness, assuming the buffers are uncorrelated.
+    ///
+    /// # Panics
+    /// If either buffer is shorter than the crossfade.
+    pub fn crossfade_loudness<G: Frame>(
+        a: &Audio<F>,
+        b: &Audio<G>,
+        len: usize,
+    ) -> Self {
+        let resampled;
+        let b = if b.sample_rate() == a.sample_rate() {
+            b
+        } else {
+            resampled = Audio::<G>::with_stream(a.sample_rate(), b);
+            &resampled
+        };
+        assert!(len <= a.len(), "Crossfade longer than first buffer");
+        assert!(len <= b.len(), "Crossfade longer than second buffer");
+        let s_rate = a.sample_rate();
+        let window = (s_rate as usize * 3).max(len);
+        let tail = a.iter().skip(a.len().saturating_sub(window)).cloned();
+        let power_a = loudness::mean_power(s_rate, tail);
+        let power_b =
+            loudness::mean_power(s_rate, b.iter().take(window).cloned());
+
+        let mut out = Self::with_capacity(s_rate, a.len() + b.len() - len);
+        Extend::extend(&mut out, a.iter().take(a.len() - len).cloned());
+        let fade = a.iter().skip(a.len() - len).zip(b.iter());
+        for (i, (src, dst)) in fade.enumerate() {
+            let t = (i as f64 + 0.5) / len as f64;
+            let (gain_a, gain_b) = loudness_gains(power_a, power_b, t);
+            let mut out_frame: F = dst.convert();
+            for (dst, src) in
+                out_frame.channels_mut().iter_mut().zip(src.channels())
+            {
+                let value = src.to_f64() * gain_a + dst.to_f64() * gain_b;
+                *dst = F::Chan::from_f64_clipped(value, ClipPolicy::Saturate);
+            }
+            out.push(out_frame);
+        }
+        Extend::extend(&mut out, b.iter().skip(len).map(|f| f.convert::<F>()));
+        out
+    }
+}
+
+// Get the gains of `a` and `b` at `t` (0 to 1) into a crossfade, so the total
+// power moves exponentially (steadily in decibels) from `a`'s to `b`'s.
+fn loudness_gains(power_a: f64, power_b: f64, t: f64) -> (f64, f64) {
+    let curve = Curve::EqualPower;
+    if !(power_a > 0.0 && power_b > 0.0) {
+        return (curve.fade_out(t), curve.fade_in(t));
+    }
+    let target = power_a.powf(1.0 - t) * power_b.powf(t);
+    if power_a >= power_b {
+        let gain_a = curve.fade_out(t).min((target / power_a).sqrt());
+        let rest = (target - gain_a * gain_a * power_a).max(0.0);
+        (gain_a, (rest / power_b).sqrt().min(1.0))
+    } else {
+        let gain_b = curve.fade_in(t).min((target / power_b).sqrt());
+        let rest = (target - gain_b * gain_b * power_b).max(0.0);
+        ((rest / power_a).sqrt().min(1.0), gain_b)
+    }
+}
+
+// Multiply every channel of a frame by a fade gain.
+fn fade_frame<F: Frame>(frame: &mut F, gain: f64) {
+    for chan in frame.channels_mut() {
+        *chan = F::Chan::from_f64(chan.to_f64() * gain);
+    }
+}
+
+/// Stream the crossfade between the last `len` frames of `a` and the first
+/// `len` frames of `b` into a sink, without making a new `Audio` buffer.
+///
+/// This is useful for gapless playback, where the rest of `b` is streamed
+/// after the crossfade.  `b` is converted to the format of `a`; if their
+/// sample rates differ `b` is linearly interpolated to the sample rate of `a`,
+/// with `len` counted at the sample rate of `a`.
+///
+/// # Panics
+/// If either buffer is shorter than the crossfade.
+pub fn crossfade_into<F, G, H, K>(
+    a: &Audio<F>,
+    b: &Audio<G>,
+    len: usize,
+    curve: Curve,
+    sink: &mut K,
+) where
+    F: Frame,
+    G: Frame,
+    H: Frame,
+    K: Sink<H>,
+{
+    sink.stream(CrossfadeStream::new(a, b, len, curve));
+}
+
+/// Stream of the crossfaded region between two `Audio` buffers.
+#[derive(Debug)]
+pub struct CrossfadeStream<'a, F: Frame, G: Frame> {
+    a: &'a Audio<F>,
+    b: &'a Audio<G>,
+    len: usize,
+    curve: Curve,
+    cursor: usize,
+}
+
+impl<'a, F: Frame, G: Frame> CrossfadeStream<'a, F, G> {
+    /// Create a stream of the crossfade between the last `len` frames of `a`
+    /// and the first `len` frames of `b` (see
+    /// [`crossfade_into()`](crossfade_into)).
+    ///
+    /// # Panics
+    /// If either buffer is shorter than the crossfade.
+    pub fn new(
+        a: &'a Audio<F>,
+        b: &'a Audio<G>,
+        len: usize,
+        curve: Curve,
+    ) -> Self {
+        let ratio = b.sample_rate() / a.sample_rate();
+        assert!(len <= a.len(), "Crossfade longer than first buffer");
+        assert!(
+            (len as f64 * ratio) as usize <= b.len(),
+            "Crossfade longer than second buffer"
+        );
+        Self {
+            a,
+            b,
+            len,
+            curve,
+            cursor: 0,
+        }
+    }
+
+    // Get frame of `b` at the sample rate of `a`.
+    fn b_frame(&self, index: usize) -> F {
+        let ratio = self.b.sample_rate() / self.a.sample_rate();
+        let pos = index as f64 * ratio;
+        let floor = pos as usize;
+        let frame = self.b.get(floor).unwrap_or_default();
+        let frame = if let Some(next) = self.b.get(floor + 1) {
+            frame.lerp_f32(next, (pos % 1.0) as f32)
+        } else {
+            frame
+        };
+        frame.convert()
+    }
+}
+
+impl<F: Frame, G: Frame> Iterator for CrossfadeStream<'_, F, G> {
+    type Item = F;
+
+    fn next(&mut self) -> Option<F> {
+        if self.cursor >= self.len {
+            return None;
+        }
+        let t = (self.cursor as f64 + 0.5) / self.len as f64;
+        let (gain_out, gain_in) =
+            (self.curve.fade_out(t), self.curve.fade_in(t));
+        let src = self.a.get(self.a.len() - self.len + self.cursor).unwrap();
+        let mut out = self.b_frame(self.cursor);
+        for (dst, src) in out.channels_mut().iter_mut().zip(src.channels()) {
+            let value = src.to_f64() * gain_out + dst.to_f64() * gain_in;
+            *dst = F::Chan::from_f64_clipped(value, ClipPolicy::Saturate);
+        }
+        self.cursor += 1;
+        Some(out)
+    }
+}
+
+impl<F: Frame, G: Frame> Stream<F> for CrossfadeStream<'_, F, G> {
+    fn sample_rate(&self) -> Option<f64> {
+        Some(self.a.sample_rate())
+    }
+
+    fn len(&self) -> Option<usize> {
+        Some(self.len - self.cursor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mono::{Mono32, Mono64},
+        stereo::Stereo16,
+    };
+
+    #[test]
+    fn crossfade_sink() {
+        let a = Audio::<Mono32>::with_frame(48_000, 100, Mono32::new(1.0));
+        let b = Audio::<Stereo16>::with_frame(
+            48_000,
+            100,
+            Stereo16::new(i16::MIN, i16::MIN),
+        );
+        let mut out = Audio::<Mono32>::with_silence(48_000, 10);
+        crossfade_into(&a, &b, 10, Curve::Linear, &mut out.sink(..));
+        let first = out.get(0).unwrap().channels()[0].to_f64();
+        let last = out.get(9).unwrap().channels()[0].to_f64();
+        assert!((first - 0.9).abs() < 1e-6);
+        assert!((last + 0.9).abs() < 1e-6);
+        assert!(out.get(5).unwrap().channels()[0].to_f64() < 0.0);
+
+        // Equal power keeps uncorrelated audio at the same loudness.
+        let curve = Curve::EqualPower;
+        for t in [0.0, 0.25, 0.5, 1.0] {
+            let power = curve.fade_in(t).powi(2) + curve.fade_out(t).powi(2);
+            assert!((power - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn fades() {
+        let mut audio =
+            Audio::<Mono32>::with_frame(48_000, 10, Mono32::new(1.0));
+        audio.fade_in(4, Curve::Linear);
+        audio.fade_out(2, Curve::Exponential);
+        let gains: Vec<f64> =
+            audio.iter().map(|f| f.channels()[0].to_f64()).collect();
+        assert!((gains[0] - 0.125).abs() < 1e-6);
+        assert!((gains[3] - 0.875).abs() < 1e-6);
+        assert_eq!(gains[4], 1.0);
+        assert!(gains[8] > gains[9]);
+        assert!(gains[9] < 0.05);
+
+        let curve = Curve::Exponential;
+        assert_eq!((curve.fade_in(0.0), curve.fade_in(1.0)), (0.0, 1.0));
+        assert!(curve.fade_in(0.5) < Curve::Linear.fade_in(0.5));
+    }
+
+    #[test]
+    fn crossfade() {
+        let a = Audio::<Mono32>::with_frame(48_000, 10, Mono32::new(0.5));
+        let b = Audio::<Stereo16>::with_frame(96_000, 40, Stereo16::new(0, 0));
+        let audio = Audio::crossfade(&a, &b, 4, Curve::EqualPower);
+        assert_eq!(audio.len(), 26);
+        assert_eq!(audio.get(5), Some(Mono32::new(0.5)));
+        let fade = audio.get(6).unwrap().channels()[0].to_f64();
+        assert!((fade - 0.5 * Curve::EqualPower.fade_out(0.125)).abs() < 1e-4);
+        assert!(audio.get(9).unwrap().channels()[0].to_f64() < 0.1);
+        assert!(audio.iter().skip(10).all(|f| f.is_silent(1e-4)));
+    }
+
+    #[test]
+    fn crossfade_loudness() {
+        // Gains keep the power on a steady path between the two levels.
+        for (power_a, power_b) in [(1.0, 0.01), (0.01, 1.0), (0.5, 0.5)] {
+            for t in [0.1, 0.3, 0.5, 0.7, 0.9] {
+                let (a, b) = loudness_gains(power_a, power_b, t);
+                let power = a * a * power_a + b * b * power_b;
+                let target: f64 = power_a.powf(1.0 - t) * power_b.powf(t);
+                assert!((power / target - 1.0).abs() < 1e-9);
+            }
+        }
+        assert_eq!(
+            loudness_gains(0.0, 1.0, 0.25),
+            (
+                Curve::EqualPower.fade_out(0.25),
+                Curve::EqualPower.fade_in(0.25),
+            )
+        );
+
+        let tone = |amplitude: f64| {
+            let samples: Vec<f64> = (0..48_000)
+                .map(|i| (i as f64 * 0.13).sin() * amplitude)
+                .collect();
+            Audio::<Mono64>::with_f64_buffer(48_000, samples)
+        };
+        let audio = Audio::crossfade_loudness(&tone(0.5), &tone(0.05), 4800);
+        assert_eq!(audio.len(), 91_200);
+        assert_eq!(audio.get(1000), tone(0.5).get(1000));
+    }
+
+    #[test]
+    fn crossfade_resample() {
+        let a = Audio::<Mono32>::with_frame(24_000, 10, Mono32::new(0.0));
+        let b: Vec<f32> = (0..20).map(|i| i as f32 / 20.0).collect();
+        let b = Audio::<Mono32>::with_f32_buffer(48_000, b);
+        let stream = CrossfadeStream::new(&a, &b, 10, Curve::Linear);
+        assert_eq!(stream.len(), Some(10));
+        let frames: Vec<Mono32> = stream.into_iter().collect();
+        let last = frames[9].channels()[0].to_f64();
+        // Frame 18 of `b`, at 95% of the fade.
+        assert!((last - 0.9 * 0.95).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,163 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! `fon-raw` wire format.
+
+use crate::{
+    chan::{Ch12, Ch16, Ch20, Ch24, Ch32, Ch64, Ch8},
+    private::Sealed,
+    Audio, Frame,
+};
+use alloc::{vec, vec::Vec};
+use core::{any::TypeId, convert::TryInto, mem::size_of};
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+// Magic bytes at the start of the header.
+const MAGIC: [u8; 4] = *b"FonR";
+// Version of the format.
+const VERSION: u16 = 1;
+// Length of the header, in bytes.
+const HEADER_LEN: usize = 28;
+
+// Sample kind codes, in the order of the channel types.
+fn sample_kind<C: Sealed>() -> u8 {
+    let kinds = [
+        TypeId::of::<Ch8>(),
+        TypeId::of::<Ch12>(),
+        TypeId::of::<Ch16>(),
+        TypeId::of::<Ch20>(),
+        TypeId::of::<Ch24>(),
+        TypeId::of::<Ch32>(),
+        TypeId::of::<Ch64>(),
+    ];
+    let kind = kinds.iter().position(|kind| *kind == TypeId::of::<C>());
+    kind.expect("Unknown channel type") as u8 + 1
+}
+
+fn invalid(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+impl<F: Frame> Audio<F> {
+    /// Write the buffer in the `fon-raw` format, a small self-describing
+    /// header followed by the interleaved little endian samples, for passing
+    /// audio between processes over pipes or sockets.
+    ///
+    /// The header is 28 bytes, all little endian:
+    ///
+    /// | Offset | Size | Field                                          |
+    /// |--------|------|------------------------------------------------|
+    /// | 0      | 4    | Magic bytes `FonR`                             |
+    /// | 4      | 2    | Format version (1)                             |
+    /// | 6      | 2    | Channel count                                  |
+    /// | 8      | 1    | Sample kind                                    |
+    /// | 9      | 3    | Reserved (zero)                                |
+    /// | 12     | 8    | Sample rate (`f64`)                            |
+    /// | 20     | 8    | Frame count (`u64`)                            |
+    ///
+    /// The sample kinds 1 to 7 are `Ch8`, `Ch12`, `Ch16`, `Ch20`, `Ch24`,
+    /// `Ch32` and `Ch64`.  Samples take the size of their channel type in
+    /// memory (`Ch12` is stored in 2 bytes, `Ch20` and `Ch24` in 4).
+    pub fn write_fonraw<W: Write>(&self, mut writer: W) -> Result<()> {
+        let mut header = [0; HEADER_LEN];
+        header[0..4].copy_from_slice(&MAGIC);
+        header[4..6].copy_from_slice(&VERSION.to_le_bytes());
+        header[6..8].copy_from_slice(&(F::CHAN_COUNT as u16).to_le_bytes());
+        header[8] = sample_kind::<F::Chan>();
+        header[12..20].copy_from_slice(&self.sample_rate().to_le_bytes());
+        header[20..28].copy_from_slice(&(self.len() as u64).to_le_bytes());
+        writer.write_all(&header)?;
+        let chan = size_of::<F::Chan>();
+        let mut bytes = vec![0; size_of::<F>()];
+        for frame in self.iter() {
+            for (src, dst) in
+                frame.channels().iter().zip(bytes.chunks_mut(chan))
+            {
+                src.to_le(dst);
+            }
+            writer.write_all(&bytes)?;
+        }
+        Ok(())
+    }
+
+    /// Read a buffer in the `fon-raw` format (see
+    /// [`write_fonraw()`](Audio::write_fonraw)).
+    ///
+    /// Returns an error of kind `InvalidData` if the header is invalid, or
+    /// doesn't match the channel count and channel type of `F`.
+    pub fn read_fonraw<R: Read>(mut reader: R) -> Result<Self> {
+        let mut header = [0; HEADER_LEN];
+        reader.read_exact(&mut header)?;
+        if header[0..4] != MAGIC {
+            return Err(invalid("not fon-raw data"));
+        }
+        if u16::from_le_bytes([header[4], header[5]]) != VERSION {
+            return Err(invalid("unsupported fon-raw version"));
+        }
+        let channels = u16::from_le_bytes([header[6], header[7]]);
+        if usize::from(channels) != F::CHAN_COUNT {
+            return Err(invalid("fon-raw channel count doesn't match"));
+        }
+        if header[8] != sample_kind::<F::Chan>() {
+            return Err(invalid("fon-raw sample kind doesn't match"));
+        }
+        let s_rate = f64::from_le_bytes(header[12..20].try_into().unwrap());
+        if !(s_rate.is_finite() && s_rate > 0.0) {
+            return Err(invalid("invalid fon-raw sample rate"));
+        }
+        let len = u64::from_le_bytes(header[20..28].try_into().unwrap());
+        // Don't trust the frame count for allocating.
+        let mut bytes = Vec::new();
+        let size = len.saturating_mul(size_of::<F>() as u64);
+        reader.take(size).read_to_end(&mut bytes)?;
+        if (bytes.len() as u64) < size {
+            return Err(Error::from(ErrorKind::UnexpectedEof));
+        }
+        let chan = size_of::<F::Chan>();
+        let mut chans = vec![F::Chan::default(); F::CHAN_COUNT];
+        let mut audio = Self::with_capacity(s_rate, len as usize);
+        for frame in bytes.chunks_exact(size_of::<F>()) {
+            for (dst, src) in chans.iter_mut().zip(frame.chunks_exact(chan)) {
+                *dst = F::Chan::from_le(src);
+            }
+            audio.push(F::from_channels(&chans));
+        }
+        Ok(audio)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mono::Mono16,
+        stereo::{Stereo24, Stereo32},
+        Audio,
+    };
+
+    #[test]
+    fn fonraw() {
+        let samples: Vec<f32> = (0..20).map(|i| i as f32 / 20.0).collect();
+        let audio = Audio::<Stereo32>::with_f32_buffer(44_100, samples);
+        let mut bytes = Vec::new();
+        audio.write_fonraw(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 28 + 20 * 4);
+        assert_eq!(&bytes[..4], b"FonR");
+        let read = Audio::<Stereo32>::read_fonraw(&bytes[..]).unwrap();
+        assert_eq!(read.sample_rate(), 44_100.0);
+        assert!(read.iter().eq(audio.iter()));
+
+        // Mismatched formats and truncated data are errors.
+        assert!(Audio::<Mono16>::read_fonraw(&bytes[..]).is_err());
+        assert!(Audio::<Stereo24>::read_fonraw(&bytes[..]).is_err());
+        assert!(Audio::<Stereo32>::read_fonraw(&bytes[..40]).is_err());
+        bytes[0] = b'X';
+        assert!(Audio::<Stereo32>::read_fonraw(&bytes[..]).is_err());
+    }
+}
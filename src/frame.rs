@@ -10,9 +10,21 @@
 
 //! Sample types
 
-use crate::{chan::Channel, mono::Mono, stereo::Stereo, surround::Surround};
+use crate::{
+    chan::{Channel, ClipPolicy},
+    matrix::DownmixMatrix,
+    mono::Mono,
+    private::Sealed,
+    stereo::Stereo,
+    surround::Surround,
+    surround61::Surround61,
+    surround71::Surround71,
+    ChannelOrder,
+};
+use alloc::{vec, vec::Vec};
 use core::{
     any::TypeId,
+    f64::consts::FRAC_1_SQRT_2,
     fmt::Debug,
     mem::size_of,
     ops::{
@@ -60,6 +72,36 @@ pub trait Frame:
     /// Get the channels mutably.
     fn channels_mut(&mut self) -> &mut [Self::Chan];
 
+    /// Get a channel, or `None` if `index` is out of range.
+    #[inline(always)]
+    fn channel(&self, index: usize) -> Option<&Self::Chan> {
+        self.channels().get(index)
+    }
+
+    /// Get a channel mutably, or `None` if `index` is out of range.
+    #[inline(always)]
+    fn channel_mut(&mut self, index: usize) -> Option<&mut Self::Chan> {
+        self.channels_mut().get_mut(index)
+    }
+
+    /// Invert the polarity of one channel, leaving the others as they are.
+    ///
+    /// # Panics
+    /// If `index` is out of range.
+    #[inline(always)]
+    fn invert_channel(mut self, index: usize) -> Self {
+        let chan = &mut self.channels_mut()[index];
+        *chan = -*chan;
+        self
+    }
+
+    /// Check if every channel is within `threshold` (linear amplitude) of
+    /// silence (see [`Channel::is_silent()`]).
+    #[inline(always)]
+    fn is_silent(&self, threshold: f64) -> bool {
+        self.channels().iter().all(|chan| chan.is_silent(threshold))
+    }
+
     /// Make an audio frame with all channels set from a floating point value.
     fn from_f64(value: f64) -> Self {
         let mut ret = Self::default();
@@ -81,12 +123,59 @@ pub trait Frame:
     /// Make an audio frame from a slice of channels.
     fn from_channels(ch: &[Self::Chan]) -> Self;
 
+    /// Make an audio frame by calling `f` with the index of each channel.
+    #[inline(always)]
+    fn from_fn<G: FnMut(usize) -> Self::Chan>(mut f: G) -> Self {
+        let mut ret = Self::default();
+        for (i, chan) in ret.channels_mut().iter_mut().enumerate() {
+            *chan = f(i);
+        }
+        ret
+    }
+
+    /// Make an audio frame by applying `f` to each channel.
+    #[inline(always)]
+    fn map_channels<G>(mut self, mut f: G) -> Self
+    where
+        G: FnMut(Self::Chan) -> Self::Chan,
+    {
+        for chan in self.channels_mut() {
+            *chan = f(*chan);
+        }
+        self
+    }
+
+    /// Make an audio frame by applying `f` to each pair of matching channels
+    /// from this frame and `other`.
+    #[inline(always)]
+    fn zip_map_channels<G>(mut self, other: Self, mut f: G) -> Self
+    where
+        G: FnMut(Self::Chan, Self::Chan) -> Self::Chan,
+    {
+        for (chan, other) in
+            self.channels_mut().iter_mut().zip(other.channels())
+        {
+            *chan = f(*chan, *other);
+        }
+        self
+    }
+
+    /// Combine all of the channels into one value, starting from `init`.
+    #[inline(always)]
+    fn fold_channels<T, G>(&self, init: T, f: G) -> T
+    where
+        G: FnMut(T, Self::Chan) -> T,
+    {
+        self.channels().iter().cloned().fold(init, f)
+    }
+
     /// Make an audio frame from a mono frame.
     fn from_mono(frame: Mono<Self::Chan>) -> Self {
         Self::from_channel(frame.channels()[0])
     }
 
-    /// Linear interpolation.
+    /// Linear interpolation, with a separate interpolation amount for each
+    /// channel.
     #[inline(always)]
     fn lerp(&self, rhs: Self, t: Self) -> Self {
         let mut out = Self::default();
@@ -98,6 +187,24 @@ pub trait Frame:
         out
     }
 
+    /// Linear interpolation, where `t` goes from 0 (`self`) to 1 (`rhs`).
+    #[inline(always)]
+    fn lerp_f32(&self, rhs: Self, t: f32) -> Self {
+        let mut out = Self::default();
+        let main = out.channels_mut().iter_mut().zip(self.channels().iter());
+        for ((out, this), rhs) in main.zip(rhs.channels().iter()) {
+            *out = this.lerp_f32(*rhs, t);
+        }
+        out
+    }
+
+    /// Change the level of every channel by `db` decibels (saturating).
+    #[inline(always)]
+    fn gain_db(mut self, db: f64) -> Self {
+        gain(&mut self, crate::chan::db_to_gain(db));
+        self
+    }
+
     /// Convert a sample to another format.
     #[inline(always)]
     fn convert<D: Frame>(self) -> D {
@@ -108,9 +215,13 @@ pub trait Frame:
                     || (a == TypeId::of::<Stereo<Self::Chan>>()
                         && b == TypeId::of::<Stereo<D::Chan>>())
                     || (a == TypeId::of::<Surround<Self::Chan>>()
-                        && b == TypeId::of::<Surround<D::Chan>>()) =>
+                        && b == TypeId::of::<Surround<D::Chan>>())
+                    || (a == TypeId::of::<Surround61<Self::Chan>>()
+                        && b == TypeId::of::<Surround61<D::Chan>>())
+                    || (a == TypeId::of::<Surround71<Self::Chan>>()
+                        && b == TypeId::of::<Surround71<D::Chan>>()) =>
             {
-                let mut out = [D::Chan::MID; 6];
+                let mut out = [D::Chan::MID; 8];
                 // Same type, 1:1
                 for (src, dst) in self.channels().iter().zip(out.iter_mut()) {
                     *dst = D::Chan::from_f64(src.to_f64());
@@ -188,12 +299,221 @@ pub trait Frame:
                 //
                 D::from_channels(&out)
             }
+            (a, b)
+                if (a == TypeId::of::<Surround61<Self::Chan>>()
+                    && b == TypeId::of::<Surround71<D::Chan>>()) =>
+            {
+                let mut out = [D::Chan::MID; 8];
+                // 6.1 -> 7.1, Back Center Split Between The Back Pair
+                let chans = self.channels();
+                let back = chans[5].to_f64() * FRAC_1_SQRT_2;
+                out[0] = D::Chan::from_f64(back);
+                for (src, dst) in chans[..5].iter().zip(out[1..6].iter_mut()) {
+                    *dst = D::Chan::from_f64(src.to_f64());
+                }
+                out[6] = D::Chan::from_f64(back);
+                out[7] = D::Chan::from_f64(chans[6].to_f64());
+                //
+                D::from_channels(&out)
+            }
+            (a, b)
+                if (a == TypeId::of::<Surround71<Self::Chan>>()
+                    && b == TypeId::of::<Surround61<D::Chan>>()) =>
+            {
+                let mut out = [D::Chan::MID; 7];
+                // 7.1 -> 6.1, Back Pair Mixed Into The Back Center
+                let chans = self.channels();
+                for (src, dst) in chans[1..6].iter().zip(out[..5].iter_mut()) {
+                    *dst = D::Chan::from_f64(src.to_f64());
+                }
+                out[5] = D::Chan::from_f64(
+                    (chans[0].to_f64() + chans[6].to_f64()) * FRAC_1_SQRT_2,
+                );
+                out[6] = D::Chan::from_f64(chans[7].to_f64());
+                //
+                D::from_channels(&out)
+            }
             _ => panic!(
                 "Cannot convert custom speaker configurations, \
                 implement custom Frame::convert() method to override."
             ),
         }
     }
+
+    /// Rotate the speaker channels (not including LFE) `n` speakers to the
+    /// right (negative `n` rotates to the left), following the order of
+    /// [`CONFIG`](Frame::CONFIG).
+    #[inline(always)]
+    fn rotate_channels(mut self, n: isize) -> Self {
+        let speakers = Self::CONFIG.len().min(Self::CHAN_COUNT);
+        if speakers != 0 {
+            let n = n.rem_euclid(speakers as isize) as usize;
+            self.channels_mut()[..speakers].rotate_right(n);
+        }
+        self
+    }
+
+    /// Mirror left and right, swapping each speaker channel with the one at
+    /// the opposite position in [`CONFIG`](Frame::CONFIG).  Centered channels
+    /// and LFE are kept in place.
+    #[inline(always)]
+    fn mirror(self) -> Self {
+        let mut out = self;
+        for (i, position) in Self::CONFIG.iter().enumerate() {
+            let opposite = Self::CONFIG
+                .iter()
+                .position(|other| (other + position).abs() < 1e-9);
+            if let Some(j) = opposite {
+                out.channels_mut()[j] = self.channels()[i];
+            }
+        }
+        out
+    }
+
+    /// Reorder the channels, so that channel `i` of the result is channel
+    /// `order[i]` of this frame.
+    ///
+    /// # Panics
+    /// If the length of `order` isn't the channel count, or if an index in
+    /// it is out of bounds.
+    #[inline(always)]
+    fn reorder_channels(self, order: &[usize]) -> Self {
+        assert_eq!(order.len(), Self::CHAN_COUNT, "Wrong number of channels");
+        let mut out = self;
+        for (chan, &index) in out.channels_mut().iter_mut().zip(order) {
+            *chan = self.channels()[index];
+        }
+        out
+    }
+
+    /// Swap two channels.
+    ///
+    /// # Panics
+    /// If either channel is out of bounds.
+    #[inline(always)]
+    fn swap_channels(mut self, a: usize, b: usize) -> Self {
+        self.channels_mut().swap(a, b);
+        self
+    }
+
+    /// Convert the frame from one channel order to another (such as when
+    /// reading or writing a file format with a different order).
+    ///
+    /// # Panics
+    /// If either order isn't defined for the channel count (see
+    /// [`ChannelOrder::indices()`]).
+    #[inline(always)]
+    fn convert_channel_order(
+        self,
+        from: ChannelOrder,
+        to: ChannelOrder,
+    ) -> Self {
+        let mut out = self;
+        let order = from.reorder(to, Self::CHAN_COUNT);
+        for (chan, index) in out.channels_mut().iter_mut().zip(order) {
+            *chan = self.channels()[index];
+        }
+        out
+    }
+
+    /// Encode the frame as interleaved little endian bytes.
+    fn to_le_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0; size_of::<Self>()];
+        let chan = size_of::<Self::Chan>();
+        for (dst, src) in bytes.chunks_exact_mut(chan).zip(self.channels()) {
+            src.to_le(dst);
+        }
+        bytes
+    }
+
+    /// Encode the frame as interleaved big endian bytes.
+    fn to_be_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0; size_of::<Self>()];
+        let chan = size_of::<Self::Chan>();
+        for (dst, src) in bytes.chunks_exact_mut(chan).zip(self.channels()) {
+            src.to_be(dst);
+        }
+        bytes
+    }
+
+    /// Decode a frame from interleaved little endian bytes.  Integer channels
+    /// are clamped to their range.
+    ///
+    /// # Panics
+    /// If there are fewer bytes than the size of the frame.
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        assert!(
+            bytes.len() >= size_of::<Self>(),
+            "Not enough bytes for frame"
+        );
+        let mut out = Self::default();
+        let chan = size_of::<Self::Chan>();
+        for (dst, src) in out.channels_mut().iter_mut().zip(bytes.chunks(chan))
+        {
+            *dst = Self::Chan::from_le(src);
+        }
+        out
+    }
+
+    /// Decode a frame from interleaved big endian bytes.  Integer channels
+    /// are clamped to their range.
+    ///
+    /// # Panics
+    /// If there are fewer bytes than the size of the frame.
+    fn from_be_bytes(bytes: &[u8]) -> Self {
+        assert!(
+            bytes.len() >= size_of::<Self>(),
+            "Not enough bytes for frame"
+        );
+        let mut out = Self::default();
+        let chan = size_of::<Self::Chan>();
+        for (dst, src) in out.channels_mut().iter_mut().zip(bytes.chunks(chan))
+        {
+            *dst = Self::Chan::from_be(src);
+        }
+        out
+    }
+
+    /// Convert a frame to another format, mixing the channels with custom
+    /// coefficients.
+    ///
+    /// # Panics
+    /// If `FROM` or `TO` don't match the channel counts of the frames.
+    #[inline(always)]
+    fn convert_matrix<D: Frame, const FROM: usize, const TO: usize>(
+        self,
+        matrix: &DownmixMatrix<FROM, TO>,
+    ) -> D {
+        assert_eq!(FROM, Self::CHAN_COUNT, "Wrong matrix input channel count");
+        assert_eq!(TO, D::CHAN_COUNT, "Wrong matrix output channel count");
+        let mut input = [0.0; FROM];
+        for (dst, src) in input.iter_mut().zip(self.channels()) {
+            *dst = src.to_f64();
+        }
+        let mut output = [0.0; TO];
+        matrix.apply(&input, &mut output);
+        let mut out = D::default();
+        for (dst, src) in out.channels_mut().iter_mut().zip(output.iter()) {
+            *dst = D::Chan::from_f64(*src);
+        }
+        out
+    }
+
+    /// Convert a sample to another format, handling out of range channel
+    /// values according to a [`ClipPolicy`](crate::chan::ClipPolicy).
+    #[inline(always)]
+    fn convert_with<D: Frame>(self, policy: ClipPolicy) -> D {
+        if policy == ClipPolicy::DebugPanic {
+            for chan in self.channels() {
+                policy.clip::<D::Chan>(chan.to_f64());
+            }
+        }
+        let mut out: D = self.convert();
+        for chan in out.channels_mut() {
+            *chan = D::Chan::from_f64_clipped(chan.to_f64(), policy);
+        }
+        out
+    }
 }
 
 impl<T: Frame> crate::Stream<T> for T {
@@ -210,3 +530,206 @@ impl<T: Frame> crate::Stream<T> for T {
     #[inline(always)]
     fn set_sample_rate<R: Into<f64>>(&mut self, _: R) {}
 }
+
+// Multiply every channel of a frame by a gain factor (saturating), shared by
+// the `MulAssign<f32>` implementations of the frame types.
+#[inline(always)]
+pub(crate) fn gain<F: Frame>(frame: &mut F, gain: f64) {
+    for chan in frame.channels_mut() {
+        let value = chan.to_f64() * gain;
+        *chan = F::Chan::from_f64_clipped(value, ClipPolicy::Saturate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chan::{Ch12, Ch16, Ch20, Ch32, Ch64},
+        mono::{Mono12, Mono20},
+        stereo::{Stereo16, Stereo32},
+        surround::Surround32,
+    };
+
+    #[test]
+    fn array_tuple() {
+        let frame = Stereo32::new(0.25, -0.5);
+        let array: [Ch32; 2] = frame.into();
+        assert_eq!(Stereo32::from(array), frame);
+        let (l, r) = frame.into();
+        assert_eq!((l, r), (Ch32::new(0.25), Ch32::new(-0.5)));
+        assert_eq!(Stereo32::from((l, r)), frame);
+        let frame = Surround32::from_fn(|i| Ch32::new(i as f32 / 10.0));
+        assert_eq!(frame, Surround32::new(0.0, 0.1, 0.2, 0.3, 0.4, 0.5));
+        let [.., lfe]: [Ch32; 6] = frame.into();
+        assert_eq!(lfe, Ch32::new(0.5));
+        let mono = Mono12::from([Ch12::new(7)]);
+        assert_eq!(<(Ch12,)>::from(mono), (Ch12::new(7),));
+    }
+
+    #[test]
+    fn combinators() {
+        let a = Stereo32::new(0.25, -0.5);
+        let b = Stereo32::new(0.5, 0.5);
+        assert_eq!(a.map_channels(|c| -c), Stereo32::new(-0.25, 0.5));
+        assert_eq!(
+            a.zip_map_channels(b, |a, b| a + b),
+            Stereo32::new(0.75, 0.0)
+        );
+        let peak = a.fold_channels(0.0, |peak, c| f32::from(c).abs().max(peak));
+        assert_eq!(peak, 0.5);
+    }
+
+    #[test]
+    fn channel_index() {
+        let mut frame = Stereo32::new(0.25, -0.5);
+        assert_eq!(frame.channel(1), Some(&Ch32::new(-0.5)));
+        assert_eq!(frame.channel(2), None);
+        *frame.channel_mut(0).unwrap() = Ch32::new(1.0);
+        assert_eq!(frame[0], Ch32::new(1.0));
+        frame[1] = Ch32::new(0.0);
+        assert_eq!(frame, Stereo32::new(1.0, 0.0));
+        assert!(frame.channel_mut(2).is_none());
+    }
+
+    #[test]
+    fn invert() {
+        let frame = Stereo32::new(0.25, -0.5);
+        assert_eq!(frame.invert_channel(1), Stereo32::new(0.25, 0.5));
+        assert_eq!(frame.invert_channel(0).invert_channel(1), -frame);
+    }
+
+    #[test]
+    fn rotate_mirror() {
+        let frame = Surround32::new(0.1, 0.2, 0.3, 0.4, 0.5, 0.6);
+        assert_eq!(
+            frame.mirror(),
+            Surround32::new(0.5, 0.4, 0.3, 0.2, 0.1, 0.6)
+        );
+        assert_eq!(
+            frame.rotate_channels(1),
+            Surround32::new(0.5, 0.1, 0.2, 0.3, 0.4, 0.6)
+        );
+        assert_eq!(frame.rotate_channels(-6), frame.rotate_channels(-1));
+        assert_eq!(frame.rotate_channels(5), frame);
+        let frame = Stereo32::new(0.25, -0.5);
+        assert_eq!(frame.mirror(), Stereo32::new(-0.5, 0.25));
+        assert_eq!(frame.rotate_channels(1), frame.mirror());
+    }
+
+    #[test]
+    fn reorder() {
+        let frame = Surround32::new(0.1, 0.2, 0.3, 0.4, 0.5, 0.6);
+        assert_eq!(
+            frame.reorder_channels(&[1, 3, 2, 5, 0, 4]),
+            Surround32::new(0.2, 0.4, 0.3, 0.6, 0.1, 0.5)
+        );
+        assert_eq!(
+            frame.swap_channels(2, 5),
+            Surround32::new(0.1, 0.2, 0.6, 0.4, 0.5, 0.3)
+        );
+        let frame = Stereo32::new(0.25, -0.5);
+        assert_eq!(frame.reorder_channels(&[0, 0]), Stereo32::new(0.25, 0.25));
+    }
+
+    #[test]
+    fn frame_bytes() {
+        let frame = Stereo16::new(0x0102, -2);
+        assert_eq!(frame.to_le_bytes(), [0x02, 0x01, 0xFE, 0xFF]);
+        assert_eq!(frame.to_be_bytes(), [0x01, 0x02, 0xFF, 0xFE]);
+        assert_eq!(Stereo16::from_le_bytes(&frame.to_le_bytes()), frame);
+        assert_eq!(Stereo16::from_be_bytes(&frame.to_be_bytes()), frame);
+
+        let frame = Stereo32::new(0.5, -0.25);
+        assert_eq!(Stereo32::from_be_bytes(&frame.to_be_bytes()), frame);
+        assert_eq!(&frame.to_le_bytes()[..4], &0.5f32.to_le_bytes());
+
+        // Out of range values are clamped.
+        let bytes = i16::MAX.to_le_bytes();
+        assert_eq!(Mono12::from_le_bytes(&bytes), Mono12::new(Ch12::new(2047)));
+        let bytes = i32::MIN.to_be_bytes();
+        assert_eq!(
+            Mono20::from_be_bytes(&bytes),
+            Mono20::new(Ch20::new(-524_288))
+        );
+    }
+
+    // Get the position of each channel with any signal, and its power.
+    fn speakers<F: Frame>(frame: F) -> Vec<(Option<f64>, f64)> {
+        let chans = frame.channels().iter().enumerate();
+        chans
+            .filter(|(_, chan)| chan.to_f64() != 0.0)
+            .map(|(i, chan)| (F::CONFIG.get(i).cloned(), chan.to_f64().powi(2)))
+            .collect()
+    }
+
+    #[test]
+    fn surround_61_71() {
+        type S61 = Surround61<Ch64>;
+        type S71 = Surround71<Ch64>;
+        let impulse = |i| move |j| Ch64::new(if i == j { 0.5 } else { 0.0 });
+
+        // Speakers at the same position in both layouts (and the LFE) are
+        // kept as they are, in either direction.
+        for i in 0..7 {
+            let back = S61::CONFIG.get(i) == Some(&1.0);
+            let out: S71 = S61::from_fn(impulse(i)).convert();
+            let position = S61::CONFIG.get(i).cloned();
+            if !back {
+                assert_eq!(speakers(out), [(position, 0.25)], "{}", i);
+                assert_eq!(out.convert::<S61>(), S61::from_fn(impulse(i)));
+            }
+        }
+        for i in [1, 2, 3, 4, 5, 7] {
+            let out: S61 = S71::from_fn(impulse(i)).convert();
+            let position = S71::CONFIG.get(i).cloned();
+            assert_eq!(speakers(out), [(position, 0.25)], "{}", i);
+        }
+
+        // The back center is split between the back pair with equal power,
+        // and nothing leaks into the sides.
+        let back = S61::new(0.0, 0.0, 0.0, 0.0, 0.0, 0.5, 0.0);
+        let out: S71 = back.convert();
+        let split = speakers(out);
+        assert_eq!(split.len(), 2);
+        assert_eq!(split[0].0, Some(-5.0 / 6.0));
+        assert_eq!(split[1].0, Some(5.0 / 6.0));
+        assert!((split[0].1 - 0.125).abs() < 1e-12);
+        assert!((split[0].1 - split[1].1).abs() < 1e-12);
+
+        // Each back speaker lands on the back center at -3 dB, and a phantom
+        // back center comes back at its original level.
+        for i in [0, 6] {
+            let out: S61 = S71::from_fn(impulse(i)).convert();
+            let center = speakers(out);
+            assert_eq!(center.len(), 1);
+            assert_eq!(center[0].0, Some(1.0));
+            assert!((center[0].1 - 0.125).abs() < 1e-12);
+        }
+        let trip: S61 = out.convert();
+        assert!((trip.channels()[5].to_f64() - 0.5).abs() < 1e-12);
+
+        // Left and right are mapped symmetrically.
+        let frame = S71::new(0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8);
+        let mapped: S61 = frame.mirror().convert();
+        assert_eq!(mapped, frame.convert::<S61>().mirror());
+        let frame = S61::new(0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7);
+        let mapped: S71 = frame.mirror().convert();
+        assert_eq!(mapped, frame.convert::<S71>().mirror());
+        assert_eq!(S71::from(frame), frame.convert());
+
+        // The default matrices match, and integer channels saturate.
+        let down: S61 = S71::from(frame).convert();
+        assert_eq!(
+            S71::from(frame).convert_matrix::<S61, 8, 7>(&Default::default()),
+            down
+        );
+        assert_eq!(
+            frame.convert_matrix::<S71, 7, 8>(&Default::default()),
+            frame.convert()
+        );
+        let loud = Surround71::<Ch16>::from_fn(|_| Ch16::MAX);
+        let out: Surround61<Ch16> = loud.convert();
+        assert_eq!(out.channels()[5], Ch16::MAX);
+    }
+}
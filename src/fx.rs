@@ -0,0 +1,445 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Audio effects.
+
+pub mod presets;
+
+use crate::{
+    chan::{Channel, ClipPolicy},
+    Audio, Frame,
+};
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::{
+    f64::consts::PI,
+    sync::atomic::{AtomicU8, Ordering},
+    time::Duration,
+};
+
+// Crate-level denormal policy, as a `DenormalPolicy` discriminant.
+static DENORMAL_POLICY: AtomicU8 =
+    AtomicU8::new(DenormalPolicy::FlushToZero as u8);
+
+/// How IIR-based effects (such as [`Biquad`](Biquad)) keep denormal floats
+/// out of their feedback state.  Decaying filter and reverb tails eventually
+/// become denormal, which is very slow on some CPUs (notably x86).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DenormalPolicy {
+    /// Don't protect against denormals.
+    Ignore,
+    /// Flush feedback state smaller than 1e-30 to zero (the default).
+    #[default]
+    FlushToZero,
+    /// Add an inaudible DC offset of 1e-24 to the feedback state, so it never
+    /// decays to a denormal.
+    DcOffset,
+}
+
+impl DenormalPolicy {
+    /// Apply the policy to one value of feedback state.
+    #[inline(always)]
+    pub fn apply(self, value: f64) -> f64 {
+        match self {
+            DenormalPolicy::Ignore => value,
+            DenormalPolicy::FlushToZero if value.abs() < 1e-30 => 0.0,
+            DenormalPolicy::FlushToZero => value,
+            DenormalPolicy::DcOffset => value + 1e-24,
+        }
+    }
+}
+
+/// Set the denormal policy used by the IIR-based effects in this crate.
+pub fn set_denormal_policy(policy: DenormalPolicy) {
+    DENORMAL_POLICY.store(policy as u8, Ordering::Relaxed);
+}
+
+/// Get the denormal policy used by the IIR-based effects in this crate.
+pub fn denormal_policy() -> DenormalPolicy {
+    match DENORMAL_POLICY.load(Ordering::Relaxed) {
+        0 => DenormalPolicy::Ignore,
+        1 => DenormalPolicy::FlushToZero,
+        _ => DenormalPolicy::DcOffset,
+    }
+}
+
+/// An audio effect, processing one frame at a time.
+pub trait Effect<F: Frame> {
+    /// Process one frame.
+    fn process(&mut self, frame: F) -> F;
+
+    /// Process every frame of an `Audio` buffer.
+    fn process_audio(&mut self, audio: &mut Audio<F>) {
+        for frame in audio.iter_mut() {
+            *frame = self.process(*frame);
+        }
+    }
+}
+
+/// Marker for processors that never allocate, lock or block while
+/// processing (in [`Effect::process()`] or [`Meter::feed()`]), so they are
+/// safe to use in a real-time audio callback.
+///
+/// [`Chain`] isn't `RtSafe`, because the effects it holds might not be.
+///
+/// ```rust
+/// use fon::fx::{Biquad, Effect, RtSafe};
+/// use fon::mono::Mono32;
+///
+/// // Only accept effects that are safe to run in the audio callback.
+/// fn callback<E>(effect: &mut E, out: &mut [Mono32])
+/// where
+///     E: Effect<Mono32> + RtSafe,
+/// {
+///     for frame in out.iter_mut() {
+///         *frame = effect.process(*frame);
+///     }
+/// }
+///
+/// let mut lowpass = Biquad::lowpass(48_000.0, 1_000.0, 0.707);
+/// callback(&mut lowpass, &mut [Mono32::new(0.5); 64]);
+/// ```
+///
+/// [`Meter::feed()`]: crate::Meter::feed
+pub trait RtSafe {}
+
+/// Statistics for one block of audio processed by a [`Chain`], passed to
+/// its [observer](Chain::observe).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BlockStats {
+    /// Number of frames in the block.
+    pub frames: usize,
+    /// Largest absolute channel value of the processed block.
+    pub peak: f64,
+    /// Time taken to process the block (only measured with the `std`
+    /// feature).
+    pub elapsed: Option<Duration>,
+}
+
+// Observer of the blocks processed by a chain.
+type Observer = Box<dyn FnMut(&BlockStats)>;
+
+/// A chain of effects, applied in order.
+#[allow(missing_debug_implementations)]
+pub struct Chain<F: Frame> {
+    effects: Vec<Box<dyn Effect<F>>>,
+    observer: Option<Observer>,
+}
+
+impl<F: Frame> Default for Chain<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Frame> Chain<F> {
+    /// Create an empty chain.
+    pub fn new() -> Self {
+        Self {
+            effects: Vec::new(),
+            observer: None,
+        }
+    }
+
+    /// Call `observer` with the statistics of every block processed with
+    /// [`process_audio()`](Effect::process_audio), for monitoring
+    /// performance and levels without wrapping each effect.
+    pub fn observe<O: FnMut(&BlockStats) + 'static>(
+        mut self,
+        observer: O,
+    ) -> Self {
+        self.observer = Some(Box::new(observer));
+        self
+    }
+
+    /// Add an effect to the end of the chain.
+    pub fn with<E: Effect<F> + 'static>(mut self, effect: E) -> Self {
+        self.effects.push(Box::new(effect));
+        self
+    }
+
+    /// Get the number of effects in the chain.
+    pub fn len(&self) -> usize {
+        self.effects.len()
+    }
+
+    /// Check if the chain has no effects.
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+}
+
+impl<F: Frame> Effect<F> for Chain<F> {
+    #[inline(always)]
+    fn process(&mut self, frame: F) -> F {
+        self.effects
+            .iter_mut()
+            .fold(frame, |frame, effect| effect.process(frame))
+    }
+
+    fn process_audio(&mut self, audio: &mut Audio<F>) {
+        #[cfg(feature = "std")]
+        let start = std::time::Instant::now();
+        for frame in audio.iter_mut() {
+            *frame = self.process(*frame);
+        }
+        if let Some(observer) = &mut self.observer {
+            #[cfg(feature = "std")]
+            let elapsed = Some(start.elapsed());
+            #[cfg(not(feature = "std"))]
+            let elapsed = None;
+            let peak = audio
+                .iter()
+                .flat_map(|frame| frame.channels().iter())
+                .map(|chan| chan.to_f64().abs())
+                .fold(0.0, f64::max);
+            observer(&BlockStats {
+                frames: audio.len(),
+                peak,
+                elapsed,
+            });
+        }
+    }
+}
+
+/// Second order IIR filter (biquad), with the coefficients from the Audio EQ
+/// Cookbook.
+#[derive(Clone, Debug)]
+pub struct Biquad<F: Frame> {
+    // Normalized feedforward (b0, b1, b2) and feedback (a1, a2) coefficients.
+    b: [f64; 3],
+    a: [f64; 2],
+    // Previous two inputs and outputs for each channel.
+    state: Vec<[f64; 4]>,
+    _frame: core::marker::PhantomData<F>,
+}
+
+impl<F: Frame> Biquad<F> {
+    /// Create a lowpass filter, with a cutoff frequency in hertz and a Q
+    /// (resonance) of `q` (`FRAC_1_SQRT_2` for Butterworth).
+    pub fn lowpass(s_rate: f64, freq: f64, q: f64) -> Self {
+        let (cos, alpha) = Self::omega(s_rate, freq, q);
+        let b1 = 1.0 - cos;
+        Self::new(
+            [b1 / 2.0, b1, b1 / 2.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    /// Create a highpass filter, with a cutoff frequency in hertz and a Q
+    /// (resonance) of `q` (`FRAC_1_SQRT_2` for Butterworth).
+    pub fn highpass(s_rate: f64, freq: f64, q: f64) -> Self {
+        let (cos, alpha) = Self::omega(s_rate, freq, q);
+        let b1 = 1.0 + cos;
+        Self::new(
+            [b1 / 2.0, -b1, b1 / 2.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    /// Create a bandpass filter (0 dB peak gain), with a center frequency in
+    /// hertz and a Q (bandwidth) of `q`.
+    pub fn bandpass(s_rate: f64, freq: f64, q: f64) -> Self {
+        let (cos, alpha) = Self::omega(s_rate, freq, q);
+        Self::new([alpha, 0.0, -alpha], [1.0 + alpha, -2.0 * cos, 1.0 - alpha])
+    }
+
+    fn omega(s_rate: f64, freq: f64, q: f64) -> (f64, f64) {
+        let omega = 2.0 * PI * freq / s_rate;
+        (omega.cos(), omega.sin() / (2.0 * q))
+    }
+
+    fn new(b: [f64; 3], a: [f64; 3]) -> Self {
+        Self {
+            b: [b[0] / a[0], b[1] / a[0], b[2] / a[0]],
+            a: [a[1] / a[0], a[2] / a[0]],
+            state: vec![[0.0; 4]; F::CHAN_COUNT],
+            _frame: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<F: Frame> RtSafe for Biquad<F> {}
+
+impl<F: Frame> Effect<F> for Biquad<F> {
+    #[inline(always)]
+    fn process(&mut self, mut frame: F) -> F {
+        let (b, a) = (self.b, self.a);
+        let policy = denormal_policy();
+        for (chan, state) in
+            frame.channels_mut().iter_mut().zip(&mut self.state)
+        {
+            let [x1, x2, y1, y2] = *state;
+            let x = chan.to_f64();
+            let y = b[0] * x + b[1] * x1 + b[2] * x2 - a[0] * y1 - a[1] * y2;
+            let y = policy.apply(y);
+            *state = [x, x1, y, y1];
+            *chan = F::Chan::from_f64_clipped(y, ClipPolicy::Saturate);
+        }
+        frame
+    }
+}
+
+/// Bitcrusher effect: bit depth reduction, and sample rate reduction by
+/// holding each frame for a number of frames (sample-and-hold).
+///
+/// Bit depth is reduced with the same mapping that
+/// [`Channel::from_f64()`](crate::chan::Channel::from_f64) uses for the
+/// integer channel types, so the minimum and maximum are kept despite their
+/// asymmetric ranges.
+#[derive(Clone, Debug)]
+pub struct Bitcrush<F: Frame> {
+    // Half the number of quantization levels, minus a half.
+    steps: f64,
+    hold: usize,
+    count: usize,
+    held: F,
+}
+
+impl<F: Frame> Bitcrush<F> {
+    /// Create a new bitcrusher, reducing to `bits` bits (clamped to 1 thru 32)
+    /// and holding each frame for `hold` frames (at least 1).
+    pub fn new(bits: u32, hold: usize) -> Self {
+        let bits = bits.clamp(1, 32) as i32;
+        Self {
+            steps: 2.0f64.powi(bits - 1) - 0.5,
+            hold: hold.max(1),
+            count: 0,
+            held: F::default(),
+        }
+    }
+
+    /// Get the number of frames each frame is held for.
+    pub fn hold(&self) -> usize {
+        self.hold
+    }
+
+    /// Start a new hold on the next frame.
+    pub fn reset(&mut self) {
+        self.count = 0;
+    }
+}
+
+impl<F: Frame> RtSafe for Bitcrush<F> {}
+
+impl<F: Frame> Effect<F> for Bitcrush<F> {
+    #[inline(always)]
+    fn process(&mut self, frame: F) -> F {
+        if self.count == 0 {
+            let steps = self.steps;
+            self.held = frame;
+            for chan in self.held.channels_mut() {
+                let value = (chan.to_f64() * steps).floor();
+                let value = value.clamp(-steps - 0.5, steps - 0.5) + 0.5;
+                *chan = F::Chan::from_f64(value / steps);
+            }
+        }
+        self.count = (self.count + 1) % self.hold;
+        self.held
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chan::{Ch16, Ch8},
+        mono::{Mono16, Mono32, Mono64},
+    };
+
+    #[test]
+    fn bitcrush_depth() {
+        let mut crush = Bitcrush::<Mono16>::new(8, 1);
+        let mut identity = Bitcrush::<Mono16>::new(16, 1);
+        for i in i16::MIN..=i16::MAX {
+            let frame = Mono16::new(Ch16::new(i));
+            let ch8 = Ch8::from_f64(Ch16::new(i).to_f64());
+            let via_ch8 = Ch16::from_f64(ch8.to_f64());
+            assert_eq!(crush.process(frame), Mono16::new(via_ch8));
+            assert_eq!(identity.process(frame), frame);
+        }
+        let mut crush = Bitcrush::<Mono32>::new(1, 1);
+        assert_eq!(crush.process(Mono32::new(0.1)), Mono32::new(1.0));
+        assert_eq!(crush.process(Mono32::new(-0.1)), Mono32::new(-1.0));
+    }
+
+    #[test]
+    fn observe() {
+        use alloc::rc::Rc;
+        use core::cell::RefCell;
+
+        let stats = Rc::new(RefCell::new(Vec::new()));
+        let observed = stats.clone();
+        let mut chain = Chain::<Mono32>::new()
+            .with(Bitcrush::new(32, 1))
+            .observe(move |block| observed.borrow_mut().push(*block));
+        let mut audio = Audio::with_frame(48_000, 64, Mono32::new(0.25));
+        chain.process_audio(&mut audio);
+        chain.process_audio(&mut Audio::with_silence(48_000, 32));
+        let stats = stats.borrow();
+        assert_eq!(stats.len(), 2);
+        assert_eq!((stats[0].frames, stats[1].frames), (64, 32));
+        assert_eq!(stats[0].peak, 0.25);
+        assert!(stats[1].peak < 1e-9);
+        #[cfg(feature = "std")]
+        assert!(stats[0].elapsed.is_some());
+        #[cfg(not(feature = "std"))]
+        assert!(stats[0].elapsed.is_none());
+    }
+
+    #[test]
+    fn bitcrush_hold() {
+        let samples: Vec<f32> = (0..6).map(|i| i as f32 / 8.0).collect();
+        let mut audio = Audio::<Mono32>::with_f32_buffer(48_000, samples);
+        Bitcrush::new(32, 3).process_audio(&mut audio);
+        let held: Vec<Mono32> = audio.iter().cloned().collect();
+        assert_eq!(held[0], held[2]);
+        assert_eq!(held[3], held[5]);
+        assert!((held[3].channels()[0].to_f64() - 0.375).abs() < 1e-6);
+    }
+
+    #[test]
+    fn biquad() {
+        let q = core::f64::consts::FRAC_1_SQRT_2;
+        // Lowpass passes DC, highpass blocks it.
+        let dc = Mono32::new(0.5);
+        let mut lowpass = Biquad::lowpass(48_000.0, 1_000.0, q);
+        let mut highpass = Biquad::highpass(48_000.0, 1_000.0, q);
+        let mut chain =
+            Chain::new().with(Biquad::lowpass(48_000.0, 1_000.0, q));
+        let (mut low, mut high, mut chained) = (dc, dc, dc);
+        for _ in 0..4800 {
+            low = lowpass.process(dc);
+            high = highpass.process(dc);
+            chained = chain.process(dc);
+        }
+        assert!((low.channels()[0].to_f64() - 0.5).abs() < 1e-4);
+        assert!(high.channels()[0].to_f64().abs() < 1e-4);
+        assert_eq!(low, chained);
+    }
+
+    #[test]
+    fn denormals() {
+        let tiny = f64::MIN_POSITIVE / 4.0;
+        assert_eq!(DenormalPolicy::Ignore.apply(tiny), tiny);
+        assert_eq!(DenormalPolicy::FlushToZero.apply(tiny), 0.0);
+        assert_eq!(DenormalPolicy::FlushToZero.apply(1e-20), 1e-20);
+        assert!(DenormalPolicy::DcOffset.apply(tiny).is_normal());
+        assert_eq!(denormal_policy(), DenormalPolicy::default());
+
+        // An impulse response tail is flushed to zero.
+        let q = core::f64::consts::FRAC_1_SQRT_2;
+        let mut lowpass = Biquad::<Mono64>::lowpass(48_000.0, 100.0, q);
+        lowpass.process(Mono64::new(1.0));
+        for _ in 0..48_000 {
+            lowpass.process(Mono64::new(0.0));
+        }
+        assert!(lowpass.state[0].iter().all(|value| *value == 0.0));
+    }
+}
@@ -0,0 +1,79 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Ready-made effect chains, simulating the sound of band limited playback.
+
+use super::{Biquad, Bitcrush, Chain};
+use crate::Frame;
+use core::f64::consts::FRAC_1_SQRT_2;
+
+/// Telephone: band limited to 300 thru 3400 hertz, at 8-bit depth.
+pub fn telephone<F: Frame>(s_rate: f64) -> Chain<F> {
+    Chain::new()
+        .with(Biquad::highpass(s_rate, 300.0, FRAC_1_SQRT_2))
+        .with(Biquad::highpass(s_rate, 300.0, FRAC_1_SQRT_2))
+        .with(Biquad::lowpass(s_rate, 3_400.0, FRAC_1_SQRT_2))
+        .with(Biquad::lowpass(s_rate, 3_400.0, FRAC_1_SQRT_2))
+        .with(Bitcrush::new(8, 1))
+}
+
+/// AM radio: band limited to 100 thru 5000 hertz, with a slight resonance
+/// near the top of the band.
+pub fn am_radio<F: Frame>(s_rate: f64) -> Chain<F> {
+    Chain::new()
+        .with(Biquad::highpass(s_rate, 100.0, FRAC_1_SQRT_2))
+        .with(Biquad::lowpass(s_rate, 5_000.0, 1.2))
+        .with(Biquad::lowpass(s_rate, 5_000.0, FRAC_1_SQRT_2))
+}
+
+/// Underwater: heavily muffled, with a resonant lowpass at 500 hertz.
+pub fn underwater<F: Frame>(s_rate: f64) -> Chain<F> {
+    Chain::new()
+        .with(Biquad::lowpass(s_rate, 500.0, 2.0))
+        .with(Biquad::lowpass(s_rate, 800.0, FRAC_1_SQRT_2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chan::{Ch64, Channel},
+        fx::{Chain, Effect},
+        mono::Mono64,
+        Audio,
+    };
+
+    // Gain of a chain for a sine wave, in decibels.
+    fn gain(mut chain: Chain<Mono64>, freq: f64) -> f64 {
+        let mut audio = Audio::<Mono64>::with_silence(48_000, 48_000);
+        for (i, frame) in audio.iter_mut().enumerate() {
+            let phase = 2.0 * core::f64::consts::PI * freq * i as f64;
+            *frame = Mono64::new(Ch64::new((phase / 48_000.0).sin() * 0.5));
+        }
+        chain.process_audio(&mut audio);
+        let rms = audio
+            .iter()
+            .skip(24_000)
+            .map(|f| f.channels()[0].to_f64().powi(2))
+            .sum::<f64>()
+            / 24_000.0;
+        10.0 * (rms / 0.125).log10()
+    }
+
+    #[test]
+    fn telephone_band() {
+        assert!(gain(telephone(48_000.0), 1_000.0).abs() < 1.0);
+        assert!(gain(telephone(48_000.0), 50.0) < -30.0);
+        assert!(gain(telephone(48_000.0), 12_000.0) < -30.0);
+        assert!(gain(am_radio(48_000.0), 12_000.0) < -20.0);
+        assert!(gain(underwater(48_000.0), 4_000.0) < -40.0);
+        assert!(gain(underwater(48_000.0), 100.0).abs() < 1.0);
+    }
+}
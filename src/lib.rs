@@ -15,13 +15,14 @@
 //!
 //! Many audio formats are supported:
 //! - Any sample rate
-//! - Bit depth: [8]- or [16]-bit integer and [32]- or [64]-bit float
-//! - [Mono], [Stereo], [5.1 Surround]
+//! - Bit depth: [8]-, [12]-, [16]-, [20]- or [24]-bit integer and [32]- or
+//!   [64]-bit float
+//! - [Mono], [Stereo], [5.1 Surround], [6.1 Surround], [7.1 Surround]
 //!
 //! Blending [operations] are supported for all formats.
 //!
 //! # Getting Started
-//! 
+//!
 //! To understand some of the concepts used in this library,
 //! [this MDN article] is a good read (although the stuff about compression
 //! isn't relevant to this crate's functionality).  This crate uses the MDN
@@ -44,12 +45,17 @@
 //!
 //! [audio buffer]: crate::Audio
 //! [8]: crate::chan::Ch8
+//! [12]: crate::chan::Ch12
 //! [16]: crate::chan::Ch16
+//! [20]: crate::chan::Ch20
+//! [24]: crate::chan::Ch24
 //! [32]: crate::chan::Ch32
 //! [64]: crate::chan::Ch64
 //! [Mono]: crate::mono::Mono
 //! [Stereo]: crate::stereo::Stereo
 //! [5.1 Surround]: crate::surround::Surround
+//! [6.1 Surround]: crate::surround61::Surround61
+//! [7.1 Surround]: crate::surround71::Surround71
 //! [operations]: crate::ops
 //! [this MDN article]: https://developer.mozilla.org/en-US/docs/Web/Media/Formats/Audio_concepts
 
@@ -82,17 +88,68 @@
 extern crate alloc;
 
 mod audio;
+mod builder;
+mod bytes;
 pub mod chan;
+pub mod dither;
+mod edit;
+mod envelope;
+mod error;
+pub mod fade;
+#[cfg(feature = "std")]
+mod fonraw;
 mod frame;
+pub mod fx;
+mod looping;
+mod loudness;
+mod markers;
 mod math;
+mod matrix;
+mod meter;
 pub mod mono;
+mod multitrack;
 pub mod ops;
+mod order;
+pub mod pipeline;
+mod planar;
+#[cfg(feature = "bytemuck")]
+#[allow(unsafe_code)]
+mod pod;
 mod private;
+#[cfg(feature = "std")]
+mod reader;
+mod routing;
+#[cfg(feature = "std")]
+mod selftest;
+mod shared;
+mod silence;
+pub mod sink;
+mod slice;
+mod speaker_test;
 pub mod stereo;
 mod streaming;
 pub mod surround;
+pub mod surround61;
+pub mod surround71;
 // mod resampler;
 
 pub use audio::Audio;
+pub use builder::AudioBuilder;
+pub use edit::Edit;
+pub use envelope::PeakCache;
+pub use error::Error;
 pub use frame::Frame;
-pub use streaming::{Resampler, Sink, Stream};
+pub use looping::{LoopIter, LoopSource};
+pub use markers::{Marker, Markers};
+pub use matrix::{DownmixMatrix, UpmixMode};
+pub use meter::Meter;
+pub use order::ChannelOrder;
+#[cfg(feature = "std")]
+pub use reader::PcmReader;
+pub use routing::{IrLayout, IrRoute};
+#[cfg(feature = "std")]
+pub use selftest::{selftest, SelfTestCheck, SelfTestReport};
+pub use shared::SharedAudio;
+pub use slice::{AudioSlice, AudioSliceMut};
+pub use speaker_test::{SpeakerTest, TestSignal};
+pub use streaming::{FrameIter, Resampler, Sink, Stream, StreamPosition};
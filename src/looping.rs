@@ -0,0 +1,141 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Repeating and looping audio.
+
+use crate::{audio, Audio, Frame, Stream};
+use core::ops::{Range, RangeBounds};
+
+impl<F: Frame> Audio<F> {
+    /// Make a new buffer of this one repeated `n` times.
+    pub fn repeat(&self, n: usize) -> Self {
+        let mut audio = Self::with_capacity(self.sample_rate(), self.len() * n);
+        for _ in 0..n {
+            Extend::extend(&mut audio, self.iter().cloned());
+        }
+        audio
+    }
+}
+
+/// Infinite [`Stream`](crate::Stream) of an `Audio` buffer's frames, playing
+/// up to the end of the loop, and then repeating the loop (the whole buffer,
+/// unless [loop points](LoopSource::loop_points) are set).
+///
+/// ```rust
+/// use fon::{mono::Mono32, Audio, LoopSource};
+///
+/// let clip = Audio::<Mono32>::with_f32_buffer(48_000, [0.0, 0.25, 0.5]);
+/// let frames: Vec<Mono32> =
+///     LoopSource::new(&clip).loop_points(1..).into_iter().take(6).collect();
+/// assert_eq!(frames, [0.0, 0.25, 0.5, 0.25, 0.5, 0.25].map(Mono32::new));
+/// ```
+#[derive(Clone, Debug)]
+pub struct LoopSource<'a, F: Frame> {
+    audio: &'a Audio<F>,
+    region: Range<usize>,
+}
+
+impl<'a, F: Frame> LoopSource<'a, F> {
+    /// Loop a whole `Audio` buffer.
+    ///
+    /// # Panics
+    /// If the buffer is empty.
+    pub fn new(audio: &'a Audio<F>) -> Self {
+        assert!(!audio.is_empty(), "Can't loop empty audio");
+        Self {
+            audio,
+            region: 0..audio.len(),
+        }
+    }
+
+    /// Set the region of frames to loop.
+    ///
+    /// # Panics
+    /// If the range is empty or out of bounds.
+    pub fn loop_points<R: RangeBounds<usize>>(mut self, range: R) -> Self {
+        let (start, end) = audio::range_bounds(&range, self.audio.len());
+        assert!(start < end, "Empty loop");
+        self.region = start..end;
+        self
+    }
+
+    /// Get the region of frames that's looped.
+    pub fn region(&self) -> Range<usize> {
+        self.region.clone()
+    }
+}
+
+impl<'a, F: Frame> IntoIterator for LoopSource<'a, F> {
+    type Item = F;
+    type IntoIter = LoopIter<'a, F>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        LoopIter {
+            index: 0,
+            source: self,
+        }
+    }
+}
+
+impl<F: Frame> Stream<F> for LoopSource<'_, F> {
+    fn sample_rate(&self) -> Option<f64> {
+        Some(self.audio.sample_rate())
+    }
+
+    fn len(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Iterator over the frames of a [`LoopSource`].
+#[derive(Clone, Debug)]
+pub struct LoopIter<'a, F: Frame> {
+    source: LoopSource<'a, F>,
+    index: usize,
+}
+
+impl<F: Frame> Iterator for LoopIter<'_, F> {
+    type Item = F;
+
+    fn next(&mut self) -> Option<F> {
+        let frame = self.source.audio[self.index];
+        self.index += 1;
+        if self.index == self.source.region.end {
+            self.index = self.source.region.start;
+        }
+        Some(frame)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mono::Mono32, Sink};
+
+    #[test]
+    fn repeat_loop() {
+        let clip = Audio::<Mono32>::with_f32_buffer(48_000, [0.0, 0.25, 0.5]);
+        let repeated = clip.repeat(3);
+        assert_eq!(repeated.len(), 9);
+        assert_eq!(repeated.get(7), Some(Mono32::new(0.25)));
+        assert!(clip.repeat(0).is_empty());
+
+        // Stream into a sink, which takes as many frames as it needs.
+        let source = LoopSource::new(&clip).loop_points(..2);
+        let mut out = Audio::<Mono32>::with_silence(48_000, 7);
+        out.sink(..).stream(source);
+        let expected = [0.0, 0.25, 0.0, 0.25, 0.0, 0.25, 0.0].map(Mono32::new);
+        assert!(out.iter().eq(expected.iter()));
+    }
+}
@@ -0,0 +1,221 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Loudness measurement (ITU-R BS.1770 / EBU R128).
+
+use crate::{chan::Channel, math, Audio, Frame};
+use alloc::{vec, vec::Vec};
+use core::f64::consts::PI;
+
+// Absolute gate, in LUFS.
+const ABSOLUTE_GATE: f64 = -70.0;
+// Relative gate, in LU below the absolute-gated loudness.
+const RELATIVE_GATE: f64 = -10.0;
+
+// Second order IIR filter on `f64` samples (no clipping), with normalized
+// feedforward (b0, b1, b2) and feedback (a1, a2) coefficients.
+#[derive(Clone, Copy)]
+struct Filter {
+    b: [f64; 3],
+    a: [f64; 2],
+    state: [f64; 4],
+}
+
+impl Filter {
+    fn process(&mut self, x: f64) -> f64 {
+        let (b, a) = (self.b, self.a);
+        let [x1, x2, y1, y2] = self.state;
+        let y = b[0] * x + b[1] * x1 + b[2] * x2 - a[0] * y1 - a[1] * y2;
+        self.state = [x, x1, y, y1];
+        y
+    }
+}
+
+// The two stages of the K-weighting filter: a high shelf modeling the head,
+// then the RLB highpass.  Coefficients are derived for any sample rate from
+// the analog prototypes (matching the 48 kHz coefficients of BS.1770).
+fn k_weighting(s_rate: f64) -> [Filter; 2] {
+    let k = (PI * 1_681.974_450_955_533 / s_rate).tan();
+    let q = 0.707_175_236_955_419_6;
+    let vh = 10.0_f64.powf(3.999_843_853_973_347 / 20.0);
+    let vb = vh.powf(0.499_666_774_154_541_6);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Filter {
+        b: [
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+        ],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        state: [0.0; 4],
+    };
+    let k = (PI * 38.135_470_876_024_44 / s_rate).tan();
+    let q = 0.500_327_037_323_877_3;
+    let a0 = 1.0 + k / q + k * k;
+    let highpass = Filter {
+        b: [1.0, -2.0, 1.0],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        state: [0.0; 4],
+    };
+    [shelf, highpass]
+}
+
+// Weight of each channel: 1.41 (+1.5 dB) for surround speakers behind the
+// listener, and none for channels without a position (LFE).
+fn weight<F: Frame>(channel: usize) -> f64 {
+    match F::CONFIG.get(channel) {
+        Some(position) if position.abs() > 0.5 => 1.41,
+        Some(_) => 1.0,
+        None => 0.0,
+    }
+}
+
+// Convert a weighted mean square to loudness.
+fn lufs(power: f64) -> f64 {
+    -0.691 + 10.0 * power.log10()
+}
+
+// K-weighting filters for each channel.
+struct KWeighting(Vec<[Filter; 2]>);
+
+impl KWeighting {
+    fn new<F: Frame>(s_rate: f64) -> Self {
+        Self(vec![k_weighting(s_rate); F::CHAN_COUNT])
+    }
+
+    // Get the K-weighted and channel-weighted power of a frame.
+    fn power<F: Frame>(&mut self, frame: F) -> f64 {
+        let mut power = 0.0;
+        for (c, (chan, [shelf, highpass])) in
+            frame.channels().iter().zip(self.0.iter_mut()).enumerate()
+        {
+            let y = highpass.process(shelf.process(chan.to_f64()));
+            power += weight::<F>(c) * y * y;
+        }
+        power
+    }
+}
+
+// Get the ungated, K-weighted mean square of frames.
+pub(crate) fn mean_power<F, I>(s_rate: f64, frames: I) -> f64
+where
+    F: Frame,
+    I: IntoIterator<Item = F>,
+{
+    let mut k_weighting = KWeighting::new::<F>(s_rate);
+    let (sum, count) = frames.into_iter().fold((0.0, 0), |(sum, count), f| {
+        (sum + k_weighting.power(f), count + 1)
+    });
+    sum / f64::from(count.max(1))
+}
+
+impl<F: Frame> Audio<F> {
+    /// Measure the integrated (gated) loudness, in LUFS, as specified by
+    /// ITU-R BS.1770 and used for EBU R128.
+    ///
+    /// Channels are K-weighted, and measured in 400 millisecond blocks
+    /// overlapping by 75%; audio shorter than one block is measured as a
+    /// single block.  Surround speakers behind the listener are weighted by
+    /// +1.5 dB, and the LFE isn't counted.  Silent audio is negative
+    /// infinity.
+    pub fn lufs_integrated(&self) -> f64 {
+        // K-weighted mean square of each channel, in 100 millisecond steps.
+        let step = math::ceil_usize(self.sample_rate() / 10.0).max(1);
+        let mut k_weighting = KWeighting::new::<F>(self.sample_rate());
+        let mut steps: Vec<f64> = Vec::new();
+        for (i, frame) in self.iter().enumerate() {
+            if i % step == 0 {
+                steps.push(0.0);
+            }
+            *steps.last_mut().unwrap() += k_weighting.power(*frame);
+        }
+        // Blocks of 4 steps.
+        let blocks: Vec<f64> = if steps.len() < 4 {
+            vec![steps.iter().sum::<f64>() / self.len().max(1) as f64]
+        } else {
+            steps
+                .windows(4)
+                .map(|block| block.iter().sum::<f64>() / (4 * step) as f64)
+                .collect()
+        };
+        let gated = |gate: f64| {
+            let (sum, count) = blocks
+                .iter()
+                .filter(|power| lufs(**power) > gate)
+                .fold((0.0, 0), |(sum, count), power| (sum + power, count + 1));
+            if count == 0 {
+                0.0
+            } else {
+                sum / f64::from(count)
+            }
+        };
+        let relative = lufs(gated(ABSOLUTE_GATE)) + RELATIVE_GATE;
+        lufs(gated(relative.max(ABSOLUTE_GATE)))
+    }
+
+    /// Change the level so that the integrated loudness (see
+    /// [`lufs_integrated()`](Audio::lufs_integrated)) is `target` LUFS, such
+    /// as -23 for EBU R128 broadcast or -14 for streaming (saturating).
+    /// Silent audio is left unchanged.
+    pub fn normalize_lufs(&mut self, target: f64) {
+        let loudness = self.lufs_integrated();
+        if loudness.is_finite() {
+            self.gain_db(target - loudness);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mono::Mono64, surround::Surround64};
+
+    fn sine(s_rate: f64, amplitude: f64) -> Audio<Mono64> {
+        let samples: Vec<f64> = (0..s_rate as usize * 2)
+            .map(|i| (2.0 * PI * 997.0 * i as f64 / s_rate).sin() * amplitude)
+            .collect();
+        Audio::with_f64_buffer(s_rate, samples)
+    }
+
+    #[test]
+    fn lufs() {
+        // A full scale sine in one front channel is -3.01 LUFS.
+        for s_rate in [44_100.0, 48_000.0, 96_000.0] {
+            let loudness = sine(s_rate, 1.0).lufs_integrated();
+            assert!((loudness + 3.01).abs() < 0.05, "{}: {}", s_rate, loudness);
+        }
+        let mut audio = sine(48_000.0, 0.5);
+        assert!((audio.lufs_integrated() + 9.03).abs() < 0.05);
+
+        // Gating ignores silence (except for the 3 blocks overlapping both,
+        // at 3/4, 1/2 and 1/4 of the power of the other 17).
+        Extend::extend(&mut audio, (0..96_000).map(|_| Mono64::default()));
+        let expected = -9.03 + 10.0 * (18.5_f64 / 20.0).log10();
+        assert!((audio.lufs_integrated() - expected).abs() < 0.05);
+        audio.normalize_lufs(-23.0);
+        assert!((audio.lufs_integrated() + 23.0).abs() < 0.01);
+        let mut silence = Audio::<Mono64>::with_silence(48_000, 100);
+        silence.normalize_lufs(-23.0);
+        assert_eq!(silence.lufs_integrated(), f64::NEG_INFINITY);
+
+        // Rear speakers are weighted +1.5 dB, and the LFE isn't counted.
+        let surround = |chan: usize| {
+            let mut audio = Audio::<Surround64>::with_silence(48_000, 96_000);
+            for (frame, src) in audio.iter_mut().zip(sine(48_000.0, 0.5).iter())
+            {
+                frame.channels_mut()[chan] = src.channels()[0];
+            }
+            audio.lufs_integrated()
+        };
+        assert!((surround(1) + 9.03).abs() < 0.05);
+        assert!((surround(4) + 9.03 - 1.49).abs() < 0.05);
+        assert_eq!(surround(5), f64::NEG_INFINITY);
+    }
+}
@@ -0,0 +1,184 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Named positions and regions in an audio buffer.
+
+use crate::Edit;
+use alloc::{string::String, vec::Vec};
+use core::{ops::Range, slice::Iter};
+
+/// A named position or region, in frames.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Marker {
+    /// Name of the marker.
+    pub name: String,
+    /// Range of frames (empty for a position).
+    pub range: Range<usize>,
+}
+
+impl Marker {
+    /// Check if the marker is a position rather than a region.
+    pub fn is_position(&self) -> bool {
+        self.range.is_empty()
+    }
+
+    // Move the marker to keep it on the same audio after an edit: first the
+    // removal, and then the insertion.
+    fn apply(&mut self, edit: &Edit) {
+        let removed_end = edit.at + edit.removed;
+        let remove = |frame: usize| {
+            if frame <= edit.at {
+                frame
+            } else if frame < removed_end {
+                edit.at
+            } else {
+                frame - edit.removed
+            }
+        };
+        // Markers starting at the edit move past the inserted frames.
+        let mut start = remove(self.range.start);
+        if start >= edit.at {
+            start += edit.inserted;
+        }
+        // Regions only grow if the frames are inserted inside of them.
+        let end = if self.is_position() {
+            start
+        } else {
+            let end = remove(self.range.end);
+            if end > edit.at {
+                end + edit.inserted
+            } else {
+                end
+            }
+        };
+        self.range = start..end.max(start);
+    }
+}
+
+/// List of named positions and regions in an [`Audio`](crate::Audio) buffer,
+/// kept consistent with destructive edits by applying their [`Edit`] records.
+///
+/// ```rust
+/// use fon::{mono::Mono32, Audio, Markers};
+///
+/// let mut audio = Audio::<Mono32>::with_silence(48_000, 100);
+/// let mut markers = Markers::new();
+/// markers.add("chorus", 50..80);
+/// markers.apply(&audio.insert_silence(10, 5));
+/// assert_eq!(markers.get("chorus").unwrap().range, 55..85);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Markers {
+    markers: Vec<Marker>,
+}
+
+impl Markers {
+    /// Create an empty marker list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a named region (or a position, if the range is empty), replacing
+    /// any marker with the same name.
+    pub fn add<N: Into<String>>(&mut self, name: N, range: Range<usize>) {
+        let name = name.into();
+        self.remove(&name);
+        let index = self
+            .markers
+            .partition_point(|m| m.range.start <= range.start);
+        self.markers.insert(index, Marker { name, range });
+    }
+
+    /// Add a named position, replacing any marker with the same name.
+    pub fn add_position<N: Into<String>>(&mut self, name: N, at: usize) {
+        self.add(name, at..at);
+    }
+
+    /// Get a marker by name.
+    pub fn get(&self, name: &str) -> Option<&Marker> {
+        self.markers.iter().find(|m| m.name == name)
+    }
+
+    /// Remove a marker by name.
+    pub fn remove(&mut self, name: &str) -> Option<Marker> {
+        let index = self.markers.iter().position(|m| m.name == name)?;
+        Some(self.markers.remove(index))
+    }
+
+    /// Returns an iterator over the markers, in order of their start frame.
+    pub fn iter(&self) -> Iter<'_, Marker> {
+        self.markers.iter()
+    }
+
+    /// Get the number of markers.
+    pub fn len(&self) -> usize {
+        self.markers.len()
+    }
+
+    /// Check if there are no markers.
+    pub fn is_empty(&self) -> bool {
+        self.markers.is_empty()
+    }
+
+    /// Adjust the markers for an edit to their buffer, so that they stay on
+    /// the same audio.  Markers inside of removed frames move to where the
+    /// frames were removed, and regions shrink to the audio that's left.
+    pub fn apply(&mut self, edit: &Edit) {
+        for marker in self.markers.iter_mut() {
+            marker.apply(edit);
+        }
+        self.markers.sort_by_key(|m| m.range.start);
+    }
+}
+
+impl<'a> IntoIterator for &'a Markers {
+    type IntoIter = Iter<'a, Marker>;
+    type Item = &'a Marker;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mono::Mono32, Audio};
+
+    #[test]
+    fn markers() {
+        let mut audio = Audio::<Mono32>::with_silence(48_000, 100);
+        let mut markers = Markers::new();
+        markers.add("region", 20..40);
+        markers.add_position("start", 20);
+        markers.add_position("end", 90);
+        assert_eq!(markers.len(), 3);
+
+        // Insertion inside a region grows it, and before a position moves it.
+        markers.apply(&audio.insert_silence(30, 10));
+        assert_eq!(markers.get("region").unwrap().range, 20..50);
+        markers.apply(&audio.insert_silence(20, 5));
+        assert_eq!(markers.get("region").unwrap().range, 25..55);
+        assert_eq!(markers.get("start").unwrap().range, 25..25);
+        assert_eq!(markers.get("end").unwrap().range, 105..105);
+
+        // Removal shrinks regions and moves removed positions.
+        markers.apply(&audio.remove_range(20..30));
+        assert_eq!(markers.get("region").unwrap().range, 20..45);
+        assert_eq!(markers.get("start").unwrap().range, 20..20);
+        markers.apply(&audio.remove_range(40..100));
+        assert_eq!(markers.get("region").unwrap().range, 20..40);
+        assert_eq!(markers.get("end").unwrap().range, 40..40);
+        assert_eq!(audio.len(), 45);
+        let names: Vec<&str> = markers.iter().map(|m| &m.name[..]).collect();
+        assert_eq!(names, ["region", "start", "end"]);
+        assert!(markers.remove("start").unwrap().is_position());
+    }
+}
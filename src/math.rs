@@ -10,6 +10,38 @@
 
 //! Math not available on no_std.
 
+use core::time::Duration;
+
+// Get the number of frames in a duration, rounded to the nearest frame.
+#[inline(always)]
+pub(crate) fn duration_frames(s_rate: f64, duration: Duration) -> usize {
+    (duration.as_secs_f64() * s_rate + 0.5) as usize
+}
+
+// Get the index of the frame playing at a time, splitting whole and fractional
+// seconds so that exact timestamps don't round down a frame.
+#[inline(always)]
+pub(crate) fn duration_index(s_rate: f64, duration: Duration) -> usize {
+    let whole = duration.as_secs() as f64 * s_rate;
+    let fract = f64::from(duration.subsec_nanos()) * s_rate / 1_000_000_000.0;
+    (whole + fract) as usize
+}
+
+// Get the duration of a number of frames, rounded down to the nanosecond.
+// Splits whole and fractional seconds like `duration_index()`, so that frame
+// counts at integer sample rates are exact.  Zero for invalid sample rates.
+#[inline(always)]
+pub(crate) fn frames_duration(s_rate: f64, frames: usize) -> Duration {
+    if frames == 0 || s_rate.is_nan() || s_rate <= 0.0 {
+        return Duration::ZERO;
+    }
+    let frames = frames as f64;
+    let secs = (frames / s_rate) as u64;
+    let rest = frames - secs as f64 * s_rate;
+    let nanos = (rest * 1_000_000_000.0 / s_rate) as u32;
+    Duration::new(secs, nanos)
+}
+
 #[inline(always)]
 pub(crate) fn floorh_i16(input: f32) -> i16 {
     if input < 0.0 {
@@ -37,6 +69,15 @@ pub(crate) fn floor_i16(input: f64) -> i16 {
     }
 }
 
+#[inline(always)]
+pub(crate) fn floor_i32(input: f64) -> i32 {
+    if input < 0.0 {
+        (-ceil(-input)) as i32
+    } else {
+        input as i32
+    }
+}
+
 #[inline(always)]
 pub(crate) fn floor_i8(input: f64) -> i8 {
     if input < 0.0 {
@@ -0,0 +1,318 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use alloc::{vec, vec::Vec};
+use core::f64::consts::FRAC_1_SQRT_2;
+
+/// How a mono channel is spread to the speakers when upmixing (see
+/// [`DownmixMatrix::upmix()`]).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UpmixMode {
+    /// Only to the center speaker, keeping speech anchored to the center in
+    /// surround.  Stereo has no center speaker, so both channels are at -3 dB
+    /// for a phantom center.
+    CenterOnly,
+    /// Duplicated to the front left and right speakers (the mixing done by
+    /// [`Frame::convert()`](crate::Frame::convert)).
+    #[default]
+    FrontPair,
+    /// To every full range speaker (not the LFE), with equal power.
+    AllSpeakersEqualPower,
+}
+
+/// Channel mixing coefficients for converting frames with `FROM` channels into
+/// frames with `TO` channels (see
+/// [`Frame::convert_matrix()`](crate::Frame::convert_matrix)).
+///
+/// The `Default` matrices match the mixing done by
+/// [`Frame::convert()`](crate::Frame::convert).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DownmixMatrix<const FROM: usize, const TO: usize> {
+    // One row of input channel gains for each output channel.
+    rows: [[f64; FROM]; TO],
+}
+
+impl<const FROM: usize, const TO: usize> DownmixMatrix<FROM, TO> {
+    /// Create a new matrix from one row of input channel gains for each output
+    /// channel.
+    pub const fn new(rows: [[f64; FROM]; TO]) -> Self {
+        Self { rows }
+    }
+
+    /// Get the gain from input channel `from` to output channel `to`.
+    ///
+    /// # Panics
+    /// If either channel is out of range.
+    pub fn gain(&self, from: usize, to: usize) -> f64 {
+        self.rows[to][from]
+    }
+
+    /// Set the gain from input channel `from` to output channel `to`.
+    ///
+    /// # Panics
+    /// If either channel is out of range.
+    pub fn set_gain(&mut self, from: usize, to: usize, gain: f64) {
+        self.rows[to][from] = gain;
+    }
+
+    /// Get the rows of input channel gains for each output channel.
+    pub fn rows(&self) -> &[[f64; FROM]; TO] {
+        &self.rows
+    }
+
+    // Mix input channel values into output channel values.
+    #[inline(always)]
+    pub(crate) fn apply(&self, input: &[f64], output: &mut [f64]) {
+        for (out, row) in output.iter_mut().zip(self.rows.iter()) {
+            *out = row.iter().zip(input).map(|(gain, chan)| gain * chan).sum();
+        }
+    }
+}
+
+impl<const TO: usize> DownmixMatrix<1, TO> {
+    /// Create a matrix for upmixing mono to `TO` channels.
+    ///
+    /// # Panics
+    /// If `TO` isn't 1, 2 or 6 (a mono, stereo or surround frame).
+    pub fn upmix(mode: UpmixMode) -> Self {
+        let gains = upmix_rows(mode, TO).expect("No speaker layout for upmix");
+        let mut rows = [[0.0; 1]; TO];
+        for (row, gain) in rows.iter_mut().zip(gains) {
+            row[0] = gain;
+        }
+        Self::new(rows)
+    }
+}
+
+// Get the flattened rows of the matrix for upmixing mono to `to` channels, if
+// there is a speaker layout with that many channels.
+pub(crate) fn upmix_rows(mode: UpmixMode, to: usize) -> Option<Vec<f64>> {
+    Some(match (mode, to) {
+        (_, 1) => vec![1.0],
+        (UpmixMode::FrontPair, 2) => vec![1.0, 1.0],
+        (_, 2) => vec![FRAC_1_SQRT_2, FRAC_1_SQRT_2],
+        (UpmixMode::CenterOnly, 6) => vec![0.0, 0.0, 1.0, 0.0, 0.0, 0.0],
+        (UpmixMode::FrontPair, 6) => vec![0.0, 1.0, 0.0, 1.0, 0.0, 0.0],
+        (UpmixMode::AllSpeakersEqualPower, 6) => {
+            let gain = 5.0f64.sqrt().recip();
+            vec![gain, gain, gain, gain, gain, 0.0]
+        }
+        _ => return None,
+    })
+}
+
+// Get the flattened rows of the default matrix for mixing `from` channels into
+// `to` channels, if there is one.
+pub(crate) fn default_rows(from: usize, to: usize) -> Option<Vec<f64>> {
+    fn rows<const FROM: usize, const TO: usize>(
+        matrix: DownmixMatrix<FROM, TO>,
+    ) -> Option<Vec<f64>> {
+        Some(matrix.rows.iter().flatten().cloned().collect())
+    }
+    match (from, to) {
+        (1, 1) => rows::<1, 1>(Default::default()),
+        (2, 2) => rows::<2, 2>(Default::default()),
+        (6, 6) => rows::<6, 6>(Default::default()),
+        (7, 7) => rows::<7, 7>(Default::default()),
+        (8, 8) => rows::<8, 8>(Default::default()),
+        (1, 2) => rows::<1, 2>(Default::default()),
+        (1, 6) => rows::<1, 6>(Default::default()),
+        (2, 6) => rows::<2, 6>(Default::default()),
+        (6, 2) => rows::<6, 2>(Default::default()),
+        (6, 1) => rows::<6, 1>(Default::default()),
+        (2, 1) => rows::<2, 1>(Default::default()),
+        (7, 8) => rows::<7, 8>(Default::default()),
+        (8, 7) => rows::<8, 7>(Default::default()),
+        _ => None,
+    }
+}
+
+// Mono -> Mono, Stereo -> Stereo and Surround -> Surround (5.1, 6.1 and 7.1):
+// 1:1.
+
+impl Default for DownmixMatrix<1, 1> {
+    fn default() -> Self {
+        Self::new([[1.0]])
+    }
+}
+
+impl Default for DownmixMatrix<2, 2> {
+    fn default() -> Self {
+        Self::new([[1.0, 0.0], [0.0, 1.0]])
+    }
+}
+
+impl Default for DownmixMatrix<6, 6> {
+    fn default() -> Self {
+        let mut rows = [[0.0; 6]; 6];
+        for (i, row) in rows.iter_mut().enumerate() {
+            row[i] = 1.0;
+        }
+        Self::new(rows)
+    }
+}
+
+impl Default for DownmixMatrix<7, 7> {
+    fn default() -> Self {
+        let mut rows = [[0.0; 7]; 7];
+        for (i, row) in rows.iter_mut().enumerate() {
+            row[i] = 1.0;
+        }
+        Self::new(rows)
+    }
+}
+
+impl Default for DownmixMatrix<8, 8> {
+    fn default() -> Self {
+        let mut rows = [[0.0; 8]; 8];
+        for (i, row) in rows.iter_mut().enumerate() {
+            row[i] = 1.0;
+        }
+        Self::new(rows)
+    }
+}
+
+// Mono -> Stereo: Duplicate the channel.
+impl Default for DownmixMatrix<1, 2> {
+    fn default() -> Self {
+        Self::new([[1.0], [1.0]])
+    }
+}
+
+// Mono -> Surround (Mono -> Stereo -> Surround).
+impl Default for DownmixMatrix<1, 6> {
+    fn default() -> Self {
+        Self::new([[0.0], [1.0], [0.0], [1.0], [0.0], [0.0]])
+    }
+}
+
+// Stereo -> Surround.
+impl Default for DownmixMatrix<2, 6> {
+    fn default() -> Self {
+        let mut rows = [[0.0; 2]; 6];
+        rows[1][0] = 1.0;
+        rows[3][1] = 1.0;
+        Self::new(rows)
+    }
+}
+
+// Surround -> Stereo.
+impl Default for DownmixMatrix<6, 2> {
+    fn default() -> Self {
+        let mut rows = [[0.0; 6]; 2];
+        rows[0][1] = 1.0;
+        rows[1][3] = 1.0;
+        Self::new(rows)
+    }
+}
+
+// Surround -> Stereo -> Mono.
+impl Default for DownmixMatrix<6, 1> {
+    fn default() -> Self {
+        Self::new([[0.0, 0.5, 0.0, 0.5, 0.0, 0.0]])
+    }
+}
+
+// Stereo -> Mono.
+impl Default for DownmixMatrix<2, 1> {
+    fn default() -> Self {
+        Self::new([[0.5, 0.5]])
+    }
+}
+
+// 6.1 -> 7.1: The back center is split between the back pair with equal power.
+impl Default for DownmixMatrix<7, 8> {
+    fn default() -> Self {
+        let mut rows = [[0.0; 7]; 8];
+        rows[0][5] = FRAC_1_SQRT_2;
+        for (i, row) in rows[1..6].iter_mut().enumerate() {
+            row[i] = 1.0;
+        }
+        rows[6][5] = FRAC_1_SQRT_2;
+        rows[7][6] = 1.0;
+        Self::new(rows)
+    }
+}
+
+// 7.1 -> 6.1: The back pair is mixed into the back center with equal power.
+impl Default for DownmixMatrix<8, 7> {
+    fn default() -> Self {
+        let mut rows = [[0.0; 8]; 7];
+        for (i, row) in rows[..5].iter_mut().enumerate() {
+            row[i + 1] = 1.0;
+        }
+        rows[5][0] = FRAC_1_SQRT_2;
+        rows[5][6] = FRAC_1_SQRT_2;
+        rows[6][7] = 1.0;
+        Self::new(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chan::Channel, mono::Mono64, stereo::Stereo64, surround::Surround64,
+        Frame,
+    };
+
+    #[test]
+    fn default_matches_convert() {
+        let frame = Surround64::new(0.1, 0.2, 0.3, 0.4, 0.5, 0.6);
+        let stereo: Stereo64 = frame.convert();
+        let mono: Mono64 = frame.convert();
+        assert_eq!(
+            frame.convert_matrix::<Stereo64, 6, 2>(&Default::default()),
+            stereo
+        );
+        assert_eq!(
+            frame.convert_matrix::<Mono64, 6, 1>(&Default::default()),
+            mono
+        );
+        let up: Surround64 = stereo.convert();
+        assert_eq!(
+            stereo.convert_matrix::<Surround64, 2, 6>(&Default::default()),
+            up
+        );
+    }
+
+    #[test]
+    fn custom_matrix() {
+        // Center and surrounds at -3 dB.
+        let g = FRAC_1_SQRT_2;
+        let matrix = DownmixMatrix::new([
+            [g, 1.0, g, 0.0, 0.0, 0.0],
+            [0.0, 0.0, g, 1.0, g, 0.0],
+        ]);
+        let frame = Surround64::new(0.5, 0.25, 0.0, 0.25, 0.5, 1.0);
+        let stereo: Stereo64 = frame.convert_matrix(&matrix);
+        let left = stereo.channels()[0].to_f64();
+        assert!((left - (0.5 * g + 0.25)).abs() < 1e-12);
+        assert_eq!(matrix.gain(2, 1), g);
+    }
+
+    #[test]
+    fn upmix() {
+        assert_eq!(
+            DownmixMatrix::<1, 6>::upmix(UpmixMode::FrontPair),
+            DownmixMatrix::default()
+        );
+        let frame = Mono64::new(0.5);
+        let center = DownmixMatrix::<1, 6>::upmix(UpmixMode::CenterOnly);
+        let center: Surround64 = frame.convert_matrix(&center);
+        assert_eq!(center, Surround64::new(0.0, 0.0, 0.5, 0.0, 0.0, 0.0));
+        let all =
+            DownmixMatrix::<1, 6>::upmix(UpmixMode::AllSpeakersEqualPower);
+        let power: f64 = all.rows().iter().map(|row| row[0].powi(2)).sum();
+        assert!((power - 1.0).abs() < 1e-12);
+        let stereo = DownmixMatrix::<1, 2>::upmix(UpmixMode::CenterOnly);
+        assert_eq!(stereo.gain(0, 1), FRAC_1_SQRT_2);
+    }
+}
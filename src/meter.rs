@@ -0,0 +1,182 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Peak and RMS level metering.
+
+use crate::{
+    chan::{self, Channel},
+    fx::RtSafe,
+    Audio, Frame,
+};
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+
+/// Streaming peak and RMS level meter, fed frames incrementally (for example
+/// once per audio callback, for a realtime level display).
+///
+/// Levels are per channel, in dBFS (negative infinity for silence), measured
+/// over every frame fed since the meter was created or
+/// [reset](Meter::reset).
+///
+/// ```rust
+/// use fon::{stereo::Stereo32, Meter};
+///
+/// let mut meter = Meter::<Stereo32>::new();
+/// meter.feed(Stereo32::new(0.5, 0.0));
+/// meter.feed(Stereo32::new(-1.0, 0.0));
+/// assert_eq!(meter.peak()[0], 0.0);
+/// assert_eq!(meter.peak()[1], f64::NEG_INFINITY);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Meter<F: Frame> {
+    peaks: Vec<f64>,
+    squares: Vec<f64>,
+    count: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: Frame> Default for Meter<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Frame> RtSafe for Meter<F> {}
+
+impl<F: Frame> Meter<F> {
+    /// Create a new meter.
+    pub fn new() -> Self {
+        Self {
+            peaks: vec![0.0; F::CHAN_COUNT],
+            squares: vec![0.0; F::CHAN_COUNT],
+            count: 0,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Feed one frame to the meter.
+    pub fn feed(&mut self, frame: F) {
+        let levels = self.peaks.iter_mut().zip(self.squares.iter_mut());
+        for (chan, (peak, square)) in frame.channels().iter().zip(levels) {
+            let value = chan.to_f64();
+            *peak = peak.max(value.abs());
+            *square += value * value;
+        }
+        self.count += 1;
+    }
+
+    /// Feed every frame of an `Audio` buffer to the meter.
+    pub fn feed_audio(&mut self, audio: &Audio<F>) {
+        for frame in audio.iter() {
+            self.feed(*frame);
+        }
+    }
+
+    /// Get the number of frames fed since the last reset.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Check if no frames were fed since the last reset.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Get the peak level of each channel, in dBFS.
+    pub fn peak(&self) -> Vec<f64> {
+        let mut levels = vec![0.0; F::CHAN_COUNT];
+        self.peak_into(&mut levels);
+        levels
+    }
+
+    /// Write the peak level of each channel, in dBFS, into `levels` (without
+    /// allocating, for realtime callbacks).  Channels past the end of
+    /// `levels` are skipped.
+    pub fn peak_into(&self, levels: &mut [f64]) {
+        for (level, peak) in levels.iter_mut().zip(self.peaks.iter()) {
+            *level = chan::gain_to_db(*peak);
+        }
+    }
+
+    /// Get the RMS level of each channel, in dBFS.
+    pub fn rms(&self) -> Vec<f64> {
+        let mut levels = vec![0.0; F::CHAN_COUNT];
+        self.rms_into(&mut levels);
+        levels
+    }
+
+    /// Write the RMS level of each channel, in dBFS, into `levels` (without
+    /// allocating, for realtime callbacks).  Channels past the end of
+    /// `levels` are skipped.
+    pub fn rms_into(&self, levels: &mut [f64]) {
+        let count = self.count.max(1) as f64;
+        for (level, square) in levels.iter_mut().zip(self.squares.iter()) {
+            *level = chan::gain_to_db((square / count).sqrt());
+        }
+    }
+
+    /// Start measuring again, forgetting the frames fed so far.
+    pub fn reset(&mut self) {
+        self.peaks.fill(0.0);
+        self.squares.fill(0.0);
+        self.count = 0;
+    }
+}
+
+impl<F: Frame> Audio<F> {
+    /// Get the peak level of each channel, in dBFS (negative infinity for
+    /// silence).
+    pub fn peak(&self) -> Vec<f64> {
+        let mut meter = Meter::new();
+        meter.feed_audio(self);
+        meter.peak()
+    }
+
+    /// Get the RMS level of each channel, in dBFS (negative infinity for
+    /// silence).
+    pub fn rms(&self) -> Vec<f64> {
+        let mut meter = Meter::new();
+        meter.feed_audio(self);
+        meter.rms()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stereo::Stereo64;
+
+    #[test]
+    fn meter() {
+        let mut audio = Audio::<Stereo64>::with_silence(48_000, 4);
+        audio[0] = Stereo64::new(0.5, 0.25);
+        audio[1] = Stereo64::new(-0.5, 0.0);
+        let peak = audio.peak();
+        let rms = audio.rms();
+        assert!((peak[0] + 6.0206).abs() < 1e-4);
+        assert!((peak[1] + 12.0412).abs() < 1e-4);
+        // Half of the frames at half amplitude is 3 dB below that.
+        assert!((rms[0] + 9.0309).abs() < 1e-4);
+
+        let mut meter = Meter::new();
+        meter.feed_audio(&audio);
+        assert_eq!(meter.len(), 4);
+        assert_eq!(meter.rms(), rms);
+        let mut levels = [0.0; 2];
+        meter.peak_into(&mut levels);
+        assert_eq!(levels, peak[..]);
+        meter.rms_into(&mut levels[..1]);
+        assert_eq!(levels, [rms[0], peak[1]]);
+        meter.reset();
+        assert!(meter.is_empty());
+        assert_eq!(meter.peak(), [f64::NEG_INFINITY; 2]);
+        assert_eq!(meter.rms(), [f64::NEG_INFINITY; 2]);
+    }
+}
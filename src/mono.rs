@@ -11,11 +11,14 @@
 //! Mono speaker configuration and types.
 
 use crate::{
-    chan::{Ch16, Ch32, Ch64, Ch8, Channel},
+    chan::{Ch12, Ch16, Ch20, Ch24, Ch32, Ch64, Ch8, Channel},
+    stereo::Stereo,
+    surround::Surround,
     Frame,
 };
 use core::ops::{
-    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign,
+    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub,
+    SubAssign,
 };
 
 /// Mono audio format (Audio [`Frame`](crate::frame::Frame) containing one
@@ -106,6 +109,21 @@ impl<C: Channel> Mul for Mono<C> {
     }
 }
 
+impl<C: Channel> MulAssign<f32> for Mono<C> {
+    fn mul_assign(&mut self, gain: f32) {
+        crate::frame::gain(self, gain.into());
+    }
+}
+
+impl<C: Channel> Mul<f32> for Mono<C> {
+    type Output = Mono<C>;
+
+    fn mul(mut self, gain: f32) -> Self {
+        self *= gain;
+        self
+    }
+}
+
 impl<C: Channel> DivAssign for Mono<C> {
     fn div_assign(&mut self, other: Self) {
         for (chan, ch) in self.channels.iter_mut().zip(other.channels.iter()) {
@@ -135,6 +153,60 @@ impl<C: Channel> Neg for Mono<C> {
     }
 }
 
+impl<C: Channel> From<[C; 1]> for Mono<C> {
+    fn from(channels: [C; 1]) -> Self {
+        Self { channels }
+    }
+}
+
+impl<C: Channel> From<Mono<C>> for [C; 1] {
+    fn from(frame: Mono<C>) -> Self {
+        frame.channels
+    }
+}
+
+impl<C: Channel> From<(C,)> for Mono<C> {
+    fn from(t: (C,)) -> Self {
+        Self { channels: [t.0] }
+    }
+}
+
+impl<C: Channel> From<Mono<C>> for (C,) {
+    fn from(c: Mono<C>) -> Self {
+        (c.channels[0],)
+    }
+}
+
+impl<C: Channel> From<Stereo<C>> for Mono<C> {
+    /// Convert with the same channel mixing as
+    /// [`Frame::convert()`](crate::Frame::convert).
+    fn from(frame: Stereo<C>) -> Self {
+        frame.convert()
+    }
+}
+
+impl<C: Channel> From<Surround<C>> for Mono<C> {
+    /// Convert with the same channel mixing as
+    /// [`Frame::convert()`](crate::Frame::convert).
+    fn from(frame: Surround<C>) -> Self {
+        frame.convert()
+    }
+}
+
+impl<C: Channel> Index<usize> for Mono<C> {
+    type Output = C;
+
+    fn index(&self, index: usize) -> &C {
+        &self.channels[index]
+    }
+}
+
+impl<C: Channel> IndexMut<usize> for Mono<C> {
+    fn index_mut(&mut self, index: usize) -> &mut C {
+        &mut self.channels[index]
+    }
+}
+
 impl<C: Channel> Iterator for Mono<C> {
     type Item = Self;
 
@@ -145,8 +217,14 @@ impl<C: Channel> Iterator for Mono<C> {
 
 /// Mono [8-bit PCM](crate::chan::Ch8) format.
 pub type Mono8 = Mono<Ch8>;
+/// Mono [12-bit PCM](crate::chan::Ch12) format.
+pub type Mono12 = Mono<Ch12>;
 /// Mono [16-bit PCM](crate::chan::Ch16) format.
 pub type Mono16 = Mono<Ch16>;
+/// Mono [20-bit PCM](crate::chan::Ch20) format.
+pub type Mono20 = Mono<Ch20>;
+/// Mono [24-bit PCM](crate::chan::Ch24) format.
+pub type Mono24 = Mono<Ch24>;
 /// Mono [32-bit Floating Point](crate::chan::Ch32) format.
 pub type Mono32 = Mono<Ch32>;
 /// Mono [64-bit Floating Point](crate::chan::Ch64) format.
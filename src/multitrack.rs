@@ -0,0 +1,206 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Assembling multichannel audio from separate tracks.
+
+use crate::{chan::Channel, mono::Mono, Audio, Error, Frame};
+use alloc::vec::Vec;
+
+impl<F: Frame> Audio<F> {
+    /// Construct an `Audio` buffer from one mono track for each channel, with
+    /// each track starting `offsets[i]` frames in.  The buffer is long enough
+    /// for the longest track, and the channels are silent outside of their
+    /// track.
+    ///
+    /// # Panics
+    /// If the number of tracks or offsets doesn't match the number of
+    /// channels, or the tracks don't all have the same sample rate.
+    pub fn interlace(
+        tracks: &[Audio<Mono<F::Chan>>],
+        offsets: &[usize],
+    ) -> Self {
+        assert_eq!(tracks.len(), F::CHAN_COUNT, "One track per channel");
+        assert_eq!(offsets.len(), F::CHAN_COUNT, "One offset per channel");
+        let s_rate = tracks[0].sample_rate();
+        assert!(
+            tracks.iter().all(|t| t.sample_rate() == s_rate),
+            "Tracks must have the same sample rate"
+        );
+        let len = tracks
+            .iter()
+            .zip(offsets)
+            .map(|(track, offset)| track.len() + offset)
+            .max()
+            .unwrap_or(0);
+        let mut frames = Vec::new();
+        frames.resize(len, F::default());
+        for (chan, (track, offset)) in tracks.iter().zip(offsets).enumerate() {
+            for (frame, src) in frames[*offset..].iter_mut().zip(track.iter()) {
+                frame.channels_mut()[chan] = src.channels()[0];
+            }
+        }
+        Self::with_frames(s_rate, frames)
+    }
+
+    /// Construct an `Audio` buffer from one mono track for each channel (see
+    /// [`interlace()`](Audio::interlace)), returning an error instead of
+    /// panicking on the wrong number of tracks or offsets
+    /// ([`Error::ChannelCount`]) or differing sample rates
+    /// ([`Error::SampleRateMismatch`]).
+    pub fn try_interlace(
+        tracks: &[Audio<Mono<F::Chan>>],
+        offsets: &[usize],
+    ) -> Result<Self, Error> {
+        if tracks.len() != F::CHAN_COUNT || offsets.len() != F::CHAN_COUNT {
+            return Err(Error::ChannelCount);
+        }
+        let s_rate = tracks[0].sample_rate();
+        if tracks.iter().any(|t| t.sample_rate() != s_rate) {
+            return Err(Error::SampleRateMismatch);
+        }
+        Ok(Self::interlace(tracks, offsets))
+    }
+
+    /// Estimate how many frames channel `chan` lags behind channel
+    /// `reference` (negative if it's ahead), up to `max_shift` frames either
+    /// way, by finding the peak of their cross-correlation.  Ties go to the
+    /// smallest shift, so silent or uncorrelated channels have a delay of 0.
+    ///
+    /// # Panics
+    /// If either channel is out of range.
+    pub fn estimate_delay(
+        &self,
+        reference: usize,
+        chan: usize,
+        max_shift: usize,
+    ) -> isize {
+        assert!(reference < F::CHAN_COUNT && chan < F::CHAN_COUNT);
+        let a = self.channel_f64(reference);
+        let b = self.channel_f64(chan);
+        if a.iter().all(|v| *v == 0.0) || b.iter().all(|v| *v == 0.0) {
+            return 0;
+        }
+        let len = self.len() as isize;
+        let correlation = |lag: isize| -> f64 {
+            let (start, end) = (lag.max(0), len + lag.min(0));
+            (start..end)
+                .map(|i| a[(i - lag) as usize] * b[i as usize])
+                .sum()
+        };
+        let max_shift = max_shift.min(self.len()) as isize;
+        let mut best = (0, correlation(0));
+        for shift in 1..=max_shift {
+            for lag in [-shift, shift] {
+                let correlation = correlation(lag);
+                if correlation > best.1 {
+                    best = (lag, correlation);
+                }
+            }
+        }
+        best.0
+    }
+
+    /// Correct small delays between channels, by shifting every channel to
+    /// line up with the first one (see
+    /// [`estimate_delay()`](Audio::estimate_delay)).  Channels are shifted by
+    /// at most `max_shift` frames, and the frames shifted in are silent.
+    ///
+    /// Returns the shift applied to each channel, in frames (negative is
+    /// earlier).
+    pub fn align_channels(&mut self, max_shift: usize) -> Vec<isize> {
+        let shifts: Vec<isize> = (0..F::CHAN_COUNT)
+            .map(|chan| -self.estimate_delay(0, chan, max_shift))
+            .collect();
+        let len = self.len() as isize;
+        for (chan, shift) in shifts.iter().enumerate() {
+            if *shift == 0 {
+                continue;
+            }
+            let old: Vec<F::Chan> =
+                self.iter().map(|frame| frame.channels()[chan]).collect();
+            for (i, frame) in self.iter_mut().enumerate() {
+                let src = i as isize - shift;
+                frame.channels_mut()[chan] = if (0..len).contains(&src) {
+                    old[src as usize]
+                } else {
+                    F::Chan::default()
+                };
+            }
+        }
+        shifts
+    }
+
+    // Get the values of one channel.
+    fn channel_f64(&self, chan: usize) -> Vec<f64> {
+        self.iter().map(|f| f.channels()[chan].to_f64()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mono::Mono32, stereo::Stereo32, Audio, Error, Frame};
+
+    #[test]
+    fn interlace() {
+        let left = Audio::<Mono32>::with_frame(48_000, 3, Mono32::new(0.5));
+        let right = Audio::<Mono32>::with_frame(48_000, 2, Mono32::new(-0.5));
+        let audio = Audio::<Stereo32>::interlace(&[left, right], &[0, 2]);
+        assert_eq!(audio.len(), 4);
+        assert_eq!(audio.sample_rate(), 48_000.0);
+        assert_eq!(audio.get(0), Some(Stereo32::new(0.5, 0.0)));
+        assert_eq!(audio.get(2), Some(Stereo32::new(0.5, -0.5)));
+        assert_eq!(audio.get(3), Some(Stereo32::new(0.0, -0.5)));
+
+        let left = Audio::<Mono32>::with_silence(48_000, 3);
+        let right = Audio::<Mono32>::with_silence(44_100, 3);
+        let tracks = [left, right];
+        let err = Audio::<Stereo32>::try_interlace(&tracks, &[0, 0]);
+        assert_eq!(err.unwrap_err(), Error::SampleRateMismatch);
+        let err = Audio::<Stereo32>::try_interlace(&tracks[..1], &[0]);
+        assert_eq!(err.unwrap_err(), Error::ChannelCount);
+        let err = Audio::<Stereo32>::try_interlace(&tracks, &[0]);
+        assert_eq!(err.unwrap_err(), Error::ChannelCount);
+    }
+
+    #[test]
+    fn align() {
+        let mut pulse = Audio::<Mono32>::with_silence(48_000, 64);
+        for (i, frame) in pulse.iter_mut().enumerate().skip(10).take(8) {
+            *frame = Mono32::new(((i as f32) * 1.7).sin());
+        }
+        let pulse = || {
+            Audio::<Mono32>::with_frames(
+                48_000,
+                pulse.iter().cloned().collect::<Vec<_>>(),
+            )
+        };
+        let mut audio =
+            Audio::<Stereo32>::interlace(&[pulse(), pulse()], &[0, 3]);
+        assert_eq!(audio.estimate_delay(0, 1, 8), 3);
+        assert_eq!(audio.estimate_delay(1, 0, 8), -3);
+        assert_eq!(audio.align_channels(8), vec![0, -3]);
+        let frame = audio.get(12).unwrap();
+        assert_eq!(frame.channels()[0], frame.channels()[1]);
+        assert_eq!(audio.estimate_delay(0, 1, 8), 0);
+    }
+
+    #[test]
+    fn align_silent() {
+        let mut audio = Audio::<Stereo32>::with_silence(48_000, 16);
+        assert_eq!(audio.estimate_delay(0, 1, 8), 0);
+        assert_eq!(audio.align_channels(8), vec![0, 0]);
+        // Channels that never overlap within the maximum shift.
+        audio[0] = Stereo32::new(0.5, 0.0);
+        audio[15] = Stereo32::new(0.0, 0.5);
+        assert_eq!(audio.estimate_delay(0, 1, 8), 0);
+        assert_eq!(audio.estimate_delay(0, 0, 8), 0);
+        assert_eq!(audio.align_channels(8), vec![0, 0]);
+    }
+}
@@ -10,7 +10,10 @@
 
 //! Audio mixing operations.
 
-use crate::{chan::Channel, Frame};
+use crate::{
+    chan::{Channel, ClipPolicy},
+    Frame,
+};
 use core::any::Any;
 
 /// Blending operation for mixing
@@ -107,31 +110,166 @@ impl Blend for Pan {
 
     #[inline(always)]
     fn mix_frames<F: Frame>(dst: F, src: F) -> F {
-        let mut out = F::default();
-        for (d, s) in dst.channels().iter().zip(src.channels().iter()) {
-            // Get the panning amount for this channel.
-            let s = s.to_f64();
-            // Figure out which two destination channels the audio applies to.
-            let mut start = F::CONFIG.len() - 1;
-            for (i, location) in F::CONFIG.iter().enumerate() {
-                if s >= *location {
-                    start = i;
-                    break;
-                }
-            }
-            let end = (start + 1) % F::CONFIG.len();
-            // Get distance between channels
-            let mut dist = F::CONFIG[end] - F::CONFIG[start];
-            if dist < 0.0 {
-                dist = 2.0 - dist;
+        pan(dst, src, PanLaw::ConstantPower)
+    }
+}
+
+/// Pan law, the gain of a channel panned halfway between two speakers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PanLaw {
+    /// -3 dB, sine/cosine constant power panning (used by [`Pan`]).
+    #[default]
+    ConstantPower,
+    /// -4.5 dB, compromise between constant power and linear panning.
+    Compromise,
+    /// -6 dB, linear (constant amplitude) panning.
+    Linear,
+}
+
+impl PanLaw {
+    /// Get the gains of the two speakers for a position between them, from 0
+    /// (first speaker) to 1 (second speaker).
+    #[inline(always)]
+    pub fn gains(self, closeness: f64) -> (f64, f64) {
+        let angle = closeness * core::f64::consts::FRAC_PI_2;
+        let power = (angle.cos(), angle.sin());
+        let linear = (1.0 - closeness, closeness);
+        match self {
+            PanLaw::ConstantPower => power,
+            PanLaw::Compromise => {
+                ((power.0 * linear.0).sqrt(), (power.1 * linear.1).sqrt())
             }
-            // Get closeness between the two channels (0 thru 1)
-            let closeness = (s - F::CONFIG[end].min(F::CONFIG[start])) / dist;
-            // Constant Power Panning
-            let angle = closeness * core::f64::consts::FRAC_PI_2;
-            out.channels_mut()[start] += *d * F::Chan::from_f64(angle.cos());
-            out.channels_mut()[end] += *d * F::Chan::from_f64(angle.sin());
+            PanLaw::Linear => linear,
+        }
+    }
+}
+
+/// Pan each channel of `frame` by the matching channel of `pan`, using a pan
+/// law (see [`Pan`]).
+#[inline(always)]
+pub fn pan<F: Frame>(frame: F, pan: F, law: PanLaw) -> F {
+    let mut out = F::default();
+    for (d, s) in frame.channels().iter().zip(pan.channels().iter()) {
+        let (start, end, gain_start, gain_end) =
+            pan_gains::<F>(s.to_f64(), law);
+        out.channels_mut()[start] += *d * F::Chan::from_f64(gain_start);
+        out.channels_mut()[end] += *d * F::Chan::from_f64(gain_end);
+    }
+    out
+}
+
+/// Position a sample in 3D space with constant power panning.
+///
+/// `azimuth` is the angle in radians from straight ahead (positive is to the
+/// right), and `elevation` is the angle in radians above (or below) the
+/// horizontal plane.  None of the speaker configurations have height speakers,
+/// so elevation spreads the sample evenly over all speakers (except the LFE),
+/// until it's played equally by every speaker straight overhead.
+#[inline(always)]
+pub fn pan_3d<F: Frame>(sample: F::Chan, azimuth: f64, elevation: f64) -> F {
+    let mut out = F::default();
+    let speakers = F::CONFIG.len();
+    if speakers < 2 {
+        out.channels_mut()[0] = sample;
+        return out;
+    }
+    let position =
+        (azimuth / core::f64::consts::PI + 1.0).rem_euclid(2.0) - 1.0;
+    let (start, end, gain_start, gain_end) =
+        pan_gains::<F>(position, PanLaw::ConstantPower);
+    let height = elevation.sin().powi(2);
+    let spread = height / speakers as f64;
+    let value = sample.to_f64();
+    for (i, chan) in out.channels_mut()[..speakers].iter_mut().enumerate() {
+        let gain = if i == start {
+            gain_start
+        } else if i == end {
+            gain_end
+        } else {
+            0.0
+        };
+        let gain = (gain * gain * (1.0 - height) + spread).sqrt();
+        *chan = F::Chan::from_f64_clipped(value * gain, ClipPolicy::Saturate);
+    }
+    out
+}
+
+// Figure out which two speakers a position is between, and their gains.
+#[inline(always)]
+fn pan_gains<F: Frame>(s: f64, law: PanLaw) -> (usize, usize, f64, f64) {
+    // Figure out which two destination channels the audio applies to.
+    let mut start = F::CONFIG.len() - 1;
+    for (i, location) in F::CONFIG.iter().enumerate() {
+        if s >= *location {
+            start = i;
+            break;
+        }
+    }
+    let end = (start + 1) % F::CONFIG.len();
+    // Get distance between channels
+    let mut dist = F::CONFIG[end] - F::CONFIG[start];
+    if dist < 0.0 {
+        dist = 2.0 - dist;
+    }
+    // Get closeness between the two channels (0 thru 1)
+    let closeness = (s - F::CONFIG[end].min(F::CONFIG[start])) / dist;
+    let (gain_start, gain_end) = law.gains(closeness);
+    (start, end, gain_start, gain_end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chan::Ch64,
+        stereo::{Stereo32, Stereo64},
+        surround::Surround64,
+    };
+    use core::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn pan_laws() {
+        let frame = Stereo32::new(1.0, 0.0);
+        let center = Stereo32::new(0.0, 0.0);
+        for (law, db) in [
+            (PanLaw::ConstantPower, -3.0),
+            (PanLaw::Compromise, -4.5),
+            (PanLaw::Linear, -6.0),
+        ] {
+            let out = pan(frame, center, law);
+            let [l, r] = [out.channels()[0], out.channels()[1]];
+            assert_eq!(l, r);
+            assert!((l.to_dbfs() - db).abs() < 0.1, "{:?}", law);
+        }
+        assert_eq!(
+            Pan::mix_frames(frame, center),
+            pan(frame, center, PanLaw::default())
+        );
+        assert_eq!(PanLaw::Linear.gains(1.0), (0.0, 1.0));
+    }
+
+    #[test]
+    fn pan_elevation() {
+        let sample = Ch64::new(1.0);
+        let ahead: Stereo64 = pan_3d(sample, 0.0, 0.0);
+        assert_eq!(
+            ahead,
+            pan(
+                Stereo64::new(1.0, 0.0),
+                Stereo64::default(),
+                PanLaw::ConstantPower
+            )
+        );
+        // Overhead is played equally, and power is kept at every elevation.
+        let overhead: Surround64 = pan_3d(sample, 0.5, FRAC_PI_2);
+        for chan in &overhead.channels()[..5] {
+            assert!((chan.to_f64() - 0.2f64.sqrt()).abs() < 1e-9);
+        }
+        for elevation in [0.0, 0.3, -0.6, 1.2] {
+            let frame: Surround64 = pan_3d(sample, 0.9, elevation);
+            let power: f64 =
+                frame.channels().iter().map(|c| c.to_f64().powi(2)).sum();
+            assert!((power - 1.0).abs() < 1e-9);
         }
-        out
     }
 }
@@ -0,0 +1,155 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Channel orders of file formats and APIs.
+
+// Channel indices of each order for 5.1 surround (see `Surround`).
+const FON: [usize; 6] = [0, 1, 2, 3, 4, 5];
+const WAVE: [usize; 6] = [1, 3, 2, 5, 0, 4];
+const VORBIS: [usize; 6] = [1, 2, 3, 0, 4, 5];
+// Channel indices of each order for 6.1 surround (see `Surround61`).
+const FON_61: [usize; 7] = [0, 1, 2, 3, 4, 5, 6];
+const WAVE_61: [usize; 7] = [1, 3, 2, 6, 5, 0, 4];
+const VORBIS_61: [usize; 7] = [1, 2, 3, 0, 4, 5, 6];
+// Channel indices of each order for 7.1 surround (see `Surround71`).
+const FON_71: [usize; 8] = [0, 1, 2, 3, 4, 5, 6, 7];
+const WAVE_71: [usize; 8] = [2, 4, 3, 7, 0, 6, 1, 5];
+const VORBIS_71: [usize; 8] = [2, 3, 4, 1, 5, 0, 6, 7];
+// Mono and stereo are ordered the same way everywhere.
+const MONO: [usize; 1] = [0];
+const STEREO: [usize; 2] = [0, 1];
+
+/// Order of the channels in a frame, which differs for surround between
+/// containers and APIs.  Mono and stereo are the same in every order.  The
+/// positions listed for each order are for 5.1; 6.1 follows the 5.1 order with
+/// the back center before the sides (after the surrounds for Vorbis), and 7.1
+/// has the back pair there instead.
+///
+/// ```rust
+/// use fon::{surround::Surround32, ChannelOrder, Frame};
+///
+/// // Front left, front right, center, LFE, rear left, rear right.
+/// let wave = Surround32::new(0.1, 0.2, 0.3, 0.4, 0.5, 0.6);
+/// let vorbis =
+///     wave.convert_channel_order(ChannelOrder::Wave, ChannelOrder::Vorbis);
+/// assert_eq!(vorbis, Surround32::new(0.1, 0.3, 0.2, 0.5, 0.6, 0.4));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChannelOrder {
+    /// The order of this crate's frames, following
+    /// [`CONFIG`](crate::Frame::CONFIG) with LFE last: rear left, front
+    /// left, center, front right, rear right, LFE.
+    Fon,
+    /// WAVEFORMATEXTENSIBLE (WAV and most APIs): front left, front right,
+    /// center, LFE, rear left, rear right.
+    Wave,
+    /// SMPTE (FLAC): left, right, center, LFE, left surround, right
+    /// surround (the same positions as [`Wave`](ChannelOrder::Wave)).
+    Smpte,
+    /// Vorbis (and Opus): front left, center, front right, rear left, rear
+    /// right, LFE.
+    Vorbis,
+}
+
+impl ChannelOrder {
+    /// Get the index in a frame of this crate of each channel in this order,
+    /// for frames with `chan_count` channels (`None` if the order isn't
+    /// defined for the channel count).
+    pub fn indices(self, chan_count: usize) -> Option<&'static [usize]> {
+        Some(match (chan_count, self) {
+            (1, _) => &MONO,
+            (2, _) => &STEREO,
+            (6, ChannelOrder::Fon) => &FON,
+            (6, ChannelOrder::Wave) | (6, ChannelOrder::Smpte) => &WAVE,
+            (6, ChannelOrder::Vorbis) => &VORBIS,
+            (7, ChannelOrder::Fon) => &FON_61,
+            (7, ChannelOrder::Wave) | (7, ChannelOrder::Smpte) => &WAVE_61,
+            (7, ChannelOrder::Vorbis) => &VORBIS_61,
+            (8, ChannelOrder::Fon) => &FON_71,
+            (8, ChannelOrder::Wave) | (8, ChannelOrder::Smpte) => &WAVE_71,
+            (8, ChannelOrder::Vorbis) => &VORBIS_71,
+            _ => return None,
+        })
+    }
+
+    // Get the reordering from this order to another, for use with
+    // `reorder_channels()`.
+    pub(crate) fn reorder(
+        self,
+        to: ChannelOrder,
+        chan_count: usize,
+    ) -> impl Iterator<Item = usize> {
+        let unsupported = "Unsupported channel count for channel order";
+        let from = self.indices(chan_count).expect(unsupported);
+        let to = to.indices(chan_count).expect(unsupported);
+        to.iter()
+            .map(move |index| from.iter().position(|i| i == index).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chan::Ch32, stereo::Stereo32, surround::Surround32,
+        surround61::Surround61, surround71::Surround71, Audio, Frame,
+    };
+
+    #[test]
+    fn channel_order() {
+        let frame = Surround32::new(0.1, 0.2, 0.3, 0.4, 0.5, 0.6);
+        let wave =
+            frame.convert_channel_order(ChannelOrder::Fon, ChannelOrder::Wave);
+        assert_eq!(wave, Surround32::new(0.2, 0.4, 0.3, 0.6, 0.1, 0.5));
+        for order in [
+            ChannelOrder::Wave,
+            ChannelOrder::Smpte,
+            ChannelOrder::Vorbis,
+        ] {
+            let there = frame.convert_channel_order(ChannelOrder::Fon, order);
+            let back = there.convert_channel_order(order, ChannelOrder::Fon);
+            assert_eq!(back, frame);
+        }
+
+        let mut audio = Audio::<Surround32>::with_frame(48_000, 2, wave);
+        audio.convert_channel_order(ChannelOrder::Wave, ChannelOrder::Fon);
+        assert!(audio.iter().all(|f| *f == frame));
+
+        let stereo = Stereo32::new(0.25, 0.5);
+        assert_eq!(
+            stereo.convert_channel_order(
+                ChannelOrder::Vorbis,
+                ChannelOrder::Wave
+            ),
+            stereo
+        );
+        assert_eq!(ChannelOrder::Wave.indices(4), None);
+
+        // The sides are the last pair in WAV 6.1 and 7.1.
+        let frame = Surround61::<Ch32>::new(0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7);
+        let wave =
+            frame.convert_channel_order(ChannelOrder::Fon, ChannelOrder::Wave);
+        assert_eq!(wave, Surround61::new(0.2, 0.4, 0.3, 0.7, 0.6, 0.1, 0.5));
+        let frame =
+            Surround71::<Ch32>::new(0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8);
+        let wave =
+            frame.convert_channel_order(ChannelOrder::Fon, ChannelOrder::Wave);
+        assert_eq!(
+            wave,
+            Surround71::new(0.3, 0.5, 0.4, 0.8, 0.1, 0.7, 0.2, 0.6)
+        );
+        let vorbis = wave
+            .convert_channel_order(ChannelOrder::Wave, ChannelOrder::Vorbis);
+        assert_eq!(
+            vorbis,
+            Surround71::new(0.3, 0.4, 0.5, 0.2, 0.6, 0.1, 0.7, 0.8)
+        );
+    }
+}
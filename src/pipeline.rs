@@ -0,0 +1,390 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Format conversion pipelines.
+//!
+//! ```rust
+//! use fon::{
+//!     dither::DitherShape, mono::Mono32, pipeline::Pipeline,
+//!     stereo::Stereo16, Audio,
+//! };
+//!
+//! let mut processor = Pipeline::new()
+//!     .resample(48_000)
+//!     .dither(DitherShape::Flat)
+//!     .build::<Mono32, Stereo16>()
+//!     .unwrap();
+//! let audio = Audio::<Mono32>::with_silence(44_100, 441);
+//! let audio = processor.process(&audio);
+//! assert_eq!(audio.sample_rate(), 48_000.0);
+//! assert_eq!(audio.len(), 480);
+//! ```
+
+use crate::{
+    chan::Channel,
+    dither::{Dither, DitherShape},
+    math,
+    matrix::{self, DownmixMatrix},
+    private::Sealed,
+    Audio, Error, Frame, Sink, Stream, UpmixMode,
+};
+use alloc::vec::Vec;
+use core::{fmt, marker::PhantomData};
+
+/// An incompatible combination of conversion steps, found when building a
+/// [`Pipeline`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PipelineError {
+    /// The channel counts of the mixing matrix don't match the frame types, or
+    /// an upmix was requested for input that isn't mono.
+    ChannelCount,
+    /// There is no default mixing matrix for the frame types (a custom one
+    /// must be set with [`Pipeline::rechannel()`]).
+    NoDefaultMatrix,
+    /// The sample rate isn't a positive, finite number.
+    SampleRate,
+    /// Dither was requested for a floating point output.
+    DitherFloat,
+}
+
+impl fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PipelineError::ChannelCount => {
+                "mixing matrix channel counts don't match the frame types"
+            }
+            PipelineError::NoDefaultMatrix => {
+                "no default mixing matrix for the frame types"
+            }
+            PipelineError::SampleRate => "invalid sample rate",
+            PipelineError::DitherFloat => {
+                "can't dither to a floating point channel type"
+            }
+        })
+    }
+}
+
+/// Builder for a format conversion [`Processor`].
+///
+/// Steps may be added in any order; they always run in the order resample,
+/// rechannel, then dither (quantize).
+#[derive(Clone, Debug, Default)]
+pub struct Pipeline {
+    s_rate: Option<f64>,
+    // Input channel count, output channel count and flattened rows.
+    matrix: Option<(usize, usize, Vec<f64>)>,
+    upmix: Option<UpmixMode>,
+    dither: Option<DitherShape>,
+}
+
+impl Pipeline {
+    /// Start building a pipeline that only converts the frame type.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mix the channels with a custom matrix, rather than the default mixing
+    /// done by [`Frame::convert()`](crate::Frame::convert).
+    pub fn rechannel<const FROM: usize, const TO: usize>(
+        mut self,
+        matrix: DownmixMatrix<FROM, TO>,
+    ) -> Self {
+        let rows = matrix.rows().iter().flatten().cloned().collect();
+        self.matrix = Some((FROM, TO, rows));
+        self.upmix = None;
+        self
+    }
+
+    /// Upmix mono input to the output speakers with an [`UpmixMode`], rather
+    /// than the default mixing done by
+    /// [`Frame::convert()`](crate::Frame::convert).  Replaces any matrix set
+    /// with [`rechannel()`](Pipeline::rechannel).
+    pub fn upmix(mut self, mode: UpmixMode) -> Self {
+        self.upmix = Some(mode);
+        self.matrix = None;
+        self
+    }
+
+    /// Resample to a new sample rate.
+    pub fn resample<R: Into<f64>>(mut self, s_rate: R) -> Self {
+        self.s_rate = Some(s_rate.into());
+        self
+    }
+
+    /// Dither when reducing the bit depth.
+    pub fn dither(mut self, shape: DitherShape) -> Self {
+        self.dither = Some(shape);
+        self
+    }
+
+    /// Build a processor converting frames of type `F` into frames of type
+    /// `G`.
+    pub fn build<F: Frame, G: Frame>(
+        self,
+    ) -> Result<Processor<F, G>, PipelineError> {
+        let (from, to) = (F::CHAN_COUNT, G::CHAN_COUNT);
+        let rows = match (self.upmix, self.matrix) {
+            (Some(_), _) if from != 1 => {
+                return Err(PipelineError::ChannelCount);
+            }
+            (Some(mode), _) => matrix::upmix_rows(mode, to)
+                .ok_or(PipelineError::NoDefaultMatrix)?,
+            (None, Some((m_from, m_to, rows))) => {
+                if (m_from, m_to) != (from, to) {
+                    return Err(PipelineError::ChannelCount);
+                }
+                rows
+            }
+            (None, None) => matrix::default_rows(from, to)
+                .ok_or(PipelineError::NoDefaultMatrix)?,
+        };
+        if let Some(s_rate) = self.s_rate {
+            if !(s_rate.is_finite() && s_rate > 0.0) {
+                return Err(PipelineError::SampleRate);
+            }
+        }
+        if self.dither.is_some() && <G::Chan as Sealed>::STEPS.is_none() {
+            return Err(PipelineError::DitherFloat);
+        }
+        Ok(Processor {
+            s_rate: self.s_rate,
+            rows,
+            dither: self.dither.map(Dither::new),
+            _phantom: PhantomData,
+        })
+    }
+}
+
+/// Format converter built with a [`Pipeline`].
+#[derive(Clone, Debug)]
+pub struct Processor<F: Frame, G: Frame> {
+    s_rate: Option<f64>,
+    rows: Vec<f64>,
+    dither: Option<Dither>,
+    _phantom: PhantomData<(F, G)>,
+}
+
+impl<F: Frame, G: Frame> Processor<F, G> {
+    /// Get the sample rate audio is resampled to, if any.
+    pub fn sample_rate(&self) -> Option<f64> {
+        self.s_rate
+    }
+
+    /// Convert an `Audio` buffer.
+    pub fn process(&mut self, audio: &Audio<F>) -> Audio<G> {
+        match self.s_rate {
+            Some(s_rate) if s_rate != audio.sample_rate() => {
+                let audio = Audio::<F>::with_stream(s_rate, audio);
+                self.convert(&audio)
+            }
+            _ => self.convert(audio),
+        }
+    }
+
+    /// Convert the audio from a stream.  Streams without a sample rate are
+    /// given the pipeline's sample rate.
+    ///
+    /// # Panics
+    /// When an infinite stream is passed in, or if neither the stream nor the
+    /// pipeline has a sample rate.
+    pub fn process_stream<M: Stream<F>>(&mut self, stream: M) -> Audio<G> {
+        let s_rate = self
+            .s_rate
+            .or_else(|| stream.sample_rate())
+            .expect("Processor::process_stream() called without sample rate");
+        let audio = Audio::<F>::with_stream(s_rate, stream);
+        self.convert(&audio)
+    }
+
+    // Rechannel and quantize.
+    fn convert(&mut self, audio: &Audio<F>) -> Audio<G> {
+        let mut out = Audio::with_capacity(audio.sample_rate(), audio.len());
+        Extend::extend(&mut out, audio.iter().map(|frame| self.frame(*frame)));
+        out
+    }
+
+    // Rechannel and quantize one frame.
+    fn frame(&mut self, frame: F) -> G {
+        let chans = frame.channels();
+        let mixed = self.rows.chunks_exact(F::CHAN_COUNT).map(|row| {
+            row.iter()
+                .zip(chans)
+                .map(|(gain, chan)| gain * chan.to_f64())
+                .sum::<f64>()
+        });
+        if let Some(dither) = &mut self.dither {
+            dither.quantize_f64(mixed)
+        } else {
+            let mut out = G::default();
+            for (dst, src) in out.channels_mut().iter_mut().zip(mixed) {
+                *dst = G::Chan::from_f64(src);
+            }
+            out
+        }
+    }
+}
+
+/// Batch converter for processing many `Audio` buffers with the same
+/// settings.
+///
+/// Unlike [`Processor::process()`], the buffer audio is resampled into is
+/// kept between calls, so converting thousands of small buffers doesn't
+/// allocate more than one output buffer each.  Dither state also carries over
+/// from one buffer to the next.
+///
+/// ```rust
+/// use fon::{
+///     dither::DitherShape, mono::Mono32, pipeline::{Converter, Pipeline},
+///     stereo::Stereo16, Audio,
+/// };
+///
+/// let settings = Pipeline::new().resample(48_000).dither(DitherShape::Flat);
+/// let mut converter = Converter::<Mono32, Stereo16>::new(settings).unwrap();
+/// for len in [441, 882, 4410] {
+///     let audio = Audio::<Mono32>::with_silence(44_100, len);
+///     let audio = converter.convert(&audio);
+///     assert_eq!(audio.sample_rate(), 48_000.0);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Converter<F: Frame, G: Frame> {
+    processor: Processor<F, G>,
+    // Resampled audio, if resampling.
+    scratch: Option<Audio<F>>,
+}
+
+impl<F: Frame, G: Frame> Converter<F, G> {
+    /// Create a converter from pipeline settings, failing with
+    /// [`Error::Pipeline`] if they can't be built.
+    pub fn new(settings: Pipeline) -> Result<Self, Error> {
+        let processor = settings.build()?;
+        let scratch = processor
+            .s_rate
+            .map(|s_rate| Audio::with_silence(s_rate, 0));
+        Ok(Self { processor, scratch })
+    }
+
+    /// Get the processor used for conversion.
+    pub fn processor(&self) -> &Processor<F, G> {
+        &self.processor
+    }
+
+    /// Convert an `Audio` buffer.
+    pub fn convert(&mut self, audio: &Audio<F>) -> Audio<G> {
+        let scratch = match &mut self.scratch {
+            Some(scratch) if scratch.sample_rate() != audio.sample_rate() => {
+                scratch
+            }
+            _ => return self.processor.convert(audio),
+        };
+        let len = scratch.sample_rate() * audio.len() as f64;
+        let len = math::ceil(len / audio.sample_rate()) as usize;
+        scratch.truncate(0);
+        scratch.resize(len, F::default());
+        {
+            let mut sink = scratch.sink(..);
+            sink.stream(audio);
+            sink.flush();
+        }
+        self.processor.convert(scratch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mono::{Mono16, Mono64},
+        stereo::{Stereo32, Stereo64},
+        surround::Surround32,
+    };
+
+    #[test]
+    fn validation() {
+        let matrix = DownmixMatrix::new([[0.5, 0.5]]);
+        let err = Pipeline::new().rechannel(matrix).build::<Mono64, Mono64>();
+        assert_eq!(err.unwrap_err(), PipelineError::ChannelCount);
+        let err = Pipeline::new().resample(0).build::<Mono64, Mono64>();
+        assert_eq!(err.unwrap_err(), PipelineError::SampleRate);
+        let err = Pipeline::new()
+            .dither(DitherShape::Flat)
+            .build::<Mono64, Mono64>();
+        assert_eq!(err.unwrap_err(), PipelineError::DitherFloat);
+        let err = Pipeline::new()
+            .upmix(UpmixMode::CenterOnly)
+            .build::<Stereo64, Surround32>();
+        assert_eq!(err.unwrap_err(), PipelineError::ChannelCount);
+    }
+
+    #[test]
+    fn upmix() {
+        let audio = Audio::<Mono64>::with_frame(48_000, 4, Mono64::new(0.5));
+        let mut processor = Pipeline::new()
+            .upmix(UpmixMode::CenterOnly)
+            .build::<Mono64, Surround32>()
+            .unwrap();
+        let surround = processor.process(&audio);
+        let center = Surround32::new(0.0, 0.0, 0.5, 0.0, 0.0, 0.0);
+        assert_eq!(surround.get(3), Some(center));
+    }
+
+    #[test]
+    fn rechannel() {
+        let audio = Audio::<Surround32>::with_frame(
+            48_000,
+            10,
+            Surround32::new(0.25, 0.5, 0.0, 0.5, 0.25, 0.0),
+        );
+        let mut processor = Pipeline::new().build().unwrap();
+        let stereo: Audio<Stereo32> = processor.process(&audio);
+        assert_eq!(stereo.get(0), audio.get(0).map(|f| f.convert()));
+
+        let matrix = DownmixMatrix::new([[0.0, 1.0, 0.0, 1.0, 0.0, 0.0]]);
+        let mut processor = Pipeline::new()
+            .rechannel(matrix)
+            .dither(DitherShape::SecondOrder)
+            .build::<Surround32, Mono16>()
+            .unwrap();
+        let mono = processor.process(&audio);
+        assert_eq!(mono.len(), 10);
+        assert!(i16::from(mono.get(9).unwrap().channels()[0]) > 32_700);
+    }
+
+    #[test]
+    fn converter() {
+        let settings = Pipeline::new().resample(48_000);
+        let mut processor = settings.clone().build::<Mono64, Mono64>().unwrap();
+        let mut converter = Converter::<Mono64, Mono64>::new(settings).unwrap();
+        for len in [10, 441, 100] {
+            let samples: Vec<f64> =
+                (0..len).map(|i| i as f64 / 500.0).collect();
+            let audio = Audio::<Mono64>::with_f64_buffer(44_100, samples);
+            let expected = processor.process(&audio);
+            let converted = converter.convert(&audio);
+            assert_eq!(converted.sample_rate(), 48_000.0);
+            assert!(converted.iter().eq(expected.iter()));
+        }
+        let audio = Audio::<Mono64>::with_silence(48_000, 10);
+        assert_eq!(converter.convert(&audio).len(), 10);
+    }
+
+    #[test]
+    fn stream() {
+        let audio = Audio::<Mono64>::with_silence(24_000, 100);
+        let mut processor = Pipeline::new()
+            .resample(48_000)
+            .build::<Mono64, Stereo64>()
+            .unwrap();
+        assert_eq!(processor.sample_rate(), Some(48_000.0));
+        let stereo = processor.process_stream(&audio);
+        assert_eq!(stereo.len(), 200);
+        assert_eq!(stereo.sample_rate(), 48_000.0);
+    }
+}
@@ -0,0 +1,122 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Per-channel (planar) access.
+
+use crate::{mono::Mono, Audio, Frame};
+use alloc::vec::Vec;
+
+impl<F: Frame> Audio<F> {
+    /// Returns an iterator over the values of one channel.
+    ///
+    /// ```rust
+    /// use fon::{chan::Ch32, stereo::Stereo32, Audio};
+    ///
+    /// let audio = Audio::<Stereo32>::with_f32_buffer(48_000, [0.25, 0.5]);
+    /// assert_eq!(audio.channel_iter(1).collect::<Vec<_>>(), [Ch32::new(0.5)]);
+    /// ```
+    ///
+    /// # Panics
+    /// If `channel` is out of bounds.
+    pub fn channel_iter(
+        &self,
+        channel: usize,
+    ) -> impl Iterator<Item = F::Chan> + '_ {
+        assert!(channel < F::CHAN_COUNT, "Channel out of bounds");
+        self.iter().map(move |frame| frame.channels()[channel])
+    }
+
+    /// Copy one channel into a new mono `Audio` buffer.
+    ///
+    /// # Panics
+    /// If `channel` is out of bounds.
+    pub fn extract_channel(&self, channel: usize) -> Audio<Mono<F::Chan>> {
+        let mut audio = Audio::with_capacity(self.sample_rate(), self.len());
+        Extend::extend(&mut audio, self.channel_iter(channel).map(Mono::new));
+        audio
+    }
+
+    /// Copy the audio into one buffer per channel (planar layout).
+    pub fn to_planar(&self) -> Vec<Vec<F::Chan>> {
+        (0..F::CHAN_COUNT)
+            .map(|channel| self.channel_iter(channel).collect())
+            .collect()
+    }
+
+    /// Construct an `Audio` buffer from one buffer per channel (planar
+    /// layout).
+    ///
+    /// ```rust
+    /// use fon::{chan::Ch16, stereo::Stereo16, Audio};
+    ///
+    /// let left = [Ch16::new(1), Ch16::new(2)];
+    /// let right = [Ch16::new(3), Ch16::new(4)];
+    /// let audio = Audio::<Stereo16>::from_planar(48_000, &[&left, &right]);
+    /// assert_eq!(audio.to_planar(), [left, right]);
+    /// ```
+    ///
+    /// # Panics
+    /// If the number of buffers isn't the frame's channel count, or if the
+    /// buffers aren't all the same length.
+    pub fn from_planar<R: Into<f64>>(s_rate: R, planes: &[&[F::Chan]]) -> Self {
+        assert_eq!(planes.len(), F::CHAN_COUNT, "Wrong number of channels");
+        let len = planes.first().map_or(0, |plane| plane.len());
+        assert!(
+            planes.iter().all(|plane| plane.len() == len),
+            "Channel buffer length mismatch"
+        );
+        let mut audio = Self::with_capacity(s_rate, len);
+        let mut chans = Vec::with_capacity(F::CHAN_COUNT);
+        for index in 0..len {
+            chans.clear();
+            chans.extend(planes.iter().map(|plane| plane[index]));
+            audio.push(F::from_channels(&chans));
+        }
+        audio
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        chan::Ch32, mono::Mono32, stereo::Stereo32, surround::Surround32, Audio,
+    };
+
+    #[test]
+    fn extract_channel() {
+        let samples: Vec<f32> = (0..12).map(|i| i as f32 / 16.0).collect();
+        let audio = Audio::<Surround32>::with_f32_buffer(48_000, samples);
+        let center = audio.extract_channel(2);
+        assert_eq!(center.len(), 2);
+        assert_eq!(center.sample_rate(), 48_000.0);
+        assert_eq!(center.get(0), Some(Mono32::new(2.0 / 16.0)));
+        assert_eq!(center.get(1), Some(Mono32::new(8.0 / 16.0)));
+    }
+
+    #[test]
+    fn planar() {
+        let samples: Vec<f32> = (0..12).map(|i| i as f32 / 16.0).collect();
+        let audio = Audio::<Surround32>::with_f32_buffer(48_000, samples);
+        let planes = audio.to_planar();
+        assert_eq!(planes.len(), 6);
+        assert_eq!(planes[5], [Ch32::new(5.0 / 16.0), Ch32::new(11.0 / 16.0)]);
+        let planes: Vec<&[Ch32]> = planes.iter().map(|p| &p[..]).collect();
+        let back = Audio::<Surround32>::from_planar(48_000, &planes);
+        assert!(back.iter().eq(audio.iter()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Channel buffer length mismatch")]
+    fn planar_mismatch() {
+        let left = [Ch32::new(0.0); 2];
+        let right = [Ch32::new(0.0); 3];
+        let _ = Audio::<Stereo32>::from_planar(48_000, &[&left, &right]);
+    }
+}
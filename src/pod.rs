@@ -0,0 +1,96 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! `bytemuck` trait implementations.
+//!
+//! Channels are `repr(transparent)` over a primitive, and frames are
+//! `repr(transparent)` over an array of channels, so none of them have
+//! padding.  Every bit pattern is a valid `Ch8`, `Ch16`, `Ch32` or `Ch64`, so
+//! those (and their frames) are `Pod`.  `Ch12`, `Ch20` and `Ch24` only use
+//! part of the range of the primitive they're stored in, so they're
+//! `CheckedBitPattern` instead (and can still be cast to bytes, as
+//! `NoUninit`).
+
+use crate::{
+    chan::{Ch12, Ch16, Ch20, Ch24, Ch32, Ch64, Ch8, Channel},
+    mono::Mono,
+    stereo::Stereo,
+    surround::Surround,
+    surround61::Surround61,
+    surround71::Surround71,
+};
+use bytemuck::{CheckedBitPattern, NoUninit, Pod, Zeroable};
+
+macro_rules! pod {
+    ($($chan:ty),*) => {
+        $(
+            unsafe impl Zeroable for $chan {}
+            unsafe impl Pod for $chan {}
+        )*
+    };
+}
+
+pod!(Ch8, Ch16, Ch32, Ch64);
+
+macro_rules! checked {
+    ($($chan:ident($bits:ty)),*) => {
+        $(
+            unsafe impl Zeroable for $chan {}
+            unsafe impl NoUninit for $chan {}
+            unsafe impl CheckedBitPattern for $chan {
+                type Bits = $bits;
+
+                fn is_valid_bit_pattern(bits: &$bits) -> bool {
+                    let range = <$bits>::from($chan::MIN)..=<$bits>::from($chan::MAX);
+                    range.contains(bits)
+                }
+            }
+        )*
+    };
+}
+
+checked!(Ch12(i16), Ch20(i32), Ch24(i32));
+
+unsafe impl<C: Channel + Zeroable> Zeroable for Mono<C> {}
+unsafe impl<C: Channel + Pod> Pod for Mono<C> {}
+unsafe impl<C: Channel + Zeroable> Zeroable for Stereo<C> {}
+unsafe impl<C: Channel + Pod> Pod for Stereo<C> {}
+unsafe impl<C: Channel + Zeroable> Zeroable for Surround<C> {}
+unsafe impl<C: Channel + Pod> Pod for Surround<C> {}
+unsafe impl<C: Channel + Zeroable> Zeroable for Surround61<C> {}
+unsafe impl<C: Channel + Pod> Pod for Surround61<C> {}
+unsafe impl<C: Channel + Zeroable> Zeroable for Surround71<C> {}
+unsafe impl<C: Channel + Pod> Pod for Surround71<C> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        chan::{Ch12, Channel},
+        mono::Mono32,
+        stereo::Stereo16,
+        Audio,
+    };
+
+    #[test]
+    fn cast() {
+        let mut audio = Audio::<Stereo16>::with_i16_buffer(48_000, [1, -2]);
+        let samples: &[i16] = bytemuck::cast_slice(audio.as_slice());
+        assert_eq!(samples, [1, -2]);
+        let frames: &[Mono32] = bytemuck::cast_slice(&[0.5f32, -0.25]);
+        assert_eq!(frames[1], Mono32::new(-0.25));
+        let zero: Stereo16 = bytemuck::Zeroable::zeroed();
+        assert_eq!(zero, Stereo16::default());
+        let chans: &[Ch12] = bytemuck::checked::cast_slice(&[2047i16, -2048]);
+        assert_eq!(chans, [Ch12::MAX, Ch12::MIN]);
+        let out_of_range =
+            bytemuck::checked::try_cast_slice::<i16, Ch12>(&[i16::MAX]);
+        assert!(out_of_range.is_err());
+    }
+}
@@ -8,11 +8,194 @@
 // At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
 // LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
 
-use crate::chan::{Ch16, Ch32, Ch64, Ch8};
+use crate::chan::{Ch12, Ch16, Ch20, Ch24, Ch32, Ch64, Ch8};
 use core::any::Any;
 
-pub trait Sealed: Any {}
-impl Sealed for Ch8 {}
-impl Sealed for Ch16 {}
-impl Sealed for Ch32 {}
-impl Sealed for Ch64 {}
+pub trait Sealed: Any {
+    /// Quantization steps between zero and one (`None` for floating point).
+    const STEPS: Option<f64> = None;
+
+    /// Decode from little endian bytes.
+    fn from_le(bytes: &[u8]) -> Self;
+
+    /// Decode from big endian bytes.
+    fn from_be(bytes: &[u8]) -> Self;
+
+    /// Encode as little endian bytes.
+    fn to_le(self, bytes: &mut [u8]);
+
+    /// Encode as big endian bytes.
+    fn to_be(self, bytes: &mut [u8]);
+}
+
+/// Channels where every bit pattern of the underlying primitive is valid.
+pub trait AnyBits: Sealed {}
+
+impl AnyBits for Ch8 {}
+impl AnyBits for Ch16 {}
+impl AnyBits for Ch32 {}
+impl AnyBits for Ch64 {}
+
+impl Sealed for Ch8 {
+    const STEPS: Option<f64> = Some(127.5);
+
+    fn from_le(bytes: &[u8]) -> Self {
+        let mut array = [0; 1];
+        array.copy_from_slice(&bytes[..1]);
+        Ch8::new(i8::from_le_bytes(array))
+    }
+
+    fn from_be(bytes: &[u8]) -> Self {
+        let mut array = [0; 1];
+        array.copy_from_slice(&bytes[..1]);
+        Ch8::new(i8::from_be_bytes(array))
+    }
+
+    fn to_le(self, bytes: &mut [u8]) {
+        bytes[..1].copy_from_slice(&i8::from(self).to_le_bytes());
+    }
+
+    fn to_be(self, bytes: &mut [u8]) {
+        bytes[..1].copy_from_slice(&i8::from(self).to_be_bytes());
+    }
+}
+
+impl Sealed for Ch16 {
+    const STEPS: Option<f64> = Some(32767.5);
+
+    fn from_le(bytes: &[u8]) -> Self {
+        let mut array = [0; 2];
+        array.copy_from_slice(&bytes[..2]);
+        Ch16::new(i16::from_le_bytes(array))
+    }
+
+    fn from_be(bytes: &[u8]) -> Self {
+        let mut array = [0; 2];
+        array.copy_from_slice(&bytes[..2]);
+        Ch16::new(i16::from_be_bytes(array))
+    }
+
+    fn to_le(self, bytes: &mut [u8]) {
+        bytes[..2].copy_from_slice(&i16::from(self).to_le_bytes());
+    }
+
+    fn to_be(self, bytes: &mut [u8]) {
+        bytes[..2].copy_from_slice(&i16::from(self).to_be_bytes());
+    }
+}
+
+impl Sealed for Ch12 {
+    const STEPS: Option<f64> = Some(2047.5);
+
+    fn from_le(bytes: &[u8]) -> Self {
+        let mut array = [0; 2];
+        array.copy_from_slice(&bytes[..2]);
+        Ch12::new(i16::from_le_bytes(array))
+    }
+
+    fn from_be(bytes: &[u8]) -> Self {
+        let mut array = [0; 2];
+        array.copy_from_slice(&bytes[..2]);
+        Ch12::new(i16::from_be_bytes(array))
+    }
+
+    fn to_le(self, bytes: &mut [u8]) {
+        bytes[..2].copy_from_slice(&i16::from(self).to_le_bytes());
+    }
+
+    fn to_be(self, bytes: &mut [u8]) {
+        bytes[..2].copy_from_slice(&i16::from(self).to_be_bytes());
+    }
+}
+
+impl Sealed for Ch20 {
+    const STEPS: Option<f64> = Some(524287.5);
+
+    fn from_le(bytes: &[u8]) -> Self {
+        let mut array = [0; 4];
+        array.copy_from_slice(&bytes[..4]);
+        Ch20::new(i32::from_le_bytes(array))
+    }
+
+    fn from_be(bytes: &[u8]) -> Self {
+        let mut array = [0; 4];
+        array.copy_from_slice(&bytes[..4]);
+        Ch20::new(i32::from_be_bytes(array))
+    }
+
+    fn to_le(self, bytes: &mut [u8]) {
+        bytes[..4].copy_from_slice(&i32::from(self).to_le_bytes());
+    }
+
+    fn to_be(self, bytes: &mut [u8]) {
+        bytes[..4].copy_from_slice(&i32::from(self).to_be_bytes());
+    }
+}
+
+impl Sealed for Ch24 {
+    const STEPS: Option<f64> = Some(8388607.5);
+
+    fn from_le(bytes: &[u8]) -> Self {
+        let mut array = [0; 4];
+        array.copy_from_slice(&bytes[..4]);
+        Ch24::new(i32::from_le_bytes(array))
+    }
+
+    fn from_be(bytes: &[u8]) -> Self {
+        let mut array = [0; 4];
+        array.copy_from_slice(&bytes[..4]);
+        Ch24::new(i32::from_be_bytes(array))
+    }
+
+    fn to_le(self, bytes: &mut [u8]) {
+        bytes[..4].copy_from_slice(&i32::from(self).to_le_bytes());
+    }
+
+    fn to_be(self, bytes: &mut [u8]) {
+        bytes[..4].copy_from_slice(&i32::from(self).to_be_bytes());
+    }
+}
+
+impl Sealed for Ch32 {
+    fn from_le(bytes: &[u8]) -> Self {
+        let mut array = [0; 4];
+        array.copy_from_slice(&bytes[..4]);
+        Ch32::new(f32::from_le_bytes(array))
+    }
+
+    fn from_be(bytes: &[u8]) -> Self {
+        let mut array = [0; 4];
+        array.copy_from_slice(&bytes[..4]);
+        Ch32::new(f32::from_be_bytes(array))
+    }
+
+    fn to_le(self, bytes: &mut [u8]) {
+        bytes[..4].copy_from_slice(&f32::from(self).to_le_bytes());
+    }
+
+    fn to_be(self, bytes: &mut [u8]) {
+        bytes[..4].copy_from_slice(&f32::from(self).to_be_bytes());
+    }
+}
+
+impl Sealed for Ch64 {
+    fn from_le(bytes: &[u8]) -> Self {
+        let mut array = [0; 8];
+        array.copy_from_slice(&bytes[..8]);
+        Ch64::new(f64::from_le_bytes(array))
+    }
+
+    fn from_be(bytes: &[u8]) -> Self {
+        let mut array = [0; 8];
+        array.copy_from_slice(&bytes[..8]);
+        Ch64::new(f64::from_be_bytes(array))
+    }
+
+    fn to_le(self, bytes: &mut [u8]) {
+        bytes[..8].copy_from_slice(&f64::from(self).to_le_bytes());
+    }
+
+    fn to_be(self, bytes: &mut [u8]) {
+        bytes[..8].copy_from_slice(&f64::from(self).to_be_bytes());
+    }
+}
@@ -0,0 +1,216 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{private::Sealed, Audio, Frame};
+use alloc::{collections::VecDeque, vec, vec::Vec};
+use core::{convert::TryFrom, mem::size_of};
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom};
+
+/// Streaming reader for large raw (headerless, interleaved little endian) PCM
+/// files.
+///
+/// Channels are stored the same way as in memory: 8-bit is signed, and 12-,
+/// 20- and 24-bit channels take the full 2 or 4 bytes of the integer they're
+/// stored in.  So the data chunk of a WAV file can only be read directly if
+/// it's 16-bit, or 32- or 64-bit float (WAV stores 8-bit as unsigned and
+/// 24-bit packed in 3 bytes, which can be loaded into memory with
+/// [`Audio::with_i24_packed_buffer()`](crate::Audio::with_i24_packed_buffer)).
+///
+/// Frames are read in chunks into [`Audio`](crate::Audio) buffers, with
+/// frame-accurate seeking and read-ahead so that editor and DAW backends can
+/// stream from disk.
+#[derive(Debug)]
+pub struct PcmReader<F: Frame, R: Read + Seek> {
+    reader: R,
+    s_rate: f64,
+    // Byte offset of the first frame.
+    offset: u64,
+    // Total number of frames.
+    len: usize,
+    // Index of the next frame to be read.
+    position: usize,
+    // Frames read ahead of `position`.
+    prefetched: VecDeque<F>,
+    prefetch: usize,
+    bytes: Vec<u8>,
+}
+
+impl<F: Frame, R: Read + Seek> PcmReader<F, R> {
+    /// Create a new reader for PCM data starting `offset` bytes into `reader`,
+    /// and continuing until the end.
+    ///
+    /// Fails with [`ErrorKind::InvalidData`] if there are more frames than
+    /// fit in a `usize` (on 32-bit targets).
+    pub fn new<H: Into<f64>>(
+        mut reader: R,
+        s_rate: H,
+        offset: u64,
+    ) -> Result<Self> {
+        let end = reader.seek(SeekFrom::End(0))?;
+        let frames = end.saturating_sub(offset) / size_of::<F>() as u64;
+        let len = usize::try_from(frames).map_err(|_| {
+            Error::new(ErrorKind::InvalidData, "Too many frames for usize")
+        })?;
+        reader.seek(SeekFrom::Start(offset))?;
+        Ok(Self {
+            reader,
+            s_rate: s_rate.into(),
+            offset,
+            len,
+            position: 0,
+            prefetched: VecDeque::new(),
+            prefetch: 4096,
+            bytes: Vec::new(),
+        })
+    }
+
+    /// Set how many frames to read ahead of the current position (default:
+    /// 4096).
+    pub fn with_prefetch(mut self, frames: usize) -> Self {
+        self.prefetch = frames;
+        self
+    }
+
+    /// Get the sample rate of the PCM data.
+    pub fn sample_rate(&self) -> f64 {
+        self.s_rate
+    }
+
+    /// Get the total number of frames.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if there are no frames.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the index of the next frame to be read.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Get the number of frames left to read.
+    pub fn remaining(&self) -> usize {
+        self.len - self.position
+    }
+
+    /// Seek to a frame index (clamped to the length).  Seeking within the
+    /// prefetched region doesn't touch the underlying reader.
+    pub fn seek(&mut self, frame: usize) -> Result<()> {
+        let frame = frame.min(self.len);
+        let ahead = self.prefetched.len();
+        if frame >= self.position && frame <= self.position + ahead {
+            self.prefetched.drain(..frame - self.position);
+        } else {
+            self.prefetched.clear();
+            let byte = self.offset + frame as u64 * size_of::<F>() as u64;
+            self.reader.seek(SeekFrom::Start(byte))?;
+        }
+        self.position = frame;
+        Ok(())
+    }
+
+    /// Read up to `frames` frames into an `Audio` buffer.  The returned buffer
+    /// is shorter when the end of the data is reached.
+    pub fn read(&mut self, frames: usize) -> Result<Audio<F>> {
+        let frames = frames.min(self.remaining());
+        if self.prefetched.len() < frames {
+            self.fill(frames - self.prefetched.len() + self.prefetch)?;
+        }
+        let audio: Vec<F> = self.prefetched.drain(..frames).collect();
+        self.position += frames;
+        if self.prefetched.is_empty() {
+            self.fill(self.prefetch)?;
+        }
+        Ok(Audio::with_frames(self.s_rate, audio))
+    }
+
+    // Read ahead `frames` more frames (fewer at the end of the data).
+    fn fill(&mut self, frames: usize) -> Result<()> {
+        let start = self.position + self.prefetched.len();
+        let frames = frames.min(self.len - start);
+        let chan = size_of::<F::Chan>();
+        self.bytes.resize(frames * size_of::<F>(), 0);
+        self.reader.read_exact(&mut self.bytes)?;
+        let mut chans = vec![F::Chan::default(); F::CHAN_COUNT];
+        for frame in self.bytes.chunks_exact(size_of::<F>()) {
+            for (dst, src) in chans.iter_mut().zip(frame.chunks_exact(chan)) {
+                *dst = F::Chan::from_le(src);
+            }
+            self.prefetched.push_back(F::from_channels(&chans));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stereo::Stereo16;
+    use std::io::Cursor;
+
+    fn pcm() -> Cursor<Vec<u8>> {
+        // A 4 byte header followed by 1000 stereo frames.
+        let mut bytes = vec![b'H', b'E', b'A', b'D'];
+        for i in 0..1000i16 {
+            bytes.extend(&i.to_le_bytes());
+            bytes.extend(&(-i).to_le_bytes());
+        }
+        Cursor::new(bytes)
+    }
+
+    #[test]
+    fn read_chunks() {
+        let mut reader =
+            PcmReader::<Stereo16, _>::new(pcm(), 44_100, 4).unwrap();
+        reader = reader.with_prefetch(64);
+        assert_eq!(reader.len(), 1000);
+        let mut total = 0;
+        loop {
+            let chunk = reader.read(300).unwrap();
+            for (i, frame) in chunk.iter().enumerate() {
+                let i = (total + i) as i16;
+                assert_eq!(*frame, Stereo16::new(i, -i));
+            }
+            total += chunk.len();
+            if chunk.is_empty() {
+                break;
+            }
+        }
+        assert_eq!(total, 1000);
+        assert_eq!(reader.remaining(), 0);
+    }
+
+    #[test]
+    fn seek() {
+        let mut reader =
+            PcmReader::<Stereo16, _>::new(pcm(), 44_100, 4).unwrap();
+        reader.seek(900).unwrap();
+        assert_eq!(
+            reader.read(1).unwrap().get(0),
+            Some(Stereo16::new(900, -900))
+        );
+        // Seek backwards, then forwards within the prefetched region.
+        reader.seek(10).unwrap();
+        assert_eq!(
+            reader.read(1).unwrap().get(0),
+            Some(Stereo16::new(10, -10))
+        );
+        reader.seek(20).unwrap();
+        assert_eq!(
+            reader.read(1).unwrap().get(0),
+            Some(Stereo16::new(20, -20))
+        );
+        reader.seek(5000).unwrap();
+        assert!(reader.read(1).unwrap().is_empty());
+    }
+}
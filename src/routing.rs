@@ -0,0 +1,133 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Convolution reverb routing.
+
+use crate::Frame;
+use alloc::vec::Vec;
+
+/// One path through a multichannel impulse response: input channel `input`
+/// is convolved with impulse response channel `ir`, and summed into output
+/// channel `output`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IrRoute {
+    /// Channel of the stereo input.
+    pub input: usize,
+    /// Channel of the impulse response.
+    pub ir: usize,
+    /// Channel of the output frame.
+    pub output: usize,
+}
+
+/// Channel layout of a multichannel impulse response for a stereo source,
+/// for routing a convolution reverb into an output speaker layout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IrLayout {
+    /// True stereo: 4 channels, the left and right responses to the left
+    /// input, then the left and right responses to the right input.
+    TrueStereo,
+    /// Quad: 8 channels, the front left, front right, rear left and rear
+    /// right responses to the left input, then the same for the right input.
+    /// Without rear speakers, rear responses are mixed into the front.
+    Quad,
+}
+
+impl IrLayout {
+    /// Get the number of channels in the impulse response.
+    pub fn channels(self) -> usize {
+        match self {
+            IrLayout::TrueStereo => 4,
+            IrLayout::Quad => 8,
+        }
+    }
+
+    /// Get the routes from a stereo input through the impulse response to
+    /// the speakers of frame type `G`, found from its
+    /// [`CONFIG`](crate::Frame::CONFIG).  The center and LFE aren't fed.
+    /// Mono output gets every response.
+    pub fn routes<G: Frame>(self) -> Vec<IrRoute> {
+        let speakers = [
+            speaker::<G>(true, false),
+            speaker::<G>(false, false),
+            speaker::<G>(true, true),
+            speaker::<G>(false, true),
+        ];
+        let per_input = self.channels() / 2;
+        let mut routes = Vec::new();
+        for input in 0..2 {
+            for (response, output) in
+                speakers.iter().take(per_input).enumerate()
+            {
+                routes.push(IrRoute {
+                    input,
+                    ir: input * per_input + response,
+                    output: *output,
+                });
+            }
+        }
+        routes
+    }
+}
+
+// Get the channel index of the front (or rear) left (or right) speaker,
+// falling back to front speakers, and then the first channel.
+fn speaker<G: Frame>(left: bool, rear: bool) -> usize {
+    let speakers = G::CONFIG.len().min(G::CHAN_COUNT);
+    let side: Vec<usize> = (0..speakers)
+        .filter(|i| {
+            let position = G::CONFIG[*i];
+            if left {
+                position < 0.0
+            } else {
+                position > 0.0
+            }
+        })
+        .collect();
+    let by_distance = |a: &&usize, b: &&usize| {
+        let (a, b) = (G::CONFIG[**a].abs(), G::CONFIG[**b].abs());
+        a.partial_cmp(&b).unwrap()
+    };
+    let found = if rear && side.len() > 1 {
+        side.iter().max_by(by_distance)
+    } else {
+        side.iter().min_by(by_distance)
+    };
+    found.cloned().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mono::Mono32, stereo::Stereo32, surround::Surround32};
+
+    fn outputs<G: Frame>(layout: IrLayout) -> Vec<(usize, usize, usize)> {
+        layout
+            .routes::<G>()
+            .iter()
+            .map(|r| (r.input, r.ir, r.output))
+            .collect()
+    }
+
+    #[test]
+    fn routes() {
+        let true_stereo = [(0, 0, 0), (0, 1, 1), (1, 2, 0), (1, 3, 1)];
+        assert_eq!(outputs::<Stereo32>(IrLayout::TrueStereo), true_stereo);
+        // Front left is channel 1, and front right is channel 3.
+        let routes = outputs::<Surround32>(IrLayout::TrueStereo);
+        assert_eq!(routes, [(0, 0, 1), (0, 1, 3), (1, 2, 1), (1, 3, 3)]);
+        let routes = outputs::<Surround32>(IrLayout::Quad);
+        assert_eq!(routes[..4], [(0, 0, 1), (0, 1, 3), (0, 2, 0), (0, 3, 4)]);
+        assert_eq!(routes[7], (1, 7, 4));
+        // Rear responses fold into the front without rear speakers.
+        let routes = outputs::<Stereo32>(IrLayout::Quad);
+        assert_eq!(routes[2..4], [(0, 2, 0), (0, 3, 1)]);
+        assert!(outputs::<Mono32>(IrLayout::Quad).iter().all(|r| r.2 == 0));
+    }
+}
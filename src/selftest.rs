@@ -0,0 +1,212 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Test signal based self-check.
+
+use crate::{
+    chan::{Ch64, Channel},
+    mono::{Mono16, Mono64},
+    ops,
+    stereo::Stereo64,
+    surround::Surround64,
+    Audio, Frame,
+};
+use alloc::vec::Vec;
+use core::{
+    f64::consts::{FRAC_PI_2, PI},
+    fmt,
+};
+
+// Frequency of the test tone, in hertz.
+const FREQ: f64 = 1_000.0;
+// Amplitude of the test tone.
+const AMPLITUDE: f64 = 0.5;
+// Frames to ignore at each end of resampled audio.
+const EDGE: usize = 64;
+
+/// One check done by [`selftest()`](crate::selftest).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SelfTestCheck {
+    /// Name of the check.
+    pub name: &'static str,
+    /// Measured value (an error, or a level in dB).
+    pub value: f64,
+    /// Largest value that passes.
+    pub limit: f64,
+}
+
+impl SelfTestCheck {
+    /// Check if the measured value is within the limit.
+    pub fn passed(&self) -> bool {
+        self.value <= self.limit
+    }
+}
+
+/// Results of [`selftest()`](crate::selftest).
+#[derive(Clone, Debug, PartialEq)]
+pub struct SelfTestReport {
+    /// Every check that was done.
+    pub checks: Vec<SelfTestCheck>,
+}
+
+impl SelfTestReport {
+    /// Check if every check passed.
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(SelfTestCheck::passed)
+    }
+
+    /// Get the checks that failed.
+    pub fn failures(&self) -> impl Iterator<Item = &SelfTestCheck> {
+        self.checks.iter().filter(|check| !check.passed())
+    }
+}
+
+impl fmt::Display for SelfTestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            let status = if check.passed() { "ok" } else { "FAILED" };
+            writeln!(
+                f,
+                "{}: {} ({} <= {})",
+                check.name, status, check.value, check.limit
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Run test signals through conversion, panning and resampling, checking the
+/// level, distortion (THD+N) and channel mapping of the results.
+///
+/// This is useful as a canary in applications after upgrading, and on targets
+/// where floating point behavior differs (such as WASM and ARM).
+///
+/// ```rust
+/// let report = fon::selftest();
+/// assert!(report.passed(), "{}", report);
+/// ```
+pub fn selftest() -> SelfTestReport {
+    let mut checks = Vec::new();
+
+    // Bit depth conversion.
+    let ch16 = Audio::<Mono16>::with_stream(48_000.0, &tone(48_000.0));
+    let back = Audio::<Mono64>::with_stream(48_000.0, &ch16);
+    let (amplitude, thd_n) = measure(&back, 0, 48_000.0);
+    checks.push(SelfTestCheck {
+        name: "16-bit conversion level (dB error)",
+        value: level_error(amplitude),
+        limit: 0.01,
+    });
+    checks.push(SelfTestCheck {
+        name: "16-bit conversion THD+N (dB)",
+        value: thd_n,
+        limit: -80.0,
+    });
+
+    // Speaker layout conversion.
+    let frame: Surround64 = Stereo64::new(0.25, 0.5).convert();
+    let expected = Surround64::new(0.0, 0.25, 0.0, 0.5, 0.0, 0.0);
+    checks.push(SelfTestCheck {
+        name: "Stereo to surround channel mapping (error)",
+        value: max_error(frame, expected),
+        limit: 1e-12,
+    });
+
+    // Panning.
+    let left: Stereo64 = ops::pan_3d(Ch64::new(AMPLITUDE), -FRAC_PI_2, 0.0);
+    let expected = Stereo64::new(AMPLITUDE, 0.0);
+    checks.push(SelfTestCheck {
+        name: "Pan channel mapping (error)",
+        value: max_error(left, expected),
+        limit: 1e-9,
+    });
+    let center: Stereo64 = ops::pan_3d(Ch64::new(AMPLITUDE), 0.0, 0.0);
+    let power: f64 = center.channels().iter().map(|c| c.to_f64().powi(2)).sum();
+    checks.push(SelfTestCheck {
+        name: "Pan center power (error)",
+        value: (power - AMPLITUDE * AMPLITUDE).abs(),
+        limit: 1e-9,
+    });
+
+    // Resampling.
+    let out = Audio::<Mono64>::with_stream(48_000.0, &tone(44_100.0));
+    let (amplitude, thd_n) = measure(&out, EDGE, 48_000.0);
+    checks.push(SelfTestCheck {
+        name: "44.1 to 48 kHz resampling level (dB error)",
+        value: level_error(amplitude),
+        limit: 0.5,
+    });
+    checks.push(SelfTestCheck {
+        name: "44.1 to 48 kHz resampling THD+N (dB)",
+        value: thd_n,
+        limit: -20.0,
+    });
+
+    SelfTestReport { checks }
+}
+
+// One tenth of a second of the test tone.
+fn tone(s_rate: f64) -> Audio<Mono64> {
+    let mut tone = Audio::with_silence(s_rate, (s_rate / 10.0) as usize);
+    for (i, frame) in tone.iter_mut().enumerate() {
+        let value = (2.0 * PI * FREQ * i as f64 / s_rate).sin() * AMPLITUDE;
+        *frame = Mono64::new(value);
+    }
+    tone
+}
+
+// Get the difference between a tone's amplitude and the test tone's, in dB.
+fn level_error(amplitude: f64) -> f64 {
+    (20.0 * (amplitude / AMPLITUDE).log10()).abs()
+}
+
+// Get the largest difference between the channels of two frames.
+fn max_error<F: Frame>(frame: F, expected: F) -> f64 {
+    frame
+        .channels()
+        .iter()
+        .zip(expected.channels())
+        .map(|(a, b)| (a.to_f64() - b.to_f64()).abs())
+        .fold(0.0, f64::max)
+}
+
+// Least squares fit of the test tone to the audio (ignoring `edge` frames at
+// each end), returning the amplitude and the THD+N in dB.
+fn measure(audio: &Audio<Mono64>, edge: usize, s_rate: f64) -> (f64, f64) {
+    let samples: Vec<f64> = audio
+        .iter()
+        .skip(edge)
+        .take(audio.len() - 2 * edge)
+        .map(|f| f.channels()[0].to_f64())
+        .collect();
+    let phase = |i: usize| 2.0 * PI * FREQ * (edge + i) as f64 / s_rate;
+    let (mut ss, mut sc, mut cc, mut ys, mut yc) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for (i, y) in samples.iter().enumerate() {
+        let (s, c) = phase(i).sin_cos();
+        ss += s * s;
+        sc += s * c;
+        cc += c * c;
+        ys += y * s;
+        yc += y * c;
+    }
+    let det = ss * cc - sc * sc;
+    let a = (ys * cc - yc * sc) / det;
+    let b = (yc * ss - ys * sc) / det;
+    let mut residual = 0.0;
+    for (i, y) in samples.iter().enumerate() {
+        let (s, c) = phase(i).sin_cos();
+        let error = y - (a * s + b * c);
+        residual += error * error;
+    }
+    let amplitude = (a * a + b * b).sqrt();
+    let signal = amplitude * amplitude / 2.0;
+    let thd_n = 10.0 * (residual / samples.len() as f64 / signal).log10();
+    (amplitude, thd_n)
+}
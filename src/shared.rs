@@ -0,0 +1,130 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Shared, copy-on-write audio buffers.
+
+use crate::{Audio, AudioSlice, Frame};
+use alloc::{sync::Arc, vec::Vec};
+use core::slice::Iter;
+
+/// Reference counted, immutable `Audio` buffer, that's only copied when it's
+/// mutated while shared (for playing one clip from many voices or threads,
+/// without a copy for each).
+///
+/// ```rust
+/// use fon::{mono::Mono32, Audio, SharedAudio};
+///
+/// let clip = SharedAudio::from(Audio::<Mono32>::with_silence(48_000, 4));
+/// let mut voice = clip.clone();
+/// assert!(voice.ptr_eq(&clip));
+/// voice.make_mut()[0] = Mono32::new(0.5);
+/// assert!(!voice.ptr_eq(&clip));
+/// assert_eq!(clip.get(0), Some(Mono32::new(0.0)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct SharedAudio<F: Frame> {
+    s_rate: f64,
+    // Always at full capacity, so it converts to `Box<[F]>` without copying.
+    frames: Arc<Vec<F>>,
+}
+
+impl<F: Frame> SharedAudio<F> {
+    /// Get the sample rate of the audio.
+    pub fn sample_rate(&self) -> f64 {
+        self.s_rate
+    }
+
+    /// Get the number of frames.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Check if there are no frames.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Get an audio frame.
+    pub fn get(&self, index: usize) -> Option<F> {
+        self.frames.get(index).cloned()
+    }
+
+    /// Get the frames as a slice.
+    pub fn as_slice(&self) -> &[F] {
+        &self.frames
+    }
+
+    /// Returns an iterator over the audio frames.
+    pub fn iter(&self) -> Iter<'_, F> {
+        self.frames.iter()
+    }
+
+    /// Borrow as a read-only view (which can be streamed).
+    pub fn as_audio_slice(&self) -> AudioSlice<'_, F> {
+        AudioSlice::new(self.s_rate, &self.frames)
+    }
+
+    /// Check if two buffers share the same frames.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.frames, &other.frames)
+    }
+
+    /// Get the frames mutably, copying them first if they are shared.
+    pub fn make_mut(&mut self) -> &mut [F] {
+        Arc::make_mut(&mut self.frames).as_mut_slice()
+    }
+
+    /// Convert into an owned `Audio` buffer (copying the frames if they are
+    /// shared).
+    pub fn into_audio(self) -> Audio<F> {
+        let frames = Arc::try_unwrap(self.frames)
+            .unwrap_or_else(|frames| frames.as_ref().clone());
+        Audio::with_frames(self.s_rate, frames)
+    }
+}
+
+impl<F: Frame> From<Audio<F>> for SharedAudio<F> {
+    fn from(audio: Audio<F>) -> Self {
+        let (s_rate, frames) = audio.into_raw_parts();
+        Self {
+            s_rate,
+            frames: Arc::new(frames.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mono::Mono32;
+
+    #[test]
+    fn copy_on_write() {
+        let audio = Audio::<Mono32>::with_f32_buffer(48_000, [0.25, 0.5]);
+        let mut shared = SharedAudio::from(audio);
+        let ptr = shared.as_slice().as_ptr();
+        // Unshared buffers aren't copied.
+        shared.make_mut()[0] = Mono32::new(1.0);
+        assert_eq!(shared.as_slice().as_ptr(), ptr);
+
+        let other = shared.clone();
+        shared.make_mut()[1] = Mono32::new(0.0);
+        assert_eq!(other.get(1), Some(Mono32::new(0.5)));
+        assert_eq!(other.get(0), Some(Mono32::new(1.0)));
+        let copy = Audio::<Mono32>::with_stream(48_000, other.as_audio_slice());
+        assert_eq!(copy.len(), 2);
+        // Unshared buffers aren't copied back either.
+        let ptr = shared.as_slice().as_ptr();
+        let mut audio = shared.into_audio();
+        assert_eq!(audio.as_slice().as_ptr(), ptr);
+        assert_eq!(audio.get(1), Some(Mono32::new(0.0)));
+        assert_eq!(other.clone().into_audio().get(1), Some(Mono32::new(0.5)));
+    }
+}
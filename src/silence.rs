@@ -0,0 +1,111 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Silence detection.
+
+use crate::{chan, Audio, Frame};
+use alloc::vec::Vec;
+use core::ops::Range;
+
+impl<F: Frame> Audio<F> {
+    /// Find the runs of at least `min_len` frames quieter than
+    /// `threshold_dbfs` (in every channel), in order.
+    ///
+    /// ```rust
+    /// use fon::{mono::Mono32, Audio};
+    ///
+    /// let samples = [0.5, 0.0, 0.0, 0.0, 0.5, 0.0, 0.5];
+    /// let audio = Audio::<Mono32>::with_f32_buffer(48_000, samples);
+    /// assert_eq!(audio.detect_silence(-60.0, 2), [1..4]);
+    /// ```
+    pub fn detect_silence(
+        &self,
+        threshold_dbfs: f64,
+        min_len: usize,
+    ) -> Vec<Range<usize>> {
+        let threshold = chan::db_to_gain(threshold_dbfs);
+        let min_len = min_len.max(1);
+        let mut runs = Vec::new();
+        let mut start = None;
+        for (index, frame) in self.iter().enumerate() {
+            match (frame.is_silent(threshold), start) {
+                (true, None) => start = Some(index),
+                (false, Some(run)) => {
+                    if index - run >= min_len {
+                        runs.push(run..index);
+                    }
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(run) = start {
+            if self.len() - run >= min_len {
+                runs.push(run..self.len());
+            }
+        }
+        runs
+    }
+
+    /// Split the audio into the segments between silent runs found with
+    /// [`detect_silence()`](Audio::detect_silence), leaving out the silence.
+    pub fn split_on_silence(
+        &self,
+        threshold_dbfs: f64,
+        min_len: usize,
+    ) -> Vec<Self> {
+        let mut bounds = Vec::new();
+        let mut start = 0;
+        for run in self.detect_silence(threshold_dbfs, min_len) {
+            if run.start > start {
+                bounds.push(start..run.start);
+            }
+            start = run.end;
+        }
+        if start < self.len() {
+            bounds.push(start..self.len());
+        }
+        bounds
+            .into_iter()
+            .map(|range| {
+                let mut audio =
+                    Self::with_capacity(self.sample_rate(), range.len());
+                Extend::extend(
+                    &mut audio,
+                    self.iter().skip(range.start).take(range.len()).cloned(),
+                );
+                audio
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mono::Mono32;
+
+    #[test]
+    fn split_on_silence() {
+        let samples = [0.0, 0.5, 0.0, 0.5, 0.0, 0.0, 0.0, 0.25, 0.0, 0.0];
+        let audio = Audio::<Mono32>::with_f32_buffer(48_000, samples);
+        assert_eq!(audio.detect_silence(-60.0, 1), [0..1, 2..3, 4..7, 8..10]);
+        assert_eq!(audio.detect_silence(-60.0, 2), [4..7, 8..10]);
+        let segments = audio.split_on_silence(-60.0, 2);
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].len(), 4);
+        assert_eq!(segments[0].get(1), Some(Mono32::new(0.5)));
+        assert_eq!(segments[1].len(), 1);
+        assert_eq!(segments[1].get(0), Some(Mono32::new(0.25)));
+        assert!(Audio::<Mono32>::with_silence(48_000, 10)
+            .split_on_silence(-60.0, 2)
+            .is_empty());
+    }
+}
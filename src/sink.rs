@@ -0,0 +1,32 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! [`Sink`](crate::Sink) adapters.
+
+mod from_fn;
+mod gain;
+mod meter;
+mod mixer;
+mod pan;
+mod ramp;
+mod resample;
+mod tee;
+#[cfg(feature = "std")]
+mod xrun;
+
+pub use from_fn::{from_fn, FnSink};
+pub use gain::GainSink;
+pub use meter::MeterSink;
+pub use mixer::MixerSink;
+pub use pan::PanSink;
+pub use resample::ResampleSink;
+pub use tee::Tee;
+#[cfg(feature = "std")]
+pub use xrun::{XrunSink, XrunStats};
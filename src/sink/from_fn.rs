@@ -0,0 +1,158 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Sinks calling a closure.
+
+use crate::{math, Frame, Resampler, Sink, Stream};
+use alloc::vec::Vec;
+
+/// Create a [`Sink`] that calls a closure with each frame streamed into it,
+/// at `s_rate`.  Each stream is resampled through a buffer of at least `len`
+/// frames (grown to fit longer streams, while `len` frames of an infinite
+/// stream are taken), and then passed on.
+///
+/// ```rust
+/// use fon::{mono::Mono32, sink, Audio, Frame, Sink};
+///
+/// let audio = Audio::<Mono32>::with_f32_buffer(48_000, [0.25, 0.5, 0.75]);
+/// let mut peak = 0.0f32;
+/// let mut sink = sink::from_fn(48_000, 1024, |frame: Mono32| {
+///     peak = peak.max(f32::from(frame.channels()[0]));
+/// });
+/// sink.stream(&audio);
+/// sink.flush();
+/// drop(sink);
+/// assert_eq!(peak, 0.75);
+/// ```
+pub fn from_fn<F, C, R>(s_rate: R, len: usize, f: C) -> FnSink<F, C>
+where
+    F: Frame,
+    C: FnMut(F),
+    R: Into<f64>,
+{
+    FnSink {
+        s_rate: s_rate.into(),
+        frames: vec![F::default(); len],
+        len,
+        resampler: Resampler::default(),
+        f,
+    }
+}
+
+/// Sink calling a closure, created with [`from_fn()`].
+#[allow(missing_debug_implementations)]
+pub struct FnSink<F: Frame, C: FnMut(F)> {
+    s_rate: f64,
+    frames: Vec<F>,
+    len: usize,
+    resampler: Resampler<F>,
+    f: C,
+}
+
+// Sink writing one period into the frames, with the default `Sink` methods.
+struct Period<'a, F: Frame> {
+    s_rate: f64,
+    frames: &'a mut [F],
+    resampler: &'a mut Resampler<F>,
+}
+
+impl<F: Frame> Sink<F> for Period<'_, F> {
+    fn sample_rate(&self) -> f64 {
+        self.s_rate
+    }
+
+    fn resampler(&mut self) -> &mut Resampler<F> {
+        self.resampler
+    }
+
+    fn buffer(&mut self) -> &mut [F] {
+        self.frames
+    }
+}
+
+impl<F: Frame, C: FnMut(F)> Sink<F> for FnSink<F, C> {
+    fn sample_rate(&self) -> f64 {
+        self.s_rate
+    }
+
+    fn resampler(&mut self) -> &mut Resampler<F> {
+        &mut self.resampler
+    }
+
+    fn buffer(&mut self) -> &mut [F] {
+        &mut self.frames
+    }
+
+    // Frames are passed on after each stream, so there's nothing pending.
+    fn rewind(&mut self) {}
+
+    fn flush(&mut self) {
+        if self.resampler.index() % 1.0 > f64::EPSILON {
+            (self.f)(self.resampler.frame());
+        }
+        self.resampler = Resampler::default();
+    }
+
+    fn sink_block(&mut self, frames: &[F]) {
+        for frame in frames {
+            (self.f)(*frame);
+        }
+    }
+
+    fn stream<S: Frame, M: Stream<S>>(&mut self, stream: M) {
+        // Grow the buffer to fit the whole stream.
+        let ratio = stream.sample_rate().map_or(1.0, |sr| self.s_rate / sr);
+        let len = stream.len().map_or(self.len, |len| {
+            let end = self.resampler.index() + ratio * len as f64;
+            math::ceil_usize(end).max(self.len)
+        });
+        if self.frames.len() < len {
+            self.frames.resize(len, F::default());
+        }
+        let mut period = Period {
+            s_rate: self.s_rate,
+            frames: &mut self.frames[..len],
+            resampler: &mut self.resampler,
+        };
+        period.stream(stream);
+        // Pass on the complete frames, keeping the partial one.
+        let done = (self.resampler.index().max(0.0) as usize).min(len);
+        for frame in self.frames[..done].iter() {
+            (self.f)(*frame);
+        }
+        let index = self.resampler.index() - done as f64;
+        self.resampler = Resampler::new(self.resampler.frame(), index);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mono::Mono32, Audio};
+
+    #[test]
+    fn from_fn() {
+        let samples: Vec<f32> = (0..10).map(|i| i as f32 / 10.0).collect();
+        let audio = Audio::<Mono32>::with_f32_buffer(48_000, samples);
+        let tail = Audio::<Mono32>::with_f32_buffer(48_000, [0.8, 0.9]);
+        let mut out = Vec::new();
+        let mut sink = super::from_fn(48_000, 4, |frame| out.push(frame));
+        sink.stream(&audio);
+        assert!(sink.pending_output().is_empty());
+        sink.sink_block(&[Mono32::new(-1.0)]);
+        sink.stream(&tail);
+        sink.flush();
+        drop(sink);
+        let mut expected = audio.iter().copied().collect::<Vec<_>>();
+        expected.push(Mono32::new(-1.0));
+        expected.extend(tail.iter());
+        assert_eq!(out, expected);
+    }
+}
@@ -0,0 +1,130 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Changing the level of audio passing through a sink.
+
+use super::{ramp::Ramp, tee};
+use crate::{
+    chan::{Channel, ClipPolicy},
+    AudioSlice, Frame, Resampler, Sink, Stream,
+};
+use alloc::vec::Vec;
+
+/// Sink adapter that multiplies every channel of the frames by a gain factor
+/// (saturating by default), before forwarding them to a sink.  The gain can ramp between
+/// values to avoid zipper noise.
+///
+/// ```rust
+/// use fon::{mono::Mono32, sink::GainSink, Audio, Sink};
+///
+/// let tone = Audio::<Mono32>::with_frame(48_000, 5, Mono32::new(0.5));
+/// let mut out = Audio::<Mono32>::with_silence(48_000, 5);
+/// let mut sink = GainSink::new(out.sink(..), 0.0);
+/// sink.ramp(1.0, 4).stream(&tone);
+/// sink.flush();
+/// drop(sink);
+/// let expected = [0.0, 0.125, 0.25, 0.375, 0.5];
+/// assert!(out.iter().eq(expected.map(Mono32::new).iter()));
+/// ```
+#[derive(Debug)]
+pub struct GainSink<F: Frame, K: Sink<F>> {
+    sink: K,
+    gain: Ramp,
+    clip: ClipPolicy,
+    frames: Vec<F>,
+}
+
+impl<F: Frame, K: Sink<F>> GainSink<F, K> {
+    /// Wrap a sink, with a gain factor.
+    pub fn new(sink: K, gain: f32) -> Self {
+        Self {
+            sink,
+            gain: Ramp::new(gain.into()),
+            clip: ClipPolicy::Saturate,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Set the gain factor, starting at the next frame.
+    pub fn gain(&mut self, gain: f32) -> &mut Self {
+        self.ramp(gain, 0)
+    }
+
+    /// Ramp the gain factor linearly to `gain`, reaching it `frames` frames
+    /// from now.
+    pub fn ramp(&mut self, gain: f32, frames: usize) -> &mut Self {
+        self.gain.ramp_to(gain.into(), frames);
+        self
+    }
+
+    /// Set how values outside of -1 to 1 after the gain are handled (default:
+    /// [`ClipPolicy::Saturate`]).
+    pub fn clip_policy(&mut self, policy: ClipPolicy) -> &mut Self {
+        self.clip = policy;
+        self
+    }
+
+    /// Get the wrapped sink back.
+    pub fn into_inner(self) -> K {
+        self.sink
+    }
+}
+
+impl<F: Frame, K: Sink<F>> Sink<F> for GainSink<F, K> {
+    fn sample_rate(&self) -> f64 {
+        self.sink.sample_rate()
+    }
+
+    fn resampler(&mut self) -> &mut Resampler<F> {
+        self.sink.resampler()
+    }
+
+    fn buffer(&mut self) -> &mut [F] {
+        self.sink.buffer()
+    }
+
+    fn pending(&mut self) -> &mut [F] {
+        self.sink.pending()
+    }
+
+    fn rewind(&mut self) {
+        self.sink.rewind()
+    }
+
+    fn flush(&mut self) {
+        self.sink.flush()
+    }
+
+    fn sink_block(&mut self, frames: &[F]) {
+        self.frames.clear();
+        self.frames.extend_from_slice(frames);
+        apply(&mut self.frames, &mut self.gain, self.clip);
+        self.sink.sink_block(&self.frames)
+    }
+
+    fn stream<S: Frame, M: Stream<S>>(&mut self, stream: M) {
+        // Only frames the sink accepts move the ramp along.
+        let s_rate =
+            tee::collect_accepted(&mut self.frames, stream, &mut self.sink);
+        apply(&mut self.frames, &mut self.gain, self.clip);
+        self.sink.stream(AudioSlice::new(s_rate, &self.frames));
+    }
+}
+
+// Multiply the frames by the ramping gain.
+fn apply<F: Frame>(frames: &mut [F], gain: &mut Ramp, clip: ClipPolicy) {
+    for frame in frames.iter_mut() {
+        let gain = gain.next();
+        for chan in frame.channels_mut() {
+            let value = chan.to_f64() * gain;
+            *chan = F::Chan::from_f64_clipped(value, clip);
+        }
+    }
+}
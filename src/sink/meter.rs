@@ -0,0 +1,127 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Metering audio passing through a sink.
+
+use super::tee;
+use crate::{AudioSlice, Frame, Meter, Resampler, Sink, Stream};
+use alloc::vec::Vec;
+
+/// Sink adapter that forwards audio to a sink, while measuring its peak and
+/// RMS levels with a [`Meter`] (before the sink resamples it).
+///
+/// ```rust
+/// use fon::{mono::Mono32, sink::MeterSink, Audio, Sink};
+///
+/// let tone = Audio::<Mono32>::with_frame(48_000, 4, Mono32::new(0.5));
+/// let mut out = Audio::<Mono32>::with_silence(44_100, 4);
+/// let mut sink = MeterSink::new(out.sink(..));
+/// sink.stream(&tone);
+/// assert!((sink.meter().peak()[0] + 6.02).abs() < 0.01);
+/// ```
+#[derive(Debug)]
+pub struct MeterSink<F: Frame, K: Sink<F>> {
+    sink: K,
+    meter: Meter<F>,
+    frames: Vec<F>,
+}
+
+impl<F: Frame, K: Sink<F>> MeterSink<F, K> {
+    /// Wrap a sink, with a new meter.
+    pub fn new(sink: K) -> Self {
+        Self {
+            sink,
+            meter: Meter::new(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Get the meter, with the levels of all audio streamed so far.
+    pub fn meter(&self) -> &Meter<F> {
+        &self.meter
+    }
+
+    /// Get the meter mutably (for resetting it).
+    pub fn meter_mut(&mut self) -> &mut Meter<F> {
+        &mut self.meter
+    }
+
+    /// Get the wrapped sink back.
+    pub fn into_inner(self) -> K {
+        self.sink
+    }
+}
+
+impl<F: Frame, K: Sink<F>> Sink<F> for MeterSink<F, K> {
+    fn sample_rate(&self) -> f64 {
+        self.sink.sample_rate()
+    }
+
+    fn resampler(&mut self) -> &mut Resampler<F> {
+        self.sink.resampler()
+    }
+
+    fn buffer(&mut self) -> &mut [F] {
+        self.sink.buffer()
+    }
+
+    fn pending(&mut self) -> &mut [F] {
+        self.sink.pending()
+    }
+
+    fn rewind(&mut self) {
+        self.sink.rewind()
+    }
+
+    fn flush(&mut self) {
+        self.sink.flush()
+    }
+
+    fn sink_block(&mut self, frames: &[F]) {
+        for frame in frames {
+            self.meter.feed(*frame);
+        }
+        self.sink.sink_block(frames)
+    }
+
+    fn stream<S: Frame, M: Stream<S>>(&mut self, stream: M) {
+        let s_rate = self.sink.sample_rate();
+        let seconds = self.sink.buffer().len() as f64 / s_rate;
+        let s_rate = tee::collect(&mut self.frames, stream, seconds, s_rate);
+        for frame in self.frames.iter() {
+            self.meter.feed(*frame);
+        }
+        self.sink.stream(AudioSlice::new(s_rate, &self.frames));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{stereo::Stereo32, Audio};
+
+    #[test]
+    fn meter_sink() {
+        let frames = [0.5, -0.25, -0.5, 0.25];
+        let audio = Audio::<Stereo32>::with_f32_buffer(48_000, frames);
+        let mut out = Audio::<Stereo32>::with_silence(48_000, 2);
+        let mut sink = MeterSink::new(out.sink(..));
+        sink.stream(&audio);
+        assert_eq!(sink.meter().len(), 2);
+        let peak = sink.meter().peak();
+        assert!((peak[0] + 6.02).abs() < 0.01);
+        assert!((peak[1] + 12.04).abs() < 0.01);
+        sink.meter_mut().reset();
+        assert!(sink.meter().is_empty());
+        sink.flush();
+        drop(sink);
+        assert_eq!(out[1], Stereo32::new(-0.5, 0.25));
+    }
+}
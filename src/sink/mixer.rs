@@ -0,0 +1,180 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Mixing many sources into one buffer.
+
+use crate::{
+    chan::{Channel, ClipPolicy},
+    Audio, Frame, Resampler, Sink, Stream,
+};
+
+/// Sink that mixes (sums) each stream into an `Audio` buffer as a separate
+/// source, scaled by a gain.  Sources follow each other, unless an offset to
+/// start the next one at is set.  Each source is resampled if its sample rate
+/// differs.
+///
+/// ```rust
+/// use fon::{mono::Mono32, sink::MixerSink, Audio, Sink};
+///
+/// let kick = Audio::<Mono32>::with_frame(48_000, 2, Mono32::new(0.5));
+/// let mut out = Audio::<Mono32>::with_silence(48_000, 8);
+/// let mut mixer = MixerSink::new(&mut out);
+/// mixer.stream(&kick);
+/// mixer.gain(0.5).offset(4).stream(&kick);
+/// mixer.gain(1.0).offset(5).stream(&kick);
+/// let expected = [0.5, 0.5, 0.0, 0.0, 0.25, 0.75, 0.5, 0.0];
+/// assert!(out.iter().eq(expected.map(Mono32::new).iter()));
+/// ```
+#[derive(Debug)]
+pub struct MixerSink<'a, F: Frame> {
+    audio: &'a mut Audio<F>,
+    scratch: Audio<F>,
+    // Index of the frame the next source starts at.
+    resampler: Resampler<F>,
+    gain: f32,
+    clip: ClipPolicy,
+}
+
+impl<'a, F: Frame> MixerSink<'a, F> {
+    /// Mix sources into an `Audio` buffer, with a gain of 1 and an offset of
+    /// 0.
+    pub fn new(audio: &'a mut Audio<F>) -> Self {
+        Self {
+            scratch: Audio::with_silence(audio.sample_rate(), 0),
+            audio,
+            resampler: Resampler::default(),
+            gain: 1.0,
+            clip: ClipPolicy::Saturate,
+        }
+    }
+
+    /// Set the gain of the next sources.
+    pub fn gain(&mut self, gain: f32) -> &mut Self {
+        self.gain = gain;
+        self
+    }
+
+    /// Set how values outside of -1 to 1 after the gain are handled for the
+    /// next sources (default: [`ClipPolicy::Saturate`]).  The sum of the
+    /// sources always saturates.
+    pub fn clip_policy(&mut self, policy: ClipPolicy) -> &mut Self {
+        self.clip = policy;
+        self
+    }
+
+    /// Set the frame of the buffer that the next source starts at.
+    pub fn offset(&mut self, offset: usize) -> &mut Self {
+        self.resampler = Resampler::new(F::default(), offset as f64);
+        self
+    }
+
+    // Move the start of the next source `frames` frames on (up to the end of
+    // the buffer).
+    fn advance(&mut self, frames: usize) {
+        let offset = (self.position() + frames).min(self.audio.len());
+        self.resampler = Resampler::new(F::default(), offset as f64);
+    }
+}
+
+// Add frames to a buffer starting at `offset`, scaled by `gain` (clipped by
+// `clip`), dropping frames past the end.
+fn mix<F: Frame, I>(
+    audio: &mut Audio<F>,
+    offset: usize,
+    gain: f32,
+    clip: ClipPolicy,
+    frames: I,
+) where
+    I: IntoIterator<Item = F>,
+{
+    let gain = f64::from(gain);
+    for (dst, mut src) in audio.iter_mut().skip(offset).zip(frames) {
+        if gain != 1.0 {
+            for chan in src.channels_mut() {
+                let value = chan.to_f64() * gain;
+                *chan = F::Chan::from_f64_clipped(value, clip);
+            }
+        }
+        *dst += src;
+    }
+}
+
+impl<F: Frame> Sink<F> for MixerSink<'_, F> {
+    fn sample_rate(&self) -> f64 {
+        self.audio.sample_rate()
+    }
+
+    fn resampler(&mut self) -> &mut Resampler<F> {
+        &mut self.resampler
+    }
+
+    fn buffer(&mut self) -> &mut [F] {
+        self.audio.as_slice()
+    }
+
+    // Each source is flushed when it's mixed in.
+    fn flush(&mut self) {}
+
+    fn sink_block(&mut self, frames: &[F]) {
+        let offset = self.position();
+        let (gain, clip) = (self.gain, self.clip);
+        mix(self.audio, offset, gain, clip, frames.iter().cloned());
+        self.advance(frames.len());
+    }
+
+    fn stream<S: Frame, M: Stream<S>>(&mut self, stream: M) {
+        let (offset, len) = (self.position(), self.remaining());
+        self.scratch.truncate(0);
+        self.scratch.resize(len, F::default());
+        let written = {
+            let mut sink = self.scratch.sink(..);
+            sink.stream(stream);
+            sink.flush();
+            sink.position()
+        };
+        let (gain, clip) = (self.gain, self.clip);
+        mix(self.audio, offset, gain, clip, self.scratch.iter().cloned());
+        self.advance(written);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mono::Mono32;
+
+    #[test]
+    fn mixer() {
+        let source = Audio::<Mono32>::with_frame(48_000, 3, Mono32::new(0.25));
+        let quiet = Mono32::new(0.125);
+        let mut out = Audio::<Mono32>::with_frame(48_000, 4, quiet);
+        let mut mixer = MixerSink::new(&mut out);
+        mixer.offset(2).stream(&source);
+        mixer.gain(-0.5).offset(0).stream(&source);
+        assert_eq!((mixer.position(), mixer.remaining()), (3, 1));
+        // Blocks follow the last source.
+        mixer.sink_block(&[Mono32::new(0.5)]);
+        mixer.flush();
+        assert_eq!(mixer.remaining(), 0);
+        let expected = [0.0, 0.0, 0.25, 0.125].map(Mono32::new);
+        assert!(out.iter().eq(expected.iter()));
+    }
+
+    #[test]
+    fn mixer_blocks() {
+        let mut out = Audio::<Mono32>::with_silence(48_000, 5);
+        let mut mixer = MixerSink::new(&mut out);
+        mixer.offset(1).sink_block(&[Mono32::new(0.25); 2]);
+        mixer.sink_block(&[Mono32::new(0.5); 3]);
+        assert_eq!(mixer.position(), 5);
+        let expected = [0.0, 0.25, 0.25, 0.5, 0.5].map(Mono32::new);
+        assert!(out.iter().eq(expected.iter()));
+    }
+}
@@ -0,0 +1,183 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Positioning audio passing through a sink.
+
+use super::{ramp::Ramp, tee};
+use crate::{mono::Mono, ops, AudioSlice, Frame, Resampler, Sink, Stream};
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+
+/// Sink adapter that mixes the frames down to mono, and positions them with
+/// constant power panning (see [`pan_3d()`](crate::ops::pan_3d)) before
+/// forwarding them to a sink.  The pan position goes from -1 (behind, to the
+/// left) through 0 (straight ahead) to 1 (behind, to the right), as with
+/// [`Pan`](crate::ops::Pan), and can ramp between positions.
+///
+/// ```rust
+/// use fon::{mono::Mono32, sink::PanSink, stereo::Stereo32, Audio, Sink};
+///
+/// let voice = Audio::<Mono32>::with_frame(48_000, 4, Mono32::new(0.5));
+/// let mut out = Audio::<Stereo32>::with_silence(48_000, 4);
+/// let mut sink = PanSink::new(out.sink(..), -0.5);
+/// sink.stream(&voice);
+/// sink.flush();
+/// drop(sink);
+/// assert_eq!(out[0], Stereo32::new(0.5, 0.0));
+/// ```
+#[derive(Debug)]
+pub struct PanSink<F: Frame, K: Sink<F>> {
+    sink: K,
+    pan: Ramp,
+    frames: Vec<F>,
+}
+
+impl<F: Frame, K: Sink<F>> PanSink<F, K> {
+    /// Wrap a sink, with a pan position.
+    pub fn new(sink: K, pan: f64) -> Self {
+        Self {
+            sink,
+            pan: Ramp::new(pan),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Set the pan position, starting at the next frame.
+    pub fn pan(&mut self, pan: f64) -> &mut Self {
+        self.ramp(pan, 0)
+    }
+
+    /// Ramp the pan position linearly to `pan`, reaching it `frames` frames
+    /// from now.
+    pub fn ramp(&mut self, pan: f64, frames: usize) -> &mut Self {
+        self.pan.ramp_to(pan, frames);
+        self
+    }
+
+    /// Get the wrapped sink back.
+    pub fn into_inner(self) -> K {
+        self.sink
+    }
+}
+
+impl<F: Frame, K: Sink<F>> Sink<F> for PanSink<F, K> {
+    fn sample_rate(&self) -> f64 {
+        self.sink.sample_rate()
+    }
+
+    fn resampler(&mut self) -> &mut Resampler<F> {
+        self.sink.resampler()
+    }
+
+    fn buffer(&mut self) -> &mut [F] {
+        self.sink.buffer()
+    }
+
+    fn pending(&mut self) -> &mut [F] {
+        self.sink.pending()
+    }
+
+    fn rewind(&mut self) {
+        self.sink.rewind()
+    }
+
+    fn flush(&mut self) {
+        self.sink.flush()
+    }
+
+    fn sink_block(&mut self, frames: &[F]) {
+        self.frames.clear();
+        self.frames.extend_from_slice(frames);
+        apply(&mut self.frames, &mut self.pan);
+        self.sink.sink_block(&self.frames)
+    }
+
+    fn stream<S: Frame, M: Stream<S>>(&mut self, stream: M) {
+        // Only frames the sink accepts move the ramp along.
+        let s_rate =
+            tee::collect_accepted(&mut self.frames, stream, &mut self.sink);
+        apply(&mut self.frames, &mut self.pan);
+        self.sink.stream(AudioSlice::new(s_rate, &self.frames));
+    }
+}
+
+// Pan the frames to the ramping position.
+fn apply<F: Frame>(frames: &mut [F], pan: &mut Ramp) {
+    for frame in frames.iter_mut() {
+        let sample = frame.convert::<Mono<F::Chan>>().channels()[0];
+        *frame = ops::pan_3d(sample, pan.next() * PI, 0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{chan::ClipPolicy, sink::GainSink, stereo::Stereo32, Audio};
+
+    #[test]
+    fn gain_pan() {
+        let voice =
+            Audio::<Stereo32>::with_frame(48_000, 3, Stereo32::new(1.0, 1.0));
+        let mut out = Audio::<Stereo32>::with_silence(48_000, 3);
+        let mut pan = PanSink::new(out.sink(..), 0.5);
+        pan.ramp(-0.5, 2);
+        let mut sink = GainSink::new(pan, 0.5);
+        sink.stream(&voice);
+        sink.flush();
+        drop(sink);
+        let center = 0.5 * core::f32::consts::FRAC_1_SQRT_2;
+        let expected = [(0.0, 0.5), (center, center), (0.5, 0.0)];
+        for (frame, (l, r)) in out.iter().zip(expected) {
+            let [a, b] = [frame.channels()[0], frame.channels()[1]];
+            assert!((f32::from(a) - l).abs() < 1e-6, "{:?}", frame);
+            assert!((f32::from(b) - r).abs() < 1e-6, "{:?}", frame);
+        }
+    }
+
+    #[test]
+    fn ramp_accepted() {
+        // The ramps only move along for the 3 frames that fit.
+        let voice =
+            Audio::<Stereo32>::with_frame(48_000, 5, Stereo32::new(1.0, 1.0));
+        let mut out = Audio::<Stereo32>::with_silence(48_000, 3);
+        let mut pan = PanSink::new(out.sink(..), -0.5);
+        pan.ramp(0.5, 4);
+        let mut sink = GainSink::new(pan, 0.0);
+        sink.ramp(1.0, 4);
+        sink.stream(&voice);
+        sink.rewind();
+        sink.stream(&voice);
+        sink.flush();
+        drop(sink);
+        let expected = [(0.75, 0.25), (1.0, 0.5), (1.0, 0.5)];
+        for (frame, (gain, pan)) in out.iter().zip(expected) {
+            let (r, l) = ((pan + 0.5) * PI * 0.5).sin_cos();
+            let (l, r) = ((gain * l) as f32, (gain * r) as f32);
+            let [a, b] = [frame.channels()[0], frame.channels()[1]];
+            assert!((f32::from(a) - l).abs() < 1e-6, "{:?}", frame);
+            assert!((f32::from(b) - r).abs() < 1e-6, "{:?}", frame);
+        }
+    }
+
+    #[test]
+    fn gain_clip_policy() {
+        let mut voice =
+            Audio::<Stereo32>::with_frame(48_000, 2, Stereo32::new(0.75, 0.75));
+        let mut out = Audio::<Stereo32>::with_silence(48_000, 2);
+        let mut sink = GainSink::new(out.sink(..), 2.0);
+        sink.stream(voice.slice(..1));
+        sink.clip_policy(ClipPolicy::OnConversion)
+            .stream(voice.slice(1..));
+        sink.flush();
+        drop(sink);
+        assert_eq!(out[0], Stereo32::new(1.0, 1.0));
+        assert_eq!(f32::from(out[1].channels()[0]), 1.5);
+    }
+}
@@ -0,0 +1,49 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Parameters ramping between values.
+
+// Value ramping linearly to a target over a number of frames.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Ramp {
+    value: f64,
+    target: f64,
+    remaining: usize,
+}
+
+impl Ramp {
+    // Create a ramp staying at a value.
+    pub(super) fn new(value: f64) -> Self {
+        Self {
+            value,
+            target: value,
+            remaining: 0,
+        }
+    }
+
+    // Ramp to `target`, reaching it at the frame `frames` from now.
+    pub(super) fn ramp_to(&mut self, target: f64, frames: usize) {
+        self.target = target;
+        self.remaining = frames;
+        if frames == 0 {
+            self.value = target;
+        }
+    }
+
+    // Get the value for the next frame.
+    pub(super) fn next(&mut self) -> f64 {
+        let value = self.value;
+        if self.remaining != 0 {
+            self.value += (self.target - value) / self.remaining as f64;
+            self.remaining -= 1;
+        }
+        value
+    }
+}
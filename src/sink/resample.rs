@@ -0,0 +1,111 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Resampling audio written to a sink.
+
+use crate::{AudioSlice, Frame, Resampler, Sink, Stream};
+use core::marker::PhantomData;
+
+/// Sink adapter that accepts audio at an input sample rate, and resamples it
+/// to the sample rate of a sink.
+///
+/// Blocks of frames written with [`sink_block()`](Sink::sink_block), and
+/// streams without a sample rate, are taken to be at the input sample rate
+/// (rather than the sink's, which would change their pitch).  Streams with a
+/// sample rate are resampled from it, as usual.
+///
+/// ```rust
+/// use fon::{mono::Mono32, sink::ResampleSink, Audio, Sink};
+///
+/// let mut out = Audio::<Mono32>::with_silence(48_000, 8);
+/// let mut sink = ResampleSink::new(out.sink(..), 96_000);
+/// assert_eq!(sink.sample_rate(), 96_000.0);
+/// sink.sink_block(&[Mono32::new(0.5); 16]);
+/// assert_eq!(sink.resampler().index(), 8.0);
+/// ```
+#[derive(Debug)]
+pub struct ResampleSink<F: Frame, K: Sink<F>> {
+    sink: K,
+    s_rate: f64,
+    _frame: PhantomData<F>,
+}
+
+impl<F: Frame, K: Sink<F>> ResampleSink<F, K> {
+    /// Wrap a sink, accepting audio at an input sample rate.
+    pub fn new<R: Into<f64>>(sink: K, s_rate: R) -> Self {
+        Self {
+            sink,
+            s_rate: s_rate.into(),
+            _frame: PhantomData,
+        }
+    }
+
+    /// Get the wrapped sink back.
+    pub fn into_inner(self) -> K {
+        self.sink
+    }
+}
+
+impl<F: Frame, K: Sink<F>> Sink<F> for ResampleSink<F, K> {
+    /// Get the input sample rate.
+    fn sample_rate(&self) -> f64 {
+        self.s_rate
+    }
+
+    fn resampler(&mut self) -> &mut Resampler<F> {
+        self.sink.resampler()
+    }
+
+    fn buffer(&mut self) -> &mut [F] {
+        self.sink.buffer()
+    }
+
+    fn pending(&mut self) -> &mut [F] {
+        self.sink.pending()
+    }
+
+    fn rewind(&mut self) {
+        self.sink.rewind()
+    }
+
+    fn flush(&mut self) {
+        self.sink.flush()
+    }
+
+    fn sink_block(&mut self, frames: &[F]) {
+        self.sink.stream(AudioSlice::new(self.s_rate, frames))
+    }
+
+    fn stream<S: Frame, M: Stream<S>>(&mut self, mut stream: M) {
+        if stream.sample_rate().is_none() {
+            stream.set_sample_rate(self.s_rate);
+        }
+        self.sink.stream(stream)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mono::Mono32, Audio};
+
+    #[test]
+    fn resample_sink() {
+        let mut out = Audio::<Mono32>::with_silence(48_000, 8);
+        let mut sink = ResampleSink::new(out.sink(..), 96_000);
+        sink.sink_block(&[Mono32::new(0.5); 8]);
+        sink.sink_block(&[Mono32::new(0.5); 8]);
+        assert_eq!(sink.resampler().index(), 8.0);
+        sink.flush();
+        drop(sink);
+        // No gap where the blocks meet (the first frame is half-weighted).
+        assert!(out.iter().skip(1).all(|frame| *frame == Mono32::new(0.5)));
+    }
+}
@@ -0,0 +1,168 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Fanning out audio to two sinks.
+
+use crate::{math, AudioSlice, Frame, Resampler, Sink, Stream};
+use alloc::vec::Vec;
+
+/// Sink that forwards every frame to two sinks (nest `Tee`s to fan out to
+/// more), such as a file and a meter.  Each sink resamples the audio to its
+/// own sample rate.
+///
+/// ```rust
+/// use fon::{mono::Mono32, sink::Tee, Audio, Sink};
+///
+/// let tone = Audio::<Mono32>::with_frame(48_000, 4, Mono32::new(0.5));
+/// let mut a = Audio::<Mono32>::with_silence(48_000, 4);
+/// let mut b = Audio::<Mono32>::with_silence(48_000, 4);
+/// let mut tee = Tee::new(a.sink(..), b.sink(..));
+/// tee.stream(&tone);
+/// tee.flush();
+/// drop(tee);
+/// assert!(a.iter().eq(tone.iter()));
+/// assert!(b.iter().eq(tone.iter()));
+/// ```
+#[derive(Debug)]
+pub struct Tee<F: Frame, A: Sink<F>, B: Sink<F>> {
+    a: A,
+    b: B,
+    frames: Vec<F>,
+}
+
+impl<F: Frame, A: Sink<F>, B: Sink<F>> Tee<F, A, B> {
+    /// Forward to two sinks.
+    pub fn new(a: A, b: B) -> Self {
+        Self {
+            a,
+            b,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Get the wrapped sinks back.
+    pub fn into_inner(self) -> (A, B) {
+        (self.a, self.b)
+    }
+}
+
+impl<F: Frame, A: Sink<F>, B: Sink<F>> Sink<F> for Tee<F, A, B> {
+    fn sample_rate(&self) -> f64 {
+        self.a.sample_rate()
+    }
+
+    fn resampler(&mut self) -> &mut Resampler<F> {
+        self.a.resampler()
+    }
+
+    fn buffer(&mut self) -> &mut [F] {
+        self.a.buffer()
+    }
+
+    fn pending(&mut self) -> &mut [F] {
+        self.a.pending()
+    }
+
+    fn rewind(&mut self) {
+        self.a.rewind();
+        self.b.rewind();
+    }
+
+    fn flush(&mut self) {
+        self.a.flush();
+        self.b.flush();
+    }
+
+    fn sink_block(&mut self, frames: &[F]) {
+        self.a.sink_block(frames);
+        self.b.sink_block(frames);
+    }
+
+    fn stream<S: Frame, M: Stream<S>>(&mut self, stream: M) {
+        let a = self.a.buffer().len() as f64 / self.a.sample_rate();
+        let b = self.b.buffer().len() as f64 / self.b.sample_rate();
+        let s_rate =
+            collect(&mut self.frames, stream, a.max(b), self.a.sample_rate());
+        self.a.stream(AudioSlice::new(s_rate, &self.frames));
+        self.b.stream(AudioSlice::new(s_rate, &self.frames));
+    }
+}
+
+// Collect the frames of a stream (enough of an infinite stream to fill
+// `seconds` of buffers), returning the sample rate of the stream (or
+// `s_rate` if it has none).
+pub(super) fn collect<F: Frame, S: Frame, M: Stream<S>>(
+    frames: &mut Vec<F>,
+    stream: M,
+    seconds: f64,
+    s_rate: f64,
+) -> f64 {
+    let s_rate = stream.sample_rate().unwrap_or(s_rate);
+    let len = stream
+        .len()
+        .unwrap_or_else(|| math::ceil_usize(seconds * s_rate) + 1);
+    frames.clear();
+    frames.extend(stream.into_iter().take(len).map(|f| f.convert::<F>()));
+    s_rate
+}
+
+// Collect only the frames of a stream that `sink` accepts on its next
+// stream (as many as fill its buffer, and pending output for finite streams,
+// after starting its next period if the buffer is full), returning the sample
+// rate of the stream (or the sink's if it has none).
+pub(super) fn collect_accepted<F, K, S, M>(
+    frames: &mut Vec<F>,
+    stream: M,
+    sink: &mut K,
+) -> f64
+where
+    F: Frame,
+    K: Sink<F>,
+    S: Frame,
+    M: Stream<S>,
+{
+    let s_rate = stream.sample_rate().unwrap_or_else(|| sink.sample_rate());
+    let buf_len = sink.buffer().len();
+    let pending = sink.pending().len();
+    let mut offset = sink.resampler().index().max(0.0);
+    if pending != 0 && offset >= buf_len as f64 {
+        offset -= buf_len as f64;
+    }
+    let end = match stream.len() {
+        Some(_) => buf_len + pending,
+        None => buf_len,
+    };
+    let ratio = sink.sample_rate() / s_rate;
+    let space = math::ceil_usize((end as f64 - offset).max(0.0) / ratio);
+    let len = stream.len().map_or(space, |len| len.min(space));
+    frames.clear();
+    frames.extend(stream.into_iter().take(len).map(|f| f.convert::<F>()));
+    s_rate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mono::Mono32, Audio, LoopSource};
+
+    #[test]
+    fn tee() {
+        let clip = Audio::<Mono32>::with_f32_buffer(48_000, [0.25, 0.5]);
+        let mut a = Audio::<Mono32>::with_silence(48_000, 5);
+        let mut b = Audio::<Mono32>::with_silence(48_000, 3);
+        let mut tee = Tee::new(a.sink(..), b.sink(..));
+        tee.stream(LoopSource::new(&clip));
+        tee.flush();
+        drop(tee);
+        let expected = [0.25, 0.5, 0.25, 0.5, 0.25].map(Mono32::new);
+        assert!(a.iter().eq(expected.iter()));
+        assert!(b.iter().eq(expected[..3].iter()));
+    }
+}
@@ -0,0 +1,213 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Real-time health checking of sinks.
+
+use crate::{Frame, Resampler, Sink, Stream};
+use core::{marker::PhantomData, time::Duration};
+use std::time::Instant;
+
+/// Real-time health statistics collected by an [`XrunSink`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct XrunStats {
+    /// Number of times audio was streamed into the sink.
+    pub calls: usize,
+    /// Number of times audio arrived too late (the sink would have run out).
+    pub underruns: usize,
+    /// Number of times audio arrived too early (more than the sink's buffer
+    /// could hold ahead of playback).
+    pub overruns: usize,
+    /// Total duration of the audio streamed into the sink.
+    pub audio: Duration,
+    /// Total time spent streaming audio into the sink.
+    pub busy: Duration,
+    /// Longest time spent on one call.
+    pub worst_call: Duration,
+}
+
+impl XrunStats {
+    /// Get the fraction of the real-time budget used streaming (above 1.0
+    /// can't keep up).
+    pub fn load(&self) -> f64 {
+        if self.audio == Duration::ZERO {
+            return 0.0;
+        }
+        self.busy.as_secs_f64() / self.audio.as_secs_f64()
+    }
+}
+
+/// Sink adapter that timestamps each [`stream()`](Sink::stream) and
+/// [`sink_block()`](Sink::sink_block) call, and
+/// checks that audio is produced as fast as the sink's sample rate consumes
+/// it, counting underruns and overruns.
+#[derive(Debug)]
+pub struct XrunSink<F: Frame, K: Sink<F>> {
+    sink: K,
+    tolerance: Duration,
+    start: Option<Instant>,
+    // Seconds of audio produced since `start`.
+    produced: f64,
+    stats: XrunStats,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: Frame, K: Sink<F>> XrunSink<F, K> {
+    /// Wrap a sink, allowing production to drift `tolerance` away from real
+    /// time before counting an underrun or overrun.
+    pub fn new(sink: K, tolerance: Duration) -> Self {
+        Self {
+            sink,
+            tolerance,
+            start: None,
+            produced: 0.0,
+            stats: XrunStats::default(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Get the statistics collected so far.
+    pub fn stats(&self) -> XrunStats {
+        self.stats
+    }
+
+    /// Reset the statistics and the real-time clock.
+    pub fn reset(&mut self) {
+        self.start = None;
+        self.produced = 0.0;
+        self.stats = XrunStats::default();
+    }
+
+    /// Get the wrapped sink back.
+    pub fn into_inner(self) -> K {
+        self.sink
+    }
+
+    // Check production against real time at `now`, before `frames` frames are
+    // streamed.  After an xrun, the clock is resynchronized so that it's only
+    // counted once.
+    fn account(&mut self, now: Instant, frames: usize) {
+        let s_rate = self.sink.sample_rate();
+        let start = *self.start.get_or_insert(now);
+        let elapsed = now.duration_since(start).as_secs_f64();
+        let tolerance = self.tolerance.as_secs_f64();
+        let capacity = self.sink.buffer().len() as f64 / s_rate;
+        if elapsed > self.produced + tolerance {
+            self.stats.underruns += 1;
+            self.produced = elapsed;
+        } else if self.produced > elapsed + capacity + tolerance {
+            self.stats.overruns += 1;
+            self.produced = elapsed + capacity;
+        }
+        let seconds = frames as f64 / s_rate;
+        self.produced += seconds;
+        self.stats.calls += 1;
+        self.stats.audio += Duration::from_secs_f64(seconds);
+    }
+
+    // Add the time spent on a call started at `start`.
+    fn time(&mut self, start: Instant) {
+        let call = start.elapsed();
+        self.stats.busy += call;
+        self.stats.worst_call = self.stats.worst_call.max(call);
+    }
+}
+
+impl<F: Frame, K: Sink<F>> Sink<F> for XrunSink<F, K> {
+    fn sample_rate(&self) -> f64 {
+        self.sink.sample_rate()
+    }
+
+    fn resampler(&mut self) -> &mut Resampler<F> {
+        self.sink.resampler()
+    }
+
+    fn buffer(&mut self) -> &mut [F] {
+        self.sink.buffer()
+    }
+
+    fn pending(&mut self) -> &mut [F] {
+        self.sink.pending()
+    }
+
+    fn rewind(&mut self) {
+        self.sink.rewind()
+    }
+
+    fn flush(&mut self) {
+        self.sink.flush()
+    }
+
+    fn sink_block(&mut self, frames: &[F]) {
+        let now = Instant::now();
+        let capacity = self.sink.buffer().len();
+        self.account(now, frames.len().min(capacity));
+        self.sink.sink_block(frames);
+        self.time(now);
+    }
+
+    fn stream<S: Frame, M: Stream<S>>(&mut self, stream: M) {
+        let now = Instant::now();
+        let capacity = self.sink.buffer().len();
+        let frames = match (stream.len(), stream.sample_rate()) {
+            (Some(len), Some(s_rate)) => {
+                (len as f64 * self.sink.sample_rate() / s_rate) as usize
+            }
+            (Some(len), None) => len,
+            (None, _) => capacity,
+        };
+        self.account(now, frames.min(capacity));
+        self.sink.stream(stream);
+        self.time(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mono::Mono32, Audio};
+
+    #[test]
+    fn xruns() {
+        let mut audio = Audio::<Mono32>::with_silence(1_000, 100);
+        let mut sink = XrunSink::new(audio.sink(..), Duration::from_millis(5));
+        let start = Instant::now();
+        let ms = |ms| start + Duration::from_millis(ms);
+        // 10 ms of audio every 10 ms keeps up.
+        sink.account(ms(0), 10);
+        sink.account(ms(10), 10);
+        sink.account(ms(20), 10);
+        assert_eq!(sink.stats().underruns, 0);
+        // Late by 20 ms.
+        sink.account(ms(50), 10);
+        assert_eq!(sink.stats().underruns, 1);
+        sink.account(ms(60), 10);
+        assert_eq!(sink.stats().underruns, 1);
+        // Way ahead of playback (more than the 100 ms buffer).
+        for _ in 0..12 {
+            sink.account(ms(70), 10);
+        }
+        assert_eq!(sink.stats().overruns, 1);
+        assert_eq!(sink.stats().calls, 17);
+
+        // Streaming real audio.
+        sink.reset();
+        sink.stream(&Audio::<Mono32>::with_silence(1_000, 50));
+        assert_eq!(sink.stats().calls, 1);
+        assert_eq!(sink.stats().audio, Duration::from_millis(50));
+
+        // Blocks are checked the same way.
+        sink.sink_block(&[Mono32::default(); 30]);
+        assert_eq!(sink.stats().calls, 2);
+        assert_eq!(sink.stats().audio, Duration::from_millis(80));
+        sink.sink_block(&[Mono32::default(); 200]);
+        assert_eq!(sink.stats().audio, Duration::from_millis(180));
+        assert!(sink.stats().busy >= sink.stats().worst_call);
+    }
+}
@@ -0,0 +1,342 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{
+    chan::{Ch16, Ch32, Ch64, Ch8},
+    Audio, Frame, Stream,
+};
+use alloc::vec::Vec;
+use core::{
+    iter::Cloned,
+    mem::size_of,
+    ops::RangeBounds,
+    slice::{Iter, IterMut, SliceIndex},
+};
+
+/// Borrowed view of a region of an [`Audio`](crate::Audio) buffer.
+#[derive(Clone, Copy, Debug)]
+pub struct AudioSlice<'a, F: Frame> {
+    s_rate: f64,
+    frames: &'a [F],
+}
+
+/// Mutably borrowed view of a region of an [`Audio`](crate::Audio) buffer.
+#[derive(Debug)]
+pub struct AudioSliceMut<'a, F: Frame> {
+    s_rate: f64,
+    frames: &'a mut [F],
+}
+
+impl<F: Frame> Audio<F> {
+    /// Borrow a region of this `Audio` buffer.  Like
+    /// [`as_slice()`](Audio::as_slice), this may have to re-arrange memory.
+    ///
+    /// # Panics
+    /// If range is out of bounds
+    pub fn slice<R>(&mut self, range: R) -> AudioSlice<'_, F>
+    where
+        R: RangeBounds<usize> + SliceIndex<[F], Output = [F]>,
+    {
+        AudioSlice {
+            s_rate: self.sample_rate(),
+            frames: &self.as_slice()[range],
+        }
+    }
+
+    /// Mutably borrow a region of this `Audio` buffer.  Like
+    /// [`as_slice()`](Audio::as_slice), this may have to re-arrange memory.
+    ///
+    /// # Panics
+    /// If range is out of bounds
+    pub fn slice_mut<R>(&mut self, range: R) -> AudioSliceMut<'_, F>
+    where
+        R: RangeBounds<usize> + SliceIndex<[F], Output = [F]>,
+    {
+        AudioSliceMut {
+            s_rate: self.sample_rate(),
+            frames: &mut self.as_slice()[range],
+        }
+    }
+
+    /// Borrow this `Audio` buffer as two regions, split at frame `index`.
+    /// Like [`as_slice()`](Audio::as_slice), this may have to re-arrange
+    /// memory.
+    ///
+    /// # Panics
+    /// If `index > len`
+    pub fn split_at(
+        &mut self,
+        index: usize,
+    ) -> (AudioSlice<'_, F>, AudioSlice<'_, F>) {
+        let s_rate = self.sample_rate();
+        let (head, tail) = self.as_slice().split_at(index);
+        (
+            AudioSlice {
+                s_rate,
+                frames: head,
+            },
+            AudioSlice {
+                s_rate,
+                frames: tail,
+            },
+        )
+    }
+}
+
+impl<'a, F: Frame> AudioSlice<'a, F> {
+    /// Borrow frames as audio at a sample rate.
+    pub fn new<R: Into<f64>>(s_rate: R, frames: &'a [F]) -> Self {
+        AudioSlice {
+            s_rate: s_rate.into(),
+            frames,
+        }
+    }
+
+    /// Get the sample rate of the audio.
+    pub fn sample_rate(&self) -> f64 {
+        self.s_rate
+    }
+
+    /// Get the number of frames.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Check if there are no frames.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Get an audio frame.
+    pub fn get(&self, index: usize) -> Option<F> {
+        self.frames.get(index).cloned()
+    }
+
+    /// Get the frames as a slice.
+    pub fn as_slice(&self) -> &'a [F] {
+        self.frames
+    }
+
+    /// Returns an iterator over the audio frames.
+    pub fn iter(&self) -> Iter<'a, F> {
+        self.frames.iter()
+    }
+
+    /// Copy the frames into a new `Audio` buffer.
+    pub fn to_audio(&self) -> Audio<F> {
+        Audio::with_frames(self.s_rate, self.frames.to_vec())
+    }
+}
+
+impl<'a, F: Frame> AudioSliceMut<'a, F> {
+    /// Mutably borrow frames as audio at a sample rate.
+    pub fn new<R: Into<f64>>(s_rate: R, frames: &'a mut [F]) -> Self {
+        AudioSliceMut {
+            s_rate: s_rate.into(),
+            frames,
+        }
+    }
+
+    /// Get the sample rate of the audio.
+    pub fn sample_rate(&self) -> f64 {
+        self.s_rate
+    }
+
+    /// Get the number of frames.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Check if there are no frames.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Get an audio frame.
+    pub fn get(&self, index: usize) -> Option<F> {
+        self.frames.get(index).cloned()
+    }
+
+    /// Get a mutable reference to an audio frame.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut F> {
+        self.frames.get_mut(index)
+    }
+
+    /// Get the frames as a slice.
+    pub fn as_slice(&self) -> &[F] {
+        self.frames
+    }
+
+    /// Get the frames as a mutable slice.
+    pub fn as_mut_slice(&mut self) -> &mut [F] {
+        self.frames
+    }
+
+    /// Returns an iterator over the audio frames.
+    pub fn iter(&self) -> Iter<'_, F> {
+        self.frames.iter()
+    }
+
+    /// Returns an iterator that allows modifying each audio frame.
+    pub fn iter_mut(&mut self) -> IterMut<'_, F> {
+        self.frames.iter_mut()
+    }
+
+    /// Borrow as a read-only view.
+    pub fn as_audio_slice(&self) -> AudioSlice<'_, F> {
+        AudioSlice {
+            s_rate: self.s_rate,
+            frames: self.frames,
+        }
+    }
+
+    /// Copy the frames into a new `Audio` buffer.
+    pub fn to_audio(&self) -> Audio<F> {
+        let frames: Vec<F> = self.frames.to_vec();
+        Audio::with_frames(self.s_rate, frames)
+    }
+}
+
+impl<'a, F: Frame> IntoIterator for AudioSlice<'a, F> {
+    type IntoIter = Cloned<Iter<'a, F>>;
+    type Item = F;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.frames.iter().cloned()
+    }
+}
+
+impl<F: Frame> Stream<F> for AudioSlice<'_, F> {
+    fn sample_rate(&self) -> Option<f64> {
+        Some(self.s_rate)
+    }
+
+    fn len(&self) -> Option<usize> {
+        Some(self.frames.len())
+    }
+}
+
+// Zero-copy views of borrowed interleaved sample buffers, for audio that
+// arrives in a callback.
+macro_rules! sample_slices {
+    ($chan:ty, $sample:ty, $with:ident) => {
+        impl<'a, F: Frame<Chan = $chan>> AudioSlice<'a, F> {
+            #[doc = concat!(
+                        "Borrow an interleaved `", stringify!($sample),
+                        "` buffer as audio, without copying."
+                    )]
+            ///
+            /// # Panics
+            /// If the length of the buffer isn't a multiple of the channel
+            /// count.
+            #[allow(unsafe_code)]
+            pub fn $with<R: Into<f64>>(
+                s_rate: R,
+                samples: &'a [$sample],
+            ) -> Self {
+                let len = frame_count::<F, $sample>(samples.len());
+                // Frames are made of channels, which are transparent
+                // wrappers around the sample type.
+                let frames = unsafe {
+                    core::slice::from_raw_parts(samples.as_ptr().cast(), len)
+                };
+                Self::new(s_rate, frames)
+            }
+        }
+
+        impl<'a, F: Frame<Chan = $chan>> AudioSliceMut<'a, F> {
+            #[doc = concat!(
+                        "Mutably borrow an interleaved `", stringify!($sample),
+                        "` buffer as audio, without copying."
+                    )]
+            ///
+            /// # Panics
+            /// If the length of the buffer isn't a multiple of the channel
+            /// count.
+            #[allow(unsafe_code)]
+            pub fn $with<R: Into<f64>>(
+                s_rate: R,
+                samples: &'a mut [$sample],
+            ) -> Self {
+                let len = frame_count::<F, $sample>(samples.len());
+                let frames = unsafe {
+                    core::slice::from_raw_parts_mut(
+                        samples.as_mut_ptr().cast(),
+                        len,
+                    )
+                };
+                Self::new(s_rate, frames)
+            }
+        }
+    };
+}
+
+sample_slices!(Ch8, i8, with_i8_slice);
+sample_slices!(Ch16, i16, with_i16_slice);
+sample_slices!(Ch32, f32, with_f32_slice);
+sample_slices!(Ch64, f64, with_f64_slice);
+
+// Get the number of frames in a buffer of `len` samples.
+fn frame_count<F: Frame, S>(len: usize) -> usize {
+    assert_eq!(size_of::<F>(), size_of::<S>() * F::CHAN_COUNT);
+    assert!(len.is_multiple_of(F::CHAN_COUNT), "Partial frame");
+    len / F::CHAN_COUNT
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mono::Mono32, stereo::Stereo16};
+
+    #[test]
+    fn sample_slices() {
+        let samples = [1, 2, 3, 4];
+        let slice = AudioSlice::<Stereo16>::with_i16_slice(48_000, &samples);
+        assert_eq!(slice.len(), 2);
+        assert_eq!(slice.get(1), Some(Stereo16::new(3, 4)));
+        let audio = Audio::<Mono32>::with_stream(48_000, slice);
+        assert_eq!(audio.len(), 2);
+
+        let mut samples = [0.0; 4];
+        let mut slice =
+            AudioSliceMut::<Mono32>::with_f32_slice(44_100, &mut samples);
+        *slice.get_mut(3).unwrap() = Mono32::new(0.5);
+        assert_eq!(slice.sample_rate(), 44_100.0);
+        assert_eq!(samples, [0.0, 0.0, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn slices() {
+        let samples: Vec<f32> = (0..10).map(|i| i as f32 / 10.0).collect();
+        let mut audio = Audio::<Mono32>::with_f32_buffer(48_000, samples);
+        let slice = audio.slice(2..5);
+        assert_eq!(slice.len(), 3);
+        assert_eq!(slice.get(0), Some(Mono32::new(0.2)));
+        assert_eq!(slice.to_audio().len(), 3);
+        let copy = Audio::<Mono32>::with_stream(48_000, slice);
+        assert_eq!(copy.get(2), Some(Mono32::new(0.4)));
+
+        let mut slice = audio.slice_mut(8..);
+        for frame in slice.iter_mut() {
+            *frame = Mono32::new(1.0);
+        }
+        assert_eq!(slice.as_audio_slice().get(1), Some(Mono32::new(1.0)));
+        assert_eq!(audio.get(7), Some(Mono32::new(0.7)));
+        assert_eq!(audio.get(9), Some(Mono32::new(1.0)));
+
+        let (head, tail) = audio.split_at(4);
+        assert_eq!((head.len(), tail.len()), (4, 6));
+        assert_eq!(tail.get(0), Some(Mono32::new(0.4)));
+        let tail = audio.split_off(7);
+        assert_eq!((audio.len(), tail.len()), (7, 3));
+        assert_eq!(tail.get(0), Some(Mono32::new(0.7)));
+        assert_eq!(tail.sample_rate(), 48_000.0);
+    }
+}
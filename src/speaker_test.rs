@@ -0,0 +1,238 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Speaker test signal generator.
+
+use crate::{
+    chan::{self, Channel},
+    dither::Random,
+    math, Frame,
+};
+use alloc::vec::Vec;
+use core::{f64::consts::PI, marker::PhantomData, time::Duration};
+
+// Number of rows summed for pink noise (Voss-McCartney), each an octave apart.
+const PINK_ROWS: usize = 16;
+// Leak of the brown noise integrator, keeping it from drifting.
+const BROWN_LEAK: f64 = 0.995;
+
+/// Signal played by a [`SpeakerTest`].
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum TestSignal {
+    /// White noise (equal power per hertz).
+    White,
+    /// Pink noise (equal power per octave).
+    #[default]
+    Pink,
+    /// Brown noise (power falling 6 dB per octave).
+    Brown,
+    /// Sine tone at a frequency, in hertz.
+    Tone(f64),
+}
+
+/// Speaker test generator, routing a test signal to one speaker at a time.
+///
+/// Speakers are visited from front to back using the layout's
+/// [`CONFIG`](crate::Frame::CONFIG): the front pair (left, then right), the
+/// center, the remaining pairs, and then the LFE.  For 5.1 surround that's
+/// front left, front right, center, rear left, rear right and LFE.
+///
+/// The level is the RMS level of the signal in dBFS (-20 dBFS by default,
+/// the usual level for calibrating speakers with pink noise).  The iterator
+/// is infinite; use [`take()`](Iterator::take) to limit it.
+///
+/// ```rust
+/// use fon::{surround::Surround32, Audio, SpeakerTest, TestSignal};
+/// use std::time::Duration;
+///
+/// let test = SpeakerTest::<Surround32>::new(48_000, TestSignal::Pink)
+///     .dwell(Duration::from_secs(1));
+/// let frames: Vec<Surround32> = test.take(48_000 * 6).collect();
+/// let audio = Audio::with_frames(48_000, frames);
+/// assert_eq!(audio.len(), 48_000 * 6);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SpeakerTest<F: Frame> {
+    s_rate: f64,
+    signal: TestSignal,
+    rms: f64,
+    dwell: usize,
+    // Channel indices in the order they're tested.
+    order: Vec<usize>,
+    index: usize,
+    seed: u32,
+    // Tone phase, in cycles.
+    phase: f64,
+    pink: [f64; PINK_ROWS],
+    brown: f64,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: Frame> SpeakerTest<F> {
+    /// Create a speaker test generator at a sample rate, switching speakers
+    /// every two seconds.
+    pub fn new<R: Into<f64>>(s_rate: R, signal: TestSignal) -> Self {
+        let s_rate = s_rate.into();
+        let mut seed = 0x9E37_79B9;
+        // Start the noise state at its steady state so the level is right
+        // from the first frame.
+        let mut pink = [0.0; PINK_ROWS];
+        for row in pink.iter_mut() {
+            *row = white(&mut seed);
+        }
+        let brown = white(&mut seed);
+        Self {
+            s_rate,
+            signal,
+            rms: 0.1,
+            dwell: math::duration_frames(s_rate, Duration::from_secs(2)),
+            order: order::<F>(),
+            index: 0,
+            seed,
+            phase: 0.0,
+            pink,
+            brown,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Set the RMS level of the signal, in dBFS.
+    pub fn level(mut self, dbfs: f64) -> Self {
+        self.rms = chan::db_to_gain(dbfs);
+        self
+    }
+
+    /// Set how long the signal plays on each speaker before moving to the
+    /// next one.
+    pub fn dwell(mut self, duration: Duration) -> Self {
+        self.dwell = math::duration_frames(self.s_rate, duration).max(1);
+        self
+    }
+
+    /// Get the channel index of the speaker currently playing.
+    pub fn speaker(&self) -> usize {
+        self.order[(self.index / self.dwell) % self.order.len()]
+    }
+
+    /// Get the position of the speaker currently playing (see
+    /// [`CONFIG`](crate::Frame::CONFIG)), or `None` for the LFE.
+    pub fn position(&self) -> Option<f64> {
+        F::CONFIG.get(self.speaker()).cloned()
+    }
+
+    // Generate the next sample of the signal, with unit RMS level.
+    fn sample(&mut self) -> f64 {
+        match self.signal {
+            TestSignal::White => white(&mut self.seed),
+            TestSignal::Pink => {
+                // Update one row per frame, the first every other frame, the
+                // second every fourth, and so on.
+                let row = (self.index + 1).trailing_zeros() as usize;
+                if let Some(row) = self.pink.get_mut(row) {
+                    *row = white(&mut self.seed);
+                }
+                let sum = self.pink.iter().sum::<f64>() + white(&mut self.seed);
+                sum / ((PINK_ROWS + 1) as f64).sqrt()
+            }
+            TestSignal::Brown => {
+                let gain = (1.0 - BROWN_LEAK * BROWN_LEAK).sqrt();
+                self.brown =
+                    BROWN_LEAK * self.brown + gain * white(&mut self.seed);
+                self.brown
+            }
+            TestSignal::Tone(freq) => {
+                let sample = (2.0 * PI * self.phase).sin() * 2.0_f64.sqrt();
+                self.phase = (self.phase + freq / self.s_rate) % 1.0;
+                sample
+            }
+        }
+    }
+}
+
+impl<F: Frame> Iterator for SpeakerTest<F> {
+    type Item = F;
+
+    fn next(&mut self) -> Option<F> {
+        let speaker = self.speaker();
+        let sample = self.sample() * self.rms;
+        self.index = self.index.wrapping_add(1);
+        let mut frame = F::default();
+        frame.channels_mut()[speaker] = F::Chan::from_f64(sample);
+        Some(frame)
+    }
+}
+
+// White noise with unit RMS level.
+fn white(seed: &mut u32) -> f64 {
+    seed.random() * 12.0_f64.sqrt()
+}
+
+// Order speakers are tested in: the front pair, center, remaining pairs from
+// front to back (left first), and then channels without a position (LFE).
+fn order<F: Frame>() -> Vec<usize> {
+    let speakers = F::CONFIG.len().min(F::CHAN_COUNT);
+    let mut sides: Vec<usize> =
+        (0..speakers).filter(|i| F::CONFIG[*i] != 0.0).collect();
+    sides.sort_by(|a, b| {
+        let (a, b) = (F::CONFIG[*a], F::CONFIG[*b]);
+        (a.abs(), a).partial_cmp(&(b.abs(), b)).unwrap()
+    });
+    let front = sides.len().min(2);
+    let mut order = sides[..front].to_vec();
+    order.extend((0..speakers).filter(|i| F::CONFIG[*i] == 0.0));
+    order.extend(&sides[front..]);
+    order.extend(speakers..F::CHAN_COUNT);
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mono::Mono64, stereo::Stereo64, surround::Surround64};
+
+    #[test]
+    fn speaker_order() {
+        assert_eq!(order::<Mono64>(), [0]);
+        assert_eq!(order::<Stereo64>(), [0, 1]);
+        assert_eq!(order::<Surround64>(), [1, 3, 2, 0, 4, 5]);
+
+        let mut test = SpeakerTest::<Surround64>::new(1_000, TestSignal::White)
+            .dwell(Duration::from_millis(10));
+        assert_eq!(test.position(), Some(-1.0 / 3.0));
+        let frames: Vec<Surround64> = test.by_ref().take(60).collect();
+        assert_eq!(test.speaker(), 1);
+        for (i, frame) in frames.iter().enumerate() {
+            let speaker = order::<Surround64>()[i / 10];
+            for (chan, value) in frame.channels().iter().enumerate() {
+                assert_eq!(chan == speaker, value.to_f64() != 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn calibrated_level() {
+        let signals = [
+            TestSignal::White,
+            TestSignal::Pink,
+            TestSignal::Brown,
+            TestSignal::Tone(1_000.0),
+        ];
+        for signal in signals {
+            let test = SpeakerTest::<Mono64>::new(48_000, signal).level(-20.0);
+            let power = test
+                .take(480_000)
+                .map(|f| f.channels()[0].to_f64().powi(2))
+                .sum::<f64>()
+                / 480_000.0;
+            let dbfs: f64 = 10.0 * power.log10();
+            assert!((dbfs + 20.0).abs() < 0.5, "{:?}: {}", signal, dbfs);
+        }
+    }
+}
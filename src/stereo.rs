@@ -11,11 +11,14 @@
 //! Stereo speaker configuration and types.
 
 use crate::{
-    chan::{Ch16, Ch32, Ch64, Ch8, Channel},
-    Frame,
+    chan::{Ch12, Ch16, Ch20, Ch24, Ch32, Ch64, Ch8, Channel, ClipPolicy},
+    mono::Mono,
+    surround::Surround,
+    Audio, Frame,
 };
 use core::ops::{
-    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign,
+    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub,
+    SubAssign,
 };
 
 /// Stereo audio format (Audio [`Frame`](crate::frame::Frame) containing a left
@@ -35,6 +38,69 @@ impl<C: Channel> Stereo<C> {
         let channels = [C::from(one), C::from(two)];
         Self { channels }
     }
+
+    /// Encode left/right into mid/side, where the first channel becomes the
+    /// mid (`(L + R) / 2`) and the second becomes the side (`(L - R) / 2`).
+    #[inline(always)]
+    pub fn to_mid_side(self) -> Self {
+        let [l, r] = self.channels;
+        let (l, r) = (l.to_f64(), r.to_f64());
+        Self::from_f64_pair((l + r) * 0.5, (l - r) * 0.5)
+    }
+
+    /// Decode mid/side (as encoded by [`to_mid_side()`](Self::to_mid_side))
+    /// back into left/right.  Channels saturate if the result is out of range.
+    #[inline(always)]
+    pub fn from_mid_side(self) -> Self {
+        let [m, s] = self.channels;
+        let (m, s) = (m.to_f64(), s.to_f64());
+        Self::from_f64_pair(m + s, m - s)
+    }
+
+    /// Rotate the left/right vector by `theta` radians, with the 2x2 rotation
+    /// matrix `[[cos, -sin], [sin, cos]]`.  On a mid/side encoded frame this
+    /// corrects the azimuth of the recording.  Channels saturate if the
+    /// result is out of range.
+    #[inline(always)]
+    pub fn rotate_stereo(self, theta: f64) -> Self {
+        let [l, r] = self.channels;
+        let (l, r) = (l.to_f64(), r.to_f64());
+        let (sin, cos) = theta.sin_cos();
+        Self::from_f64_pair(l * cos - r * sin, l * sin + r * cos)
+    }
+
+    #[inline(always)]
+    fn from_f64_pair(one: f64, two: f64) -> Self {
+        let one = C::from_f64_clipped(one, ClipPolicy::Saturate);
+        let two = C::from_f64_clipped(two, ClipPolicy::Saturate);
+        Self::new::<C>(one, two)
+    }
+}
+
+impl<C: Channel> Audio<Stereo<C>> {
+    /// Encode every frame of the `Audio` buffer from left/right into mid/side
+    /// (see [`Stereo::to_mid_side()`]).
+    pub fn to_mid_side(&mut self) {
+        for frame in self.iter_mut() {
+            *frame = frame.to_mid_side();
+        }
+    }
+
+    /// Decode every frame of the `Audio` buffer from mid/side back into
+    /// left/right (see [`Stereo::from_mid_side()`]).
+    pub fn from_mid_side(&mut self) {
+        for frame in self.iter_mut() {
+            *frame = frame.from_mid_side();
+        }
+    }
+
+    /// Rotate every frame of the `Audio` buffer by `theta` radians (see
+    /// [`Stereo::rotate_stereo()`]).
+    pub fn rotate_stereo(&mut self, theta: f64) {
+        for frame in self.iter_mut() {
+            *frame = frame.rotate_stereo(theta);
+        }
+    }
 }
 
 impl<C: Channel> Frame for Stereo<C> {
@@ -106,6 +172,21 @@ impl<C: Channel> Mul for Stereo<C> {
     }
 }
 
+impl<C: Channel> MulAssign<f32> for Stereo<C> {
+    fn mul_assign(&mut self, gain: f32) {
+        crate::frame::gain(self, gain.into());
+    }
+}
+
+impl<C: Channel> Mul<f32> for Stereo<C> {
+    type Output = Stereo<C>;
+
+    fn mul(mut self, gain: f32) -> Self {
+        self *= gain;
+        self
+    }
+}
+
 impl<C: Channel> DivAssign for Stereo<C> {
     fn div_assign(&mut self, other: Self) {
         for (chan, ch) in self.channels.iter_mut().zip(other.channels.iter()) {
@@ -135,6 +216,62 @@ impl<C: Channel> Neg for Stereo<C> {
     }
 }
 
+impl<C: Channel> From<[C; 2]> for Stereo<C> {
+    fn from(channels: [C; 2]) -> Self {
+        Self { channels }
+    }
+}
+
+impl<C: Channel> From<Stereo<C>> for [C; 2] {
+    fn from(frame: Stereo<C>) -> Self {
+        frame.channels
+    }
+}
+
+impl<C: Channel> From<(C, C)> for Stereo<C> {
+    fn from(t: (C, C)) -> Self {
+        Self {
+            channels: [t.0, t.1],
+        }
+    }
+}
+
+impl<C: Channel> From<Stereo<C>> for (C, C) {
+    fn from(c: Stereo<C>) -> Self {
+        (c.channels[0], c.channels[1])
+    }
+}
+
+impl<C: Channel> From<Mono<C>> for Stereo<C> {
+    /// Convert with the same channel mixing as
+    /// [`Frame::convert()`](crate::Frame::convert).
+    fn from(frame: Mono<C>) -> Self {
+        frame.convert()
+    }
+}
+
+impl<C: Channel> From<Surround<C>> for Stereo<C> {
+    /// Convert with the same channel mixing as
+    /// [`Frame::convert()`](crate::Frame::convert).
+    fn from(frame: Surround<C>) -> Self {
+        frame.convert()
+    }
+}
+
+impl<C: Channel> Index<usize> for Stereo<C> {
+    type Output = C;
+
+    fn index(&self, index: usize) -> &C {
+        &self.channels[index]
+    }
+}
+
+impl<C: Channel> IndexMut<usize> for Stereo<C> {
+    fn index_mut(&mut self, index: usize) -> &mut C {
+        &mut self.channels[index]
+    }
+}
+
 impl<C: Channel> Iterator for Stereo<C> {
     type Item = Self;
 
@@ -145,9 +282,59 @@ impl<C: Channel> Iterator for Stereo<C> {
 
 /// Stereo [8-bit PCM](crate::chan::Ch8) format.
 pub type Stereo8 = Stereo<Ch8>;
+/// Stereo [12-bit PCM](crate::chan::Ch12) format.
+pub type Stereo12 = Stereo<Ch12>;
 /// Stereo [16-bit PCM](crate::chan::Ch16) format.
 pub type Stereo16 = Stereo<Ch16>;
+/// Stereo [20-bit PCM](crate::chan::Ch20) format.
+pub type Stereo20 = Stereo<Ch20>;
+/// Stereo [24-bit PCM](crate::chan::Ch24) format.
+pub type Stereo24 = Stereo<Ch24>;
 /// Stereo [32-bit Floating Point](crate::chan::Ch32) format.
 pub type Stereo32 = Stereo<Ch32>;
 /// Stereo [64-bit Floating Point](crate::chan::Ch64) format.
 pub type Stereo64 = Stereo<Ch64>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mid_side() {
+        let frame = Stereo32::new(0.75, 0.25);
+        assert_eq!(frame.to_mid_side(), Stereo32::new(0.5, 0.25));
+        assert_eq!(frame.to_mid_side().from_mid_side(), frame);
+
+        let mut audio =
+            Audio::<Stereo32>::with_frame(48_000, 4, Stereo32::new(1.0, -1.0));
+        audio.to_mid_side();
+        assert_eq!(audio.get(3), Some(Stereo32::new(0.0, 1.0)));
+        audio.from_mid_side();
+        assert_eq!(audio.get(3), Some(Stereo32::new(1.0, -1.0)));
+
+        // Decoding saturates.
+        let frame = Stereo16::new(i16::MAX, i16::MAX).from_mid_side();
+        assert_eq!(frame, Stereo16::new(i16::MAX, 0));
+    }
+
+    #[test]
+    fn rotate_stereo() {
+        use core::f64::consts::FRAC_PI_2;
+        let frame = Stereo64::new(0.5, 0.25).rotate_stereo(FRAC_PI_2);
+        let [l, r] = frame.channels;
+        assert!((l.to_f64() + 0.25).abs() < 1e-12);
+        assert!((r.to_f64() - 0.5).abs() < 1e-12);
+
+        let mut audio = Audio::<Stereo16>::with_frame(
+            48_000,
+            2,
+            Stereo16::new(i16::MAX, 0),
+        );
+        audio.rotate_stereo(-FRAC_PI_2 / 2.0);
+        let [l, r] = audio.get(1).unwrap().channels;
+        assert!((l.to_f64() + r.to_f64()).abs() < 1e-4);
+        // Saturates at full scale.
+        let frame = Stereo16::new(i16::MAX, i16::MAX).rotate_stereo(0.5);
+        assert_eq!(frame.channels[1], Ch16::MAX);
+    }
+}
@@ -9,9 +9,12 @@
 // LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
 
 use crate::{math, ops::Blend, Frame};
+use alloc::boxed::Box;
 use core::{
     iter::{Map, Take, Zip},
     marker::PhantomData,
+    mem,
+    time::Duration,
 };
 
 /// Context for an audio resampler.
@@ -43,6 +46,136 @@ impl<F: Frame> Resampler<F> {
     }
 }
 
+/// Drift-free position of a resampled stream, counting the frames consumed
+/// from the input and produced for the output.
+///
+/// The sample rates are integers, so the relationship between the input and
+/// output domains is an exact ratio, and conversions between them don't
+/// accumulate floating point error however long the stream runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StreamPosition {
+    input_rate: u32,
+    output_rate: u32,
+    input_frames: u64,
+    output_frames: u64,
+}
+
+impl StreamPosition {
+    /// Start tracking a stream resampled from `input_rate` to `output_rate`
+    /// (in hertz).
+    ///
+    /// # Panics
+    /// If either sample rate is 0.
+    pub fn new(input_rate: u32, output_rate: u32) -> Self {
+        assert!(input_rate != 0 && output_rate != 0, "Sample rate is 0");
+        Self {
+            input_rate,
+            output_rate,
+            input_frames: 0,
+            output_frames: 0,
+        }
+    }
+
+    /// Stream audio into a sink, counting the input frames it consumes and
+    /// the output frames it produces (including
+    /// [pending output](Sink::pending_output)).  The stream and the sink
+    /// should be at the input and output sample rates.
+    ///
+    /// ```rust
+    /// use fon::{mono::Mono32, Audio, StreamPosition};
+    ///
+    /// let clip = Audio::<Mono32>::with_silence(96_000, 10);
+    /// let mut out = Audio::<Mono32>::with_silence(48_000, 10);
+    /// let mut position = StreamPosition::new(96_000, 48_000);
+    /// position.stream(&mut out.sink(..), &clip);
+    /// assert_eq!(position.input_frames(), 10);
+    /// assert_eq!(position.output_frames(), 5);
+    /// ```
+    pub fn stream<F, S, K, M>(&mut self, sink: &mut K, stream: M)
+    where
+        F: Frame,
+        S: Frame,
+        K: Sink<F>,
+        M: Stream<S>,
+    {
+        let start = sink.position();
+        sink.stream(CountedStream(stream, &mut self.input_frames, PhantomData));
+        self.output_frames += sink.position().saturating_sub(start) as u64;
+    }
+
+    /// Count `frames` more input frames as consumed.
+    pub fn advance_input(&mut self, frames: u64) {
+        self.input_frames += frames;
+    }
+
+    /// Count `frames` more output frames as produced.
+    pub fn advance_output(&mut self, frames: u64) {
+        self.output_frames += frames;
+    }
+
+    /// Get the total number of input frames consumed.
+    pub fn input_frames(&self) -> u64 {
+        self.input_frames
+    }
+
+    /// Get the total number of output frames produced.
+    pub fn output_frames(&self) -> u64 {
+        self.output_frames
+    }
+
+    /// Get the number of output frames per input frame as a reduced fraction
+    /// (numerator, denominator).
+    pub fn ratio(&self) -> (u64, u64) {
+        let (mut a, mut b) = (self.output_rate, self.input_rate);
+        while b != 0 {
+            let r = a % b;
+            a = b;
+            b = r;
+        }
+        (
+            u64::from(self.output_rate / a),
+            u64::from(self.input_rate / a),
+        )
+    }
+
+    /// Convert an input frame position to the output domain, rounded down.
+    pub fn input_to_output(&self, frame: u64) -> u64 {
+        Self::scale(frame, self.output_rate, self.input_rate)
+    }
+
+    /// Convert an output frame position to the input domain, rounded down.
+    pub fn output_to_input(&self, frame: u64) -> u64 {
+        Self::scale(frame, self.input_rate, self.output_rate)
+    }
+
+    /// Get the number of output frames produced beyond (positive) or short of
+    /// (negative) the exact number for the input frames consumed.
+    pub fn drift(&self) -> i64 {
+        let expected = self.input_to_output(self.input_frames);
+        self.output_frames as i64 - expected as i64
+    }
+
+    /// Get the time of the input position.
+    pub fn input_time(&self) -> Duration {
+        Self::time(self.input_frames, self.input_rate)
+    }
+
+    /// Get the time of the output position.
+    pub fn output_time(&self) -> Duration {
+        Self::time(self.output_frames, self.output_rate)
+    }
+
+    fn scale(frame: u64, numer: u32, denom: u32) -> u64 {
+        (u128::from(frame) * u128::from(numer) / u128::from(denom)) as u64
+    }
+
+    fn time(frames: u64, rate: u32) -> Duration {
+        let rate = u64::from(rate);
+        let nanos = (frames % rate) * 1_000_000_000 / rate;
+        Duration::new(frames / rate, nanos as u32)
+    }
+}
+
 /// Audio sink - a type that consumes audio samples.
 pub trait Sink<F: Frame>: Sized {
     /// Get the (target) sample rate of the [`Sink`](crate::Sink).
@@ -56,17 +189,117 @@ pub trait Sink<F: Frame>: Sized {
     /// [`Sink`](crate::Sink).
     fn buffer(&mut self) -> &mut [F];
 
+    /// Get the storage for resampled frames that don't fit in the audio
+    /// buffer, for sinks that reuse one buffer for each period (like an audio
+    /// device).  Frames that don't fit in either are dropped.
+    ///
+    /// Once the buffer is full, the next [`stream()`](Sink::stream) or
+    /// [`flush()`](Sink::flush) takes it to have been consumed, and writes the
+    /// [pending output](Sink::pending_output) to the start of it.  Empty by
+    /// default, so sinks that are only filled once (like
+    /// [`Audio::sink()`](crate::Audio::sink)) don't keep anything.
+    fn pending(&mut self) -> &mut [F] {
+        &mut []
+    }
+
+    /// Get the resampled frames that didn't fit in the audio buffer, and are
+    /// waiting to be written at the start of the next period.
+    fn pending_output(&mut self) -> &[F] {
+        let len = pending_len(self);
+        &self.pending()[..len]
+    }
+
+    /// Start writing the audio buffer from the start again, writing the
+    /// [pending output](Sink::pending_output) first (streams continue after
+    /// it).
+    ///
+    /// This happens on the next stream or flush once the buffer is full, so
+    /// it's only needed to start a new period early.
+    fn rewind(&mut self) {
+        let buf_len = self.buffer().len();
+        let len = pending_len(self);
+        let written = len.min(buf_len);
+        for i in 0..written {
+            let frame = self.pending()[i];
+            self.buffer()[i] = frame;
+        }
+        self.pending().copy_within(written..len, 0);
+        let offseti = self.resampler().offseti - buf_len as f64;
+        self.resampler().offseti = offseti.max(0.0);
+    }
+
+    /// Get the index in the audio buffer that the next frame will be written
+    /// at (the number of frames written, including
+    /// [pending output](Sink::pending_output)).
+    ///
+    /// ```rust
+    /// use fon::{mono::Mono32, Audio, Sink};
+    ///
+    /// let clip = Audio::<Mono32>::with_silence(48_000, 3);
+    /// let mut out = Audio::<Mono32>::with_silence(48_000, 8);
+    /// let mut sink = out.sink(..);
+    /// sink.stream(&clip);
+    /// assert_eq!(sink.position(), 3);
+    /// assert_eq!(sink.remaining(), 5);
+    /// ```
+    fn position(&mut self) -> usize {
+        self.resampler().offseti.max(0.0) as usize
+    }
+
+    /// Get the number of frames left to fill in the audio buffer (0 when
+    /// it's full).
+    fn remaining(&mut self) -> usize {
+        let position = self.position();
+        self.buffer().len().saturating_sub(position)
+    }
+
     /// Flush the partial sample from the resampler into the audio buffer if
-    /// there is one.
-    fn flush(mut self) {
-        if self.resampler().offseti % 1.0 > f64::EPSILON
-            || self.resampler().offseti % 1.0 < -f64::EPSILON
-        {
+    /// there is one, signaling the end of a stream.  If the buffer is full,
+    /// the [pending output](Sink::pending_output) is written first (see
+    /// [`pending()`](Sink::pending)).
+    ///
+    /// Sinks that buffer audio internally should override this to write it
+    /// out.  Flushing again does nothing until more audio is streamed, and
+    /// streams after a flush continue after the flushed frame.
+    fn flush(&mut self) {
+        let partial = self.resampler().offseti % 1.0 > f64::EPSILON
+            || self.resampler().offseti % 1.0 < -f64::EPSILON;
+        if (partial || !self.pending_output().is_empty()) && is_full(self) {
+            self.rewind();
+        }
+        if partial {
             let i = self.resampler().offseti as usize;
-            self.buffer()[i] = self.resampler().partial;
+            let partial = mem::take(&mut self.resampler().partial);
+            if let Some(dst) = frame_at(self, i) {
+                *dst = partial;
+            }
+            self.resampler().offseti = self.resampler().offseti.ceil();
         }
     }
 
+    /// Write a block of frames at the sink's sample rate, starting at the
+    /// resampler's index and advancing it.  Frames past the end of the buffer
+    /// are kept as [pending output](Sink::pending_output), if there's room.
+    ///
+    /// [`stream()`](Sink::stream) calls this when no resampling is needed, so
+    /// implementations can override it for faster (vectorized) writes.
+    fn sink_block(&mut self, frames: &[F]) {
+        let start = self.position();
+        let buf_len = self.buffer().len();
+        let end = buf_len + self.pending().len();
+        let frames = &frames[..end.saturating_sub(start).min(frames.len())];
+        let fit = buf_len.saturating_sub(start).min(frames.len());
+        if fit != 0 {
+            self.buffer()[start..start + fit].copy_from_slice(&frames[..fit]);
+        }
+        let rest = &frames[fit..];
+        if !rest.is_empty() {
+            let first = (start + fit) - buf_len;
+            self.pending()[first..first + rest.len()].copy_from_slice(rest);
+        }
+        self.resampler().offseti += frames.len() as f64;
+    }
+
     /// [`Stream`](crate::Stream) audio into this audio [`Sink`](crate::Sink).
     #[inline(always)]
     fn stream<S: Frame, M: Stream<S>>(&mut self, mut stream: M) {
@@ -77,22 +310,52 @@ pub trait Sink<F: Frame>: Sized {
             stream.set_sample_rate(self.sample_rate());
             1.0
         };
-        // Add left over audio.
-        let partial = self.resampler().partial;
-        if let Some(dst) = self.buffer().get_mut(0) {
-            *dst += partial;
+        // Start the next period once the buffer is full.
+        if is_full(self) {
+            self.rewind();
         }
         // Calculate Ranges
         let mut srclen = stream.len();
-        let dst_range = if let Some(len) = stream.len() {
-            ..((ratio * len as f64) as usize).min(self.buffer().len())
+        let buf_len = self.buffer().len();
+        let end = buf_len + self.pending().len();
+        let offseti = self.resampler().offseti;
+        let dst_start = (offseti.max(0.0) as usize).min(end);
+        let dst_end = if let Some(len) = stream.len() {
+            ((offseti + ratio * len as f64) as usize).min(end)
         } else {
-            ..self.buffer().len()
+            buf_len.max(dst_start)
         };
         // Clear destination range.
-        for f in self.buffer()[dst_range].iter_mut() {
+        let buffer = dst_start.min(buf_len)..dst_end.min(buf_len);
+        for f in self.buffer()[buffer].iter_mut() {
+            *f = F::default();
+        }
+        let pending =
+            dst_start.saturating_sub(buf_len)..dst_end - buf_len.min(dst_end);
+        for f in self.pending()[pending].iter_mut() {
             *f = F::default();
         }
+        // Add left over audio from the last frame of the last stream.
+        let partial = mem::take(&mut self.resampler().partial);
+        add_at(self, offseti.max(0.0) as usize, partial);
+        // Without resampling, write blocks of frames.
+        if ratio == 1.0 && offseti % 1.0 == 0.0 {
+            let mut block = [F::default(); 64];
+            let count = dst_end.saturating_sub(offseti.max(0.0) as usize);
+            let mut stream_iter = stream.into_iter().take(count);
+            loop {
+                let mut len = 0;
+                for (dst, src) in block.iter_mut().zip(&mut stream_iter) {
+                    *dst = src.convert();
+                    len += 1;
+                }
+                if len == 0 {
+                    break;
+                }
+                self.sink_block(&block[..len]);
+            }
+            return;
+        }
         // Go through each source sample and add to destination.
         let mut stream_iter = stream.into_iter();
         for i in 0.. {
@@ -100,7 +363,7 @@ pub trait Sink<F: Frame>: Sized {
             let j = ratio * i as f64 + self.resampler().offseti;
             let ceil = math::ceil_usize(j);
             let floor = j as usize;
-            if !dst_range.contains(&floor) {
+            if floor >= dst_end {
                 srclen = Some(i);
                 break;
             }
@@ -113,9 +376,9 @@ pub trait Sink<F: Frame>: Sized {
                 break;
             };
             let src: F = src.convert();
-            self.buffer()[dst_range][floor] += src * floor_a;
-            if let Some(buf) = self.buffer()[dst_range].get_mut(ceil) {
-                *buf += src * ceil_a;
+            add_at(self, floor, src * floor_a);
+            if ceil < dst_end {
+                add_at(self, ceil, src * ceil_a);
             } else {
                 self.resampler().partial += src * ceil_a;
             }
@@ -125,6 +388,42 @@ pub trait Sink<F: Frame>: Sized {
     }
 }
 
+// Get the number of frames of pending output.
+fn pending_len<F: Frame, K: Sink<F>>(sink: &mut K) -> usize {
+    let buf_len = sink.buffer().len();
+    let len = sink.position().saturating_sub(buf_len);
+    len.min(sink.pending().len())
+}
+
+// Check if a sink that keeps pending output has filled its buffer.
+fn is_full<F: Frame, K: Sink<F>>(sink: &mut K) -> bool {
+    let buf_len = sink.buffer().len();
+    !sink.pending().is_empty() && sink.position() >= buf_len
+}
+
+// Get a frame of the sink's buffer, or of the pending output if it's past the
+// end of the buffer.
+fn frame_at<F: Frame, K: Sink<F>>(
+    sink: &mut K,
+    index: usize,
+) -> Option<&mut F> {
+    let buf_len = sink.buffer().len();
+    if index < buf_len {
+        sink.buffer().get_mut(index)
+    } else {
+        sink.pending().get_mut(index - buf_len)
+    }
+}
+
+// Add a frame to the sink's buffer, or to the pending output if it's past the
+// end of the buffer (dropping it if there's no room).
+#[inline(always)]
+fn add_at<F: Frame, K: Sink<F>>(sink: &mut K, index: usize, frame: F) {
+    if let Some(dst) = frame_at(sink, index) {
+        *dst += frame;
+    }
+}
+
 /// Audio stream - a type that generates audio samples.
 pub trait Stream<F: Frame>: Sized + IntoIterator<Item = F> {
     /// Get the (source) sample rate of the stream.
@@ -181,6 +480,75 @@ pub trait Stream<F: Frame>: Sized + IntoIterator<Item = F> {
     }
 }
 
+/// Type-erased [`Stream`](crate::Stream), so that streams of different types
+/// can be stored together.  The sample rate and length of the stream are
+/// kept, so the size hint is exact when the length is known.
+#[allow(missing_debug_implementations)]
+pub struct FrameIter<'a, F: Frame> {
+    iter: Box<dyn Iterator<Item = F> + 'a>,
+    s_rate: Option<f64>,
+    len: Option<usize>,
+}
+
+impl<'a, F: Frame> FrameIter<'a, F> {
+    /// Erase the type of a stream.
+    pub fn new<M: Stream<F> + 'a>(stream: M) -> Self
+    where
+        M::IntoIter: 'a,
+    {
+        let (s_rate, len) = (stream.sample_rate(), stream.len());
+        Self {
+            iter: Box::new(stream.into_iter()),
+            s_rate,
+            len,
+        }
+    }
+}
+
+impl<F: Frame> Iterator for FrameIter<'_, F> {
+    type Item = F;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<F> {
+        let frame = self.iter.next();
+        if let Some(len) = &mut self.len {
+            *len = len.saturating_sub(1);
+        }
+        frame
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.len {
+            Some(len) => (len, Some(len)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+impl<F: Frame> Stream<F> for FrameIter<'_, F> {
+    #[inline(always)]
+    fn sample_rate(&self) -> Option<f64> {
+        self.s_rate
+    }
+
+    #[inline(always)]
+    fn len(&self) -> Option<usize> {
+        self.len
+    }
+
+    #[inline(always)]
+    fn set_sample_rate<R: Into<f64>>(&mut self, sr: R) {
+        let sr = sr.into();
+        assert!(
+            self.s_rate.is_none(),
+            "set_sample_rate({}) called on a fixed-sample rate stream!",
+            sr
+        );
+        self.s_rate = Some(sr);
+    }
+}
+
 /// Take stream.
 #[derive(Debug)]
 pub struct TakeStream<F: Frame, S: Stream<F>>(S, usize, PhantomData<F>);
@@ -212,6 +580,49 @@ impl<F: Frame, S: Stream<F>> Stream<F> for TakeStream<F, S> {
     }
 }
 
+// Stream counting the frames taken from it.
+struct CountedStream<'a, F: Frame, S: Stream<F>>(
+    S,
+    &'a mut u64,
+    PhantomData<F>,
+);
+
+impl<'a, F: Frame, S: Stream<F>> IntoIterator for CountedStream<'a, F, S> {
+    type Item = F;
+    type IntoIter = CountedIter<'a, S::IntoIter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        CountedIter(self.0.into_iter(), self.1)
+    }
+}
+
+impl<F: Frame, S: Stream<F>> Stream<F> for CountedStream<'_, F, S> {
+    fn sample_rate(&self) -> Option<f64> {
+        self.0.sample_rate()
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.0.len()
+    }
+
+    fn set_sample_rate<R: Into<f64>>(&mut self, rate: R) {
+        self.0.set_sample_rate(rate)
+    }
+}
+
+// Iterator of a `CountedStream`.
+struct CountedIter<'a, I: Iterator>(I, &'a mut u64);
+
+impl<I: Iterator> Iterator for CountedIter<'_, I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let item = self.0.next()?;
+        *self.1 += 1;
+        Some(item)
+    }
+}
+
 /// Blended stream.
 #[derive(Debug)]
 pub struct BlendStream<F, G, A, B, O>(A, B, PhantomData<(F, G, O)>)
@@ -238,7 +649,7 @@ where
     fn into_iter(self) -> Self::IntoIter {
         self.0
             .into_iter()
-            .zip(self.1.into_iter())
+            .zip(self.1)
             .map(|(a, b)| O::mix_frames(a, b.convert()))
     }
 }
@@ -273,3 +684,203 @@ where
         self.1.set_sample_rate(sr);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mono::Mono32, Audio};
+
+    // A sink that reuses one buffer for each period, like an audio device.
+    struct PeriodSink<const N: usize> {
+        buffer: [Mono32; N],
+        pending: [Mono32; N],
+        resampler: Resampler<Mono32>,
+    }
+
+    impl<const N: usize> PeriodSink<N> {
+        fn new(resampler: Resampler<Mono32>) -> Self {
+            Self {
+                buffer: [Mono32::default(); N],
+                pending: [Mono32::default(); N],
+                resampler,
+            }
+        }
+    }
+
+    impl<const N: usize> Sink<Mono32> for PeriodSink<N> {
+        fn sample_rate(&self) -> f64 {
+            48_000.0
+        }
+
+        fn resampler(&mut self) -> &mut Resampler<Mono32> {
+            &mut self.resampler
+        }
+
+        fn buffer(&mut self) -> &mut [Mono32] {
+            &mut self.buffer
+        }
+
+        fn pending(&mut self) -> &mut [Mono32] {
+            &mut self.pending
+        }
+    }
+
+    #[test]
+    fn pending_output() {
+        let samples: Vec<f32> = (1..=6).map(|i| i as f32 / 8.0).collect();
+        let audio = Audio::<Mono32>::with_f32_buffer(48_000, samples);
+        let mut sink = PeriodSink::<4>::new(Resampler::default());
+        sink.stream(&audio);
+        assert_eq!(sink.buffer[3], Mono32::new(0.5));
+        assert_eq!(
+            sink.pending_output(),
+            &[Mono32::new(0.625), Mono32::new(0.75)]
+        );
+
+        // Next period: the left over frames come first.
+        sink.stream(&audio);
+        assert_eq!(sink.resampler().index(), 8.0);
+        assert_eq!(sink.buffer[0], Mono32::new(0.625));
+        assert_eq!(sink.buffer[1], Mono32::new(0.75));
+        assert_eq!(sink.buffer[2], Mono32::new(0.125));
+        assert_eq!(sink.pending_output().len(), 4);
+    }
+
+    #[test]
+    fn next_period() {
+        let mut sink = PeriodSink::<4>::new(Resampler::default());
+        let clips = [&[0.1, 0.2, 0.3][..], &[0.4, 0.5]];
+        for clip in clips {
+            sink.stream(&Audio::<Mono32>::with_f32_buffer(48_000, clip));
+        }
+        // Frames that don't fit stay pending, without overwriting the buffer.
+        let expected = [0.1, 0.2, 0.3, 0.4].map(Mono32::new);
+        assert_eq!(sink.buffer, expected);
+        assert_eq!(sink.pending_output(), [Mono32::new(0.5)]);
+        // Until the next stream, which writes them first.
+        sink.stream(&Audio::<Mono32>::with_f32_buffer(48_000, [0.9]));
+        assert_eq!(sink.buffer[..2], [0.5, 0.9].map(Mono32::new));
+        assert!(sink.pending_output().is_empty());
+        assert_eq!(sink.position(), 2);
+
+        // Starting the next period early.
+        sink.rewind();
+        assert_eq!(sink.position(), 0);
+        // Frames past the end of the pending storage are dropped.
+        sink.stream(&Audio::<Mono32>::with_silence(48_000, 10));
+        assert_eq!((sink.position(), sink.pending_output().len()), (8, 4));
+    }
+
+    #[test]
+    fn audio_sink_overflow() {
+        let clip = Audio::<Mono32>::with_frame(48_000, 10, Mono32::new(0.5));
+        let mut out = Audio::<Mono32>::with_silence(48_000, 4);
+        let mut sink = out.sink(..);
+        sink.stream(&clip);
+        assert!(sink.pending_output().is_empty());
+        assert_eq!(sink.position(), 4);
+        // A full sink without pending storage doesn't start over.
+        sink.stream(Stream::take(Mono32::new(-0.5), 2));
+        sink.flush();
+        drop(sink);
+        assert!(out.iter().all(|frame| *frame == Mono32::new(0.5)));
+    }
+
+    #[test]
+    fn resampled_streams() {
+        // Each frame at 96 kHz is split over two frames at 48 kHz, so the
+        // last one of a stream is left over for the next.
+        let half = Audio::<Mono32>::with_frame(96_000, 4, Mono32::new(0.5));
+        let mut sink = PeriodSink::<4>::new(Resampler::default());
+        sink.stream(&half);
+        sink.stream(&half);
+        assert_eq!(sink.buffer[0], Mono32::new(0.25));
+        assert_eq!(sink.buffer[2], Mono32::new(0.5));
+        assert_eq!(sink.buffer[3], Mono32::new(0.5));
+    }
+
+    #[test]
+    fn stream_position() {
+        let mut position = StreamPosition::new(44_100, 48_000);
+        assert_eq!(position.ratio(), (160, 147));
+        // Ten hours of 441 frame periods.
+        let periods = 10 * 60 * 60 * 100;
+        for _ in 0..periods {
+            position.advance_input(441);
+            position.advance_output(480);
+        }
+        assert_eq!(position.drift(), 0);
+        assert_eq!(position.output_time(), Duration::from_secs(36_000));
+        assert_eq!(position.input_time(), position.output_time());
+        assert_eq!(position.output_to_input(480), 441);
+        assert_eq!(position.input_to_output(1), 1);
+        position.advance_output(1);
+        assert_eq!(position.drift(), 1);
+    }
+
+    #[test]
+    fn stream_position_sink() {
+        let period = Audio::<Mono32>::with_silence(44_100, 441);
+        let mut sink = PeriodSink::<480>::new(Resampler::default());
+        let mut position = StreamPosition::new(44_100, 48_000);
+        for _ in 0..1_000 {
+            position.stream(&mut sink, &period);
+            sink.rewind();
+        }
+        assert_eq!(position.input_frames(), 441_000);
+        assert!(position.drift().abs() <= 1, "{}", position.drift());
+    }
+
+    #[test]
+    fn frame_iter() {
+        let audio = Audio::<Mono32>::with_silence(48_000, 10);
+        let streams = vec![
+            FrameIter::new(&audio),
+            FrameIter::new(Stream::take(Mono32::new(0.5), 3)),
+        ];
+        assert_eq!(streams[0].sample_rate(), Some(48_000.0));
+        assert_eq!(streams[1].len(), Some(3));
+        let mut iter = streams.into_iter().nth(1).unwrap();
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+        iter.next();
+        assert_eq!(iter.size_hint(), (2, Some(2)));
+        let mut sink = PeriodSink::<4>::new(Resampler::default());
+        sink.stream(iter);
+        assert_eq!(sink.buffer[1], Mono32::new(0.5));
+        assert_eq!(sink.buffer[2], Mono32::default());
+    }
+
+    #[test]
+    fn sink_block() {
+        let mut sink = PeriodSink::<4>::new(Resampler::default());
+        sink.sink_block(&[Mono32::new(0.5); 3]);
+        sink.sink_block(&[Mono32::new(-0.5); 2]);
+        assert_eq!(sink.buffer[2], Mono32::new(0.5));
+        assert_eq!(sink.buffer[3], Mono32::new(-0.5));
+        assert_eq!(sink.pending_output(), &[Mono32::new(-0.5)]);
+        assert_eq!(sink.resampler().index(), 5.0);
+        assert_eq!((sink.position(), sink.remaining()), (5, 0));
+    }
+
+    #[test]
+    fn flush() {
+        let mut sink =
+            PeriodSink::<4>::new(Resampler::new(Mono32::new(0.25), 1.5));
+        sink.flush();
+        assert_eq!(sink.buffer[1], Mono32::new(0.25));
+        assert_eq!(sink.resampler().index(), 2.0);
+        // Flushing again does nothing, and streams continue after the frame.
+        sink.buffer[1] = Mono32::new(0.5);
+        sink.flush();
+        sink.stream(Stream::take(Mono32::new(-0.5), 1));
+        assert_eq!(sink.buffer[1], Mono32::new(0.5));
+        assert_eq!(sink.buffer[2], Mono32::new(-0.5));
+
+        // A partial frame past the end of the buffer starts the next period.
+        let mut sink =
+            PeriodSink::<4>::new(Resampler::new(Mono32::new(0.25), 4.5));
+        sink.flush();
+        assert_eq!(sink.buffer[0], Mono32::new(0.25));
+        assert_eq!(sink.resampler().index(), 1.0);
+    }
+}
@@ -11,11 +11,14 @@
 //! Surround Sound 5.1 speaker configuration and types.
 
 use crate::{
-    chan::{Ch16, Ch32, Ch64, Ch8, Channel},
+    chan::{Ch12, Ch16, Ch20, Ch24, Ch32, Ch64, Ch8, Channel},
+    mono::Mono,
+    stereo::Stereo,
     Frame,
 };
 use core::ops::{
-    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign,
+    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub,
+    SubAssign,
 };
 
 /// Surround Sound 5.1 audio format (Audio [`Frame`](crate::frame::Frame)
@@ -120,6 +123,21 @@ impl<C: Channel> Mul for Surround<C> {
     }
 }
 
+impl<C: Channel> MulAssign<f32> for Surround<C> {
+    fn mul_assign(&mut self, gain: f32) {
+        crate::frame::gain(self, gain.into());
+    }
+}
+
+impl<C: Channel> Mul<f32> for Surround<C> {
+    type Output = Surround<C>;
+
+    fn mul(mut self, gain: f32) -> Self {
+        self *= gain;
+        self
+    }
+}
+
 impl<C: Channel> DivAssign for Surround<C> {
     fn div_assign(&mut self, other: Self) {
         for (chan, ch) in self.channels.iter_mut().zip(other.channels.iter()) {
@@ -149,6 +167,63 @@ impl<C: Channel> Neg for Surround<C> {
     }
 }
 
+impl<C: Channel> From<[C; 6]> for Surround<C> {
+    fn from(channels: [C; 6]) -> Self {
+        Self { channels }
+    }
+}
+
+impl<C: Channel> From<Surround<C>> for [C; 6] {
+    fn from(frame: Surround<C>) -> Self {
+        frame.channels
+    }
+}
+
+impl<C: Channel> From<(C, C, C, C, C, C)> for Surround<C> {
+    fn from(t: (C, C, C, C, C, C)) -> Self {
+        Self {
+            channels: [t.0, t.1, t.2, t.3, t.4, t.5],
+        }
+    }
+}
+
+impl<C: Channel> From<Surround<C>> for (C, C, C, C, C, C) {
+    fn from(c: Surround<C>) -> Self {
+        let [a, b, c, d, e, f] = c.channels;
+        (a, b, c, d, e, f)
+    }
+}
+
+impl<C: Channel> From<Mono<C>> for Surround<C> {
+    /// Convert with the same channel mixing as
+    /// [`Frame::convert()`](crate::Frame::convert).
+    fn from(frame: Mono<C>) -> Self {
+        frame.convert()
+    }
+}
+
+impl<C: Channel> From<Stereo<C>> for Surround<C> {
+    /// Convert with the same channel mixing as
+    /// [`Frame::convert()`](crate::Frame::convert).
+    fn from(frame: Stereo<C>) -> Self {
+        frame.convert()
+    }
+}
+
+impl<C: Channel> Index<usize> for Surround<C> {
+    type Output = C;
+
+    fn index(&self, index: usize) -> &C {
+        &self.channels[index]
+    }
+}
+
+impl<C: Channel> IndexMut<usize> for Surround<C> {
+    fn index_mut(&mut self, index: usize) -> &mut C {
+        &mut self.channels[index]
+    }
+}
+
 impl<C: Channel> Iterator for Surround<C> {
     type Item = Self;
 
@@ -159,8 +234,14 @@ impl<C: Channel> Iterator for Surround<C> {
 
 /// 5.1 Surround [8-bit PCM](crate::chan::Ch8) format.
 pub type Surround8 = Surround<Ch8>;
+/// 5.1 Surround [12-bit PCM](crate::chan::Ch12) format.
+pub type Surround12 = Surround<Ch12>;
 /// 5.1 Surround [16-bit PCM](crate::chan::Ch16) format.
 pub type Surround16 = Surround<Ch16>;
+/// 5.1 Surround [20-bit PCM](crate::chan::Ch20) format.
+pub type Surround20 = Surround<Ch20>;
+/// 5.1 Surround [24-bit PCM](crate::chan::Ch24) format.
+pub type Surround24 = Surround<Ch24>;
 /// 5.1 Surround [32-bit Floating Point](crate::chan::Ch32) format.
 pub type Surround32 = Surround<Ch32>;
 /// 5.1 Surround [64-bit Floating Point](crate::chan::Ch64) format.
@@ -0,0 +1,239 @@
+// Fon
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Surround Sound 7.1 speaker configuration and types.
+//!
+//! Conversion to and from [`Surround61`] mixes the back pair into the back
+//! center channel with equal power (and back again), keeping every other
+//! speaker where it is.  Other layouts can be converted with
+//! [`Frame::convert_matrix()`](crate::Frame::convert_matrix).
+
+use crate::{chan::Channel, surround61::Surround61, Frame};
+use core::ops::{
+    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub,
+    SubAssign,
+};
+
+/// Surround Sound 7.1 audio format (Audio [`Frame`](crate::frame::Frame)
+/// containing a back left, side left, front left, center, front right, side
+/// right, back right and lfe [`Channel`](crate::chan::Channel)).
+#[derive(Default, PartialEq, Copy, Clone, Debug)]
+#[repr(transparent)]
+pub struct Surround71<C: Channel> {
+    channels: [C; 8],
+}
+
+impl<C: Channel> Surround71<C> {
+    /// Create an eight-channel Sample.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new<H>(
+        one: H,
+        two: H,
+        three: H,
+        four: H,
+        five: H,
+        six: H,
+        seven: H,
+        eight: H,
+    ) -> Self
+    where
+        C: From<H>,
+    {
+        let channels = [
+            C::from(one),
+            C::from(two),
+            C::from(three),
+            C::from(four),
+            C::from(five),
+            C::from(six),
+            C::from(seven),
+            C::from(eight),
+        ];
+        Self { channels }
+    }
+}
+
+impl<C: Channel> Frame for Surround71<C> {
+    const CONFIG: &'static [f64] = &[
+        -5.0 / 6.0, // Back Left
+        -4.0 / 6.0, // Side Left
+        -2.0 / 6.0, // Front Left
+        0.0 / 6.0,  // Center
+        2.0 / 6.0,  // Front Right
+        4.0 / 6.0,  // Side Right
+        5.0 / 6.0,  // Back Right
+    ];
+
+    type Chan = C;
+
+    fn channels(&self) -> &[Self::Chan] {
+        &self.channels
+    }
+
+    fn channels_mut(&mut self) -> &mut [Self::Chan] {
+        &mut self.channels
+    }
+
+    fn from_channels(ch: &[Self::Chan]) -> Self {
+        Self::new::<C>(ch[0], ch[1], ch[2], ch[3], ch[4], ch[5], ch[6], ch[7])
+    }
+}
+
+impl<C: Channel> AddAssign for Surround71<C> {
+    fn add_assign(&mut self, other: Self) {
+        for (chan, ch) in self.channels.iter_mut().zip(other.channels.iter()) {
+            *chan += *ch;
+        }
+    }
+}
+
+impl<C: Channel> Add for Surround71<C> {
+    type Output = Surround71<C>;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+impl<C: Channel> SubAssign for Surround71<C> {
+    fn sub_assign(&mut self, other: Self) {
+        for (chan, ch) in self.channels.iter_mut().zip(other.channels.iter()) {
+            *chan -= *ch;
+        }
+    }
+}
+
+impl<C: Channel> Sub for Surround71<C> {
+    type Output = Surround71<C>;
+
+    fn sub(mut self, other: Self) -> Self {
+        self -= other;
+        self
+    }
+}
+
+impl<C: Channel> MulAssign for Surround71<C> {
+    fn mul_assign(&mut self, other: Self) {
+        for (chan, ch) in self.channels.iter_mut().zip(other.channels.iter()) {
+            *chan *= *ch;
+        }
+    }
+}
+
+impl<C: Channel> Mul for Surround71<C> {
+    type Output = Surround71<C>;
+
+    fn mul(mut self, other: Self) -> Self {
+        self *= other;
+        self
+    }
+}
+
+impl<C: Channel> MulAssign<f32> for Surround71<C> {
+    fn mul_assign(&mut self, gain: f32) {
+        crate::frame::gain(self, gain.into());
+    }
+}
+
+impl<C: Channel> Mul<f32> for Surround71<C> {
+    type Output = Surround71<C>;
+
+    fn mul(mut self, gain: f32) -> Self {
+        self *= gain;
+        self
+    }
+}
+
+impl<C: Channel> DivAssign for Surround71<C> {
+    fn div_assign(&mut self, other: Self) {
+        for (chan, ch) in self.channels.iter_mut().zip(other.channels.iter()) {
+            *chan /= *ch;
+        }
+    }
+}
+
+impl<C: Channel> Div for Surround71<C> {
+    type Output = Surround71<C>;
+
+    fn div(mut self, other: Self) -> Self {
+        self /= other;
+        self
+    }
+}
+
+impl<C: Channel> Neg for Surround71<C> {
+    type Output = Surround71<C>;
+
+    #[inline(always)]
+    fn neg(mut self) -> Self {
+        for chan in self.channels.iter_mut() {
+            *chan = -*chan;
+        }
+        self
+    }
+}
+
+impl<C: Channel> From<[C; 8]> for Surround71<C> {
+    fn from(channels: [C; 8]) -> Self {
+        Self { channels }
+    }
+}
+
+impl<C: Channel> From<Surround71<C>> for [C; 8] {
+    fn from(frame: Surround71<C>) -> Self {
+        frame.channels
+    }
+}
+
+impl<C: Channel> From<(C, C, C, C, C, C, C, C)> for Surround71<C> {
+    fn from(t: (C, C, C, C, C, C, C, C)) -> Self {
+        Self {
+            channels: [t.0, t.1, t.2, t.3, t.4, t.5, t.6, t.7],
+        }
+    }
+}
+
+impl<C: Channel> From<Surround71<C>> for (C, C, C, C, C, C, C, C) {
+    fn from(c: Surround71<C>) -> Self {
+        let [a, b, c, d, e, f, g, h] = c.channels;
+        (a, b, c, d, e, f, g, h)
+    }
+}
+
+impl<C: Channel> From<Surround61<C>> for Surround71<C> {
+    /// Convert with the same channel mixing as
+    /// [`Frame::convert()`](crate::Frame::convert).
+    fn from(frame: Surround61<C>) -> Self {
+        frame.convert()
+    }
+}
+
+impl<C: Channel> Index<usize> for Surround71<C> {
+    type Output = C;
+
+    fn index(&self, index: usize) -> &C {
+        &self.channels[index]
+    }
+}
+
+impl<C: Channel> IndexMut<usize> for Surround71<C> {
+    fn index_mut(&mut self, index: usize) -> &mut C {
+        &mut self.channels[index]
+    }
+}
+
+impl<C: Channel> Iterator for Surround71<C> {
+    type Item = Self;
+
+    fn next(&mut self) -> Option<Self> {
+        Some(*self)
+    }
+}